@@ -0,0 +1,2 @@
+-- 已发布博客按 (created_at, id) 做游标查询（上一篇/下一篇）
+CREATE INDEX idx_blogs_published_created_at_id ON blogs(published, created_at, id);
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS snippet_slug_histories;
+DROP TABLE IF EXISTS blog_slug_histories;
@@ -0,0 +1,18 @@
+-- 修改 slug 后保留旧 slug，用于把旧链接永久重定向到当前 slug
+
+-- 博客旧slug表
+CREATE TABLE blog_slug_histories (
+    old_slug VARCHAR(191) PRIMARY KEY,
+    blog_id INTEGER NOT NULL REFERENCES blogs(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- 代码片段旧slug表
+CREATE TABLE snippet_slug_histories (
+    old_slug VARCHAR(191) PRIMARY KEY,
+    snippet_id INTEGER NOT NULL REFERENCES snippets(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_blog_slug_histories_blog_id ON blog_slug_histories(blog_id);
+CREATE INDEX idx_snippet_slug_histories_snippet_id ON snippet_slug_histories(snippet_id);
@@ -0,0 +1,13 @@
+-- This file should undo anything in `up.sql`
+DELETE FROM files WHERE original_id IS NOT NULL;
+
+DROP INDEX IF EXISTS idx_files_original_variant;
+DROP INDEX IF EXISTS idx_files_hash;
+CREATE UNIQUE INDEX idx_files_hash ON files(file_hash);
+ALTER TABLE files ADD CONSTRAINT files_file_hash_key UNIQUE (file_hash);
+
+ALTER TABLE files
+    DROP COLUMN height,
+    DROP COLUMN width,
+    DROP COLUMN variant,
+    DROP COLUMN original_id;
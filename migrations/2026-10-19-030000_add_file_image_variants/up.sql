@@ -0,0 +1,13 @@
+-- 图片变体（缩放、WebP/AVIF 转码）关联到原始文件
+ALTER TABLE files
+    ADD COLUMN original_id INTEGER REFERENCES files(id) ON DELETE CASCADE,
+    ADD COLUMN variant VARCHAR(32),
+    ADD COLUMN width INTEGER,
+    ADD COLUMN height INTEGER;
+
+-- 不同尺寸的变体内容可能相同，hash 只对原始文件去重
+ALTER TABLE files DROP CONSTRAINT files_file_hash_key;
+DROP INDEX idx_files_hash;
+CREATE UNIQUE INDEX idx_files_hash ON files(file_hash) WHERE original_id IS NULL;
+
+CREATE UNIQUE INDEX idx_files_original_variant ON files(original_id, variant);
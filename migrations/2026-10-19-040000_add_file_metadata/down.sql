@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE files
+    DROP COLUMN original_filename,
+    DROP COLUMN byte_size,
+    DROP COLUMN mime_type;
@@ -0,0 +1,18 @@
+-- 上传时检测的文件类型、大小和原始文件名
+ALTER TABLE files
+    ADD COLUMN mime_type VARCHAR(127),
+    ADD COLUMN byte_size BIGINT,
+    ADD COLUMN original_filename VARCHAR(255);
+
+-- 已有文件按扩展名补充类型
+UPDATE files SET mime_type = CASE lower(substring(file_path FROM '\.([^./]+)$'))
+    WHEN 'jpg' THEN 'image/jpeg'
+    WHEN 'jpeg' THEN 'image/jpeg'
+    WHEN 'png' THEN 'image/png'
+    WHEN 'gif' THEN 'image/gif'
+    WHEN 'webp' THEN 'image/webp'
+    WHEN 'avif' THEN 'image/avif'
+    WHEN 'pdf' THEN 'application/pdf'
+    WHEN 'mp4' THEN 'video/mp4'
+    ELSE NULL
+END;
@@ -0,0 +1,12 @@
+-- This file should undo anything in `up.sql`
+-- 已回填或新上传的 SHA-256 记录无法还原为 MD5，存在时拒绝回滚，避免删除文件记录
+DO $$
+BEGIN
+    IF EXISTS (SELECT 1 FROM files WHERE hash_algorithm <> 'md5') THEN
+        RAISE EXCEPTION 'files contain SHA-256 hashes that cannot be converted back to MD5';
+    END IF;
+END $$;
+DROP INDEX IF EXISTS idx_files_hash_algorithm;
+ALTER TABLE files DROP COLUMN storage_key;
+ALTER TABLE files DROP COLUMN hash_algorithm;
+ALTER TABLE files ALTER COLUMN file_hash TYPE VARCHAR(32);
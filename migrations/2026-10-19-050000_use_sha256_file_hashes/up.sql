@@ -0,0 +1,11 @@
+-- 文件hash改为 SHA-256，已有的 MD5 hash 由服务启动时重新计算文件内容回填
+ALTER TABLE files ALTER COLUMN file_hash TYPE VARCHAR(64);
+ALTER TABLE files ADD COLUMN hash_algorithm VARCHAR(16) NOT NULL DEFAULT 'md5';
+ALTER TABLE files ALTER COLUMN hash_algorithm SET DEFAULT 'sha256';
+
+-- 存储中的文件名与hash解耦，回填后已有文件的URL保持不变
+ALTER TABLE files ADD COLUMN storage_key VARCHAR(255);
+UPDATE files SET storage_key = substring(file_path FROM '[^/]+$');
+ALTER TABLE files ALTER COLUMN storage_key SET NOT NULL;
+
+CREATE INDEX idx_files_hash_algorithm ON files(hash_algorithm);
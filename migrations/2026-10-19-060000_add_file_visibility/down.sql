@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE files DROP CONSTRAINT IF EXISTS files_visibility_check;
+ALTER TABLE files DROP COLUMN visibility;
@@ -0,0 +1,3 @@
+-- 私有文件不能通过 /static 访问，只能通过签名URL访问；图片变体与原始文件保持一致
+ALTER TABLE files ADD COLUMN visibility VARCHAR(16) NOT NULL DEFAULT 'public';
+ALTER TABLE files ADD CONSTRAINT files_visibility_check CHECK (visibility IN ('public', 'private'));
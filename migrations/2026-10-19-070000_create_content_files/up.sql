@@ -0,0 +1,36 @@
+-- 内容与上传文件的关联，每行只属于一种内容（博客、代码片段、笔记或标签）
+CREATE TABLE content_files (
+    id SERIAL PRIMARY KEY,
+    file_id INTEGER NOT NULL REFERENCES files(id) ON DELETE RESTRICT,
+    role VARCHAR(16) NOT NULL CHECK (role IN ('cover', 'inline', 'icon')),
+    blog_id INTEGER REFERENCES blogs(id) ON DELETE CASCADE,
+    snippet_id INTEGER REFERENCES snippets(id) ON DELETE CASCADE,
+    note_id INTEGER REFERENCES notes(id) ON DELETE CASCADE,
+    tag_id INTEGER REFERENCES tags(id) ON DELETE CASCADE,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    CONSTRAINT content_files_one_owner CHECK (num_nonnulls(blog_id, snippet_id, note_id, tag_id) = 1)
+);
+
+CREATE INDEX idx_content_files_file_id ON content_files(file_id);
+CREATE UNIQUE INDEX idx_content_files_blog ON content_files(blog_id, role, file_id) WHERE blog_id IS NOT NULL;
+CREATE UNIQUE INDEX idx_content_files_snippet ON content_files(snippet_id, role, file_id) WHERE snippet_id IS NOT NULL;
+CREATE UNIQUE INDEX idx_content_files_note ON content_files(note_id, role, file_id) WHERE note_id IS NOT NULL;
+CREATE UNIQUE INDEX idx_content_files_tag ON content_files(tag_id, role, file_id) WHERE tag_id IS NOT NULL;
+
+-- 按存储文件名回填已有内容中引用的文件
+INSERT INTO content_files (file_id, role, blog_id)
+SELECT DISTINCT f.id, 'cover', b.id FROM blogs b JOIN files f
+    ON f.original_id IS NULL AND position(split_part(f.storage_key, '.', 1) IN b.cover) > 0;
+INSERT INTO content_files (file_id, role, blog_id)
+SELECT DISTINCT f.id, 'inline', b.id FROM blogs b JOIN files f
+    ON f.original_id IS NULL AND position(split_part(f.storage_key, '.', 1) IN b.body) > 0;
+INSERT INTO content_files (file_id, role, snippet_id)
+SELECT DISTINCT f.id, 'inline', s.id FROM snippets s JOIN files f
+    ON f.original_id IS NULL AND position(split_part(f.storage_key, '.', 1) IN s.body) > 0;
+INSERT INTO content_files (file_id, role, note_id)
+SELECT DISTINCT f.id, 'inline', n.id FROM notes n JOIN files f
+    ON f.original_id IS NULL AND position(split_part(f.storage_key, '.', 1) IN n.body) > 0;
+INSERT INTO content_files (file_id, role, tag_id)
+SELECT DISTINCT f.id, 'icon', t.id FROM tags t JOIN files f
+    ON f.original_id IS NULL
+    AND position(split_part(f.storage_key, '.', 1) IN concat_ws(' ', t.icon, t.icon_dark)) > 0;
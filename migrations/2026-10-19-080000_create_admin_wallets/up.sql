@@ -0,0 +1,5 @@
+-- 管理员钱包白名单，与配置文件中的 auth.address 合并使用，可通过管理命令增删
+CREATE TABLE admin_wallets (
+    address VARCHAR(64) PRIMARY KEY,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+DROP TRIGGER IF EXISTS set_updated_at ON tags;
+DROP TRIGGER IF EXISTS set_updated_at ON snippets;
+DROP TRIGGER IF EXISTS set_updated_at ON notes;
+DROP TRIGGER IF EXISTS set_updated_at ON blogs;
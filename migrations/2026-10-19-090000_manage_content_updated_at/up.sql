@@ -0,0 +1,5 @@
+-- 内容更新时自动刷新 updated_at，用于 Last-Modified 和条件请求
+SELECT diesel_manage_updated_at('blogs');
+SELECT diesel_manage_updated_at('notes');
+SELECT diesel_manage_updated_at('snippets');
+SELECT diesel_manage_updated_at('tags');
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE tags DROP COLUMN version;
+ALTER TABLE snippets DROP COLUMN version;
+ALTER TABLE notes DROP COLUMN version;
+ALTER TABLE blogs DROP COLUMN version;
@@ -0,0 +1,5 @@
+-- 内容版本号，每次更新加一，用于更新时的乐观并发控制
+ALTER TABLE blogs ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE notes ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE snippets ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE tags ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,2 @@
+-- 按URL中的文件名（存储文件名去掉扩展名）查找原始文件和内容引用
+CREATE INDEX files_storage_stem_idx ON files ((split_part(storage_key, '.', 1))) WHERE original_id IS NULL;
@@ -0,0 +1,6 @@
+docker stop zwj_minio
+docker rm zwj_minio
+docker pull minio/minio
+docker run --rm -p 127.0.0.1:9000:9000 -p 127.0.0.1:9001:9001 -e MINIO_ROOT_USER="minioadmin" -e MINIO_ROOT_PASSWORD="minioadmin" -d --name zwj_minio minio/minio server /data --console-address ":9001"
+# 创建存储桶并允许匿名读取
+docker run --rm --network host --entrypoint sh minio/mc -c "mc alias set local http://127.0.0.1:9000 minioadmin minioadmin && mc mb --ignore-existing local/zwj-blog && mc anonymous set download local/zwj-blog"
@@ -0,0 +1,164 @@
+use actix_web::{http::header::ContentType, HttpRequest, HttpResponse};
+use serde::Serialize;
+
+use crate::api::dto::response::ApiResponse;
+use crate::api::middleware::jwt::is_authenticated;
+use crate::domain::models::cache::{CacheKey, CacheLookup};
+use crate::domain::services::cache::CacheService;
+use crate::metrics::CACHE_REQUESTS_TOTAL;
+
+// 携带有效 JWT 时该请求头跳过缓存直接查询数据库，用于发布后立即确认内容
+pub const CACHE_BYPASS_HEADER: &str = "X-Cache-Bypass";
+// 响应的缓存结果：HIT、MISS 或 BYPASS
+pub const CACHE_STATUS_HEADER: &str = "X-Cache";
+
+// 公开读接口的读穿缓存：先查缓存，未命中时由接口查询后写入
+pub struct ResponseCache<'a> {
+    cache: &'a dyn CacheService,
+    // 跳过缓存时为空
+    lookup: Option<CacheLookup>,
+}
+
+impl<'a> ResponseCache<'a> {
+    pub async fn new(req: &HttpRequest, cache: &'a dyn CacheService, key: CacheKey) -> Self {
+        if req.headers().contains_key(CACHE_BYPASS_HEADER) && is_authenticated(req) {
+            CACHE_REQUESTS_TOTAL
+                .with_label_values(&[key.name(), "bypass"])
+                .inc();
+            return ResponseCache {
+                cache,
+                lookup: None,
+            };
+        }
+        let lookup = cache.lookup(&key).await;
+        ResponseCache {
+            cache,
+            lookup: Some(lookup),
+        }
+    }
+
+    // 命中时返回缓存的响应
+    pub fn cached(&self) -> Option<HttpResponse> {
+        let body = self.lookup.as_ref()?.body.clone()?;
+        Some(json_response(body, "HIT"))
+    }
+
+    // 返回查询结果，未跳过缓存时同时写入缓存
+    pub async fn respond<T: Serialize>(&self, data: T) -> HttpResponse {
+        let body = match serde_json::to_string(&ApiResponse::success(data)) {
+            Ok(body) => body,
+            Err(e) => {
+                return HttpResponse::InternalServerError()
+                    .json(ApiResponse::<()>::error(&e.to_string()))
+            }
+        };
+        match &self.lookup {
+            Some(lookup) => {
+                self.cache.store(lookup, body.clone()).await;
+                json_response(body, "MISS")
+            }
+            None => json_response(body, "BYPASS"),
+        }
+    }
+}
+
+fn json_response(body: String, status: &str) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type(ContentType::json())
+        .insert_header((CACHE_STATUS_HEADER, status))
+        .body(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use actix_web::{body::to_bytes, test::TestRequest};
+    use async_trait::async_trait;
+
+    use super::{ResponseCache, CACHE_BYPASS_HEADER, CACHE_STATUS_HEADER};
+    use crate::api::middleware::jwt::generate_jwt_token;
+    use crate::domain::models::cache::{CacheGroup, CacheKey, CacheLookup};
+    use crate::domain::services::cache::CacheService;
+
+    // 按接口名称保存响应的缓存
+    #[derive(Default)]
+    struct MemoryCache(Mutex<HashMap<String, String>>);
+
+    #[async_trait]
+    impl CacheService for MemoryCache {
+        async fn lookup(&self, key: &CacheKey) -> CacheLookup {
+            CacheLookup {
+                entry: Some(key.name().to_string()),
+                ttl: Duration::from_secs(60),
+                body: self.0.lock().unwrap().get(key.name()).cloned(),
+            }
+        }
+        async fn store(&self, lookup: &CacheLookup, body: String) {
+            let entry = lookup.entry.clone().unwrap();
+            self.0.lock().unwrap().insert(entry, body);
+        }
+        async fn evict(&self, _groups: &[CacheGroup]) {}
+    }
+
+    fn status(response: &actix_web::HttpResponse) -> &str {
+        response
+            .headers()
+            .get(CACHE_STATUS_HEADER)
+            .unwrap()
+            .to_str()
+            .unwrap()
+    }
+
+    #[actix_web::test]
+    async fn miss_then_hit() {
+        let cache = MemoryCache::default();
+        let req = TestRequest::default().to_http_request();
+
+        let response_cache = ResponseCache::new(&req, &cache, CacheKey::Notes).await;
+        assert!(response_cache.cached().is_none());
+        let response = response_cache.respond(vec!["note"]).await;
+        assert_eq!(status(&response), "MISS");
+        let body = to_bytes(response.into_body()).await.unwrap();
+
+        let response_cache = ResponseCache::new(&req, &cache, CacheKey::Notes).await;
+        let response = response_cache.cached().unwrap();
+        assert_eq!(status(&response), "HIT");
+        assert_eq!(to_bytes(response.into_body()).await.unwrap(), body);
+    }
+
+    #[actix_web::test]
+    async fn bypass_requires_valid_jwt() {
+        let cache = MemoryCache::default();
+        cache
+            .0
+            .lock()
+            .unwrap()
+            .insert("notes".to_string(), "cached".to_string());
+
+        // 没有或携带无效 JWT 时忽略跳过缓存的请求头
+        for authorization in [None, Some("Bearer invalid")] {
+            let mut req = TestRequest::default().insert_header((CACHE_BYPASS_HEADER, "1"));
+            if let Some(authorization) = authorization {
+                req = req.insert_header(("Authorization", authorization));
+            }
+            let req = req.to_http_request();
+            let response_cache = ResponseCache::new(&req, &cache, CacheKey::Notes).await;
+            assert_eq!(status(&response_cache.cached().unwrap()), "HIT");
+        }
+
+        // 管理员跳过缓存时查询结果不写入缓存
+        let token = generate_jwt_token().unwrap();
+        let req = TestRequest::default()
+            .insert_header((CACHE_BYPASS_HEADER, "1"))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_http_request();
+        let response_cache = ResponseCache::new(&req, &cache, CacheKey::Notes).await;
+        assert!(response_cache.cached().is_none());
+        let response = response_cache.respond(vec!["fresh"]).await;
+        assert_eq!(status(&response), "BYPASS");
+        assert_eq!(cache.0.lock().unwrap()["notes"], "cached");
+    }
+}
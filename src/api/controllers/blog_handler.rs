@@ -1,10 +1,23 @@
-use actix_web::{web, HttpResponse};
+use actix_web::{http::header::IfMatch, web, HttpMessage, HttpRequest, HttpResponse};
 
+use crate::api::cache::ResponseCache;
 use crate::api::dto::{
-    blog::{CreateBlogDTO, ListBlogsDTO, UpdateBlogDTO},
-    response::ApiResponse,
+    blog::{
+        BlogArchiveQuery, BlogFieldsQuery, CreateBlogDTO, ListBlogsDTO, PublishedBlogDTO,
+        PublishedBlogQuery, UpdateBlogDTO,
+    },
+    response::{error_response, moved_permanently, ApiResponse},
+};
+use crate::api::precondition;
+use crate::domain::{
+    error::ApiError,
+    models::{
+        blog::{BlogFields, UpdateBlog},
+        cache::CacheKey,
+        version::VersionedUpdate,
+    },
+    services::{blog::BlogService, cache::CacheService},
 };
-use crate::domain::{error::ApiError, services::blog::BlogService};
 
 pub async fn create_blog_handler(
     body: web::Json<CreateBlogDTO>,
@@ -13,9 +26,7 @@ pub async fn create_blog_handler(
     let create_blog = body.into_inner().into();
     match blog_service.create(create_blog).await {
         Ok(blog) => Ok(HttpResponse::Ok().json(ApiResponse::success(blog))),
-        Err(e) => {
-            Ok(HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&e.to_string())))
-        }
+        Err(e) => Ok(error_response(&e)),
     }
 }
 
@@ -23,12 +34,22 @@ pub async fn list_blogs_handler(
     body: web::Json<ListBlogsDTO>,
     blog_service: web::Data<dyn BlogService>,
 ) -> Result<HttpResponse, ApiError> {
-    let params = body.into_inner().into();
-    match blog_service.list(params).await {
-        Ok(result) => Ok(HttpResponse::Ok().json(ApiResponse::success(result))),
-        Err(e) => {
-            Ok(HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&e.to_string())))
-        }
+    let body = body.into_inner();
+    let fields = body.fields.unwrap_or_default();
+    let params = body.into();
+    let result = match fields {
+        BlogFields::Summary => blog_service
+            .list_summaries(params)
+            .await
+            .map(|result| HttpResponse::Ok().json(ApiResponse::success(result))),
+        BlogFields::Full => blog_service
+            .list(params)
+            .await
+            .map(|result| HttpResponse::Ok().json(ApiResponse::success(result))),
+    };
+    match result {
+        Ok(response) => Ok(response),
+        Err(e) => Ok(error_response(&e)),
     }
 }
 
@@ -61,10 +82,36 @@ pub async fn get_blog_by_slug_handler(
 }
 
 pub async fn get_published_blogs_handler(
+    req: HttpRequest,
+    query: web::Query<BlogFieldsQuery>,
     blog_service: web::Data<dyn BlogService>,
+    cache_service: web::Data<dyn CacheService>,
 ) -> Result<HttpResponse, ApiError> {
-    match blog_service.get_published().await {
-        Ok(blogs) => Ok(HttpResponse::Ok().json(ApiResponse::success(blogs))),
+    let fields = query.fields.unwrap_or_default();
+    let cache = ResponseCache::new(
+        &req,
+        cache_service.get_ref(),
+        CacheKey::PublishedBlogs {
+            full: fields == BlogFields::Full,
+        },
+    )
+    .await;
+    if let Some(response) = cache.cached() {
+        return Ok(response);
+    }
+
+    let result = match fields {
+        BlogFields::Summary => match blog_service.get_published_summaries().await {
+            Ok(blogs) => Ok(cache.respond(blogs).await),
+            Err(e) => Err(e),
+        },
+        BlogFields::Full => match blog_service.get_published().await {
+            Ok(blogs) => Ok(cache.respond(blogs).await),
+            Err(e) => Err(e),
+        },
+    };
+    match result {
+        Ok(response) => Ok(response),
         Err(e) => {
             Ok(HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&e.to_string())))
         }
@@ -72,15 +119,65 @@ pub async fn get_published_blogs_handler(
 }
 
 pub async fn get_published_blog_by_slug_handler(
+    req: HttpRequest,
     path: web::Path<String>,
+    query: web::Query<PublishedBlogQuery>,
     blog_service: web::Data<dyn BlogService>,
+    cache_service: web::Data<dyn CacheService>,
 ) -> Result<HttpResponse, ApiError> {
     let slug = path.into_inner();
+    let neighbors = query.neighbors.unwrap_or(false);
+    // 只缓存找到博客时的响应，重定向和 404 每次查询
+    let cache = ResponseCache::new(
+        &req,
+        cache_service.get_ref(),
+        CacheKey::PublishedBlog {
+            slug: slug.clone(),
+            neighbors,
+        },
+    )
+    .await;
+    if let Some(response) = cache.cached() {
+        return Ok(response);
+    }
+
     match blog_service.get_published_by_slug(&slug).await {
-        Ok(Some(blog)) => Ok(HttpResponse::Ok().json(ApiResponse::success(blog))),
-        Ok(None) => {
-            Ok(HttpResponse::NotFound().json(ApiResponse::<()>::error("Published blog not found")))
+        Ok(Some(blog)) if neighbors => match blog_service.get_published_neighbors(&blog).await {
+            Ok(neighbors) => Ok(cache
+                .respond(PublishedBlogDTO {
+                    blog,
+                    previous: neighbors.previous,
+                    next: neighbors.next,
+                })
+                .await),
+            Err(e) => Ok(HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(&e.to_string()))),
+        },
+        Ok(Some(blog)) => Ok(cache.respond(blog).await),
+        // 旧 slug 永久重定向到当前 slug
+        Ok(None) => match blog_service.find_published_redirect(&slug).await {
+            Ok(Some(current_slug)) => Ok(moved_permanently(
+                "/api/blogs/published/slug",
+                &current_slug,
+                req.query_string(),
+            )),
+            Ok(None) => Ok(HttpResponse::NotFound()
+                .json(ApiResponse::<()>::error("Published blog not found"))),
+            Err(e) => Ok(HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(&e.to_string()))),
+        },
+        Err(e) => {
+            Ok(HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&e.to_string())))
         }
+    }
+}
+
+pub async fn get_blog_archive_handler(
+    query: web::Query<BlogArchiveQuery>,
+    blog_service: web::Data<dyn BlogService>,
+) -> Result<HttpResponse, ApiError> {
+    match blog_service.get_archive(query.into_inner().tag).await {
+        Ok(archive) => Ok(HttpResponse::Ok().json(ApiResponse::success(archive))),
         Err(e) => {
             Ok(HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&e.to_string())))
         }
@@ -88,18 +185,44 @@ pub async fn get_published_blog_by_slug_handler(
 }
 
 pub async fn update_blog_handler(
+    req: HttpRequest,
     path: web::Path<i32>,
     body: web::Json<UpdateBlogDTO>,
     blog_service: web::Data<dyn BlogService>,
 ) -> Result<HttpResponse, ApiError> {
     let blog_id = path.into_inner();
-    let update_blog = body.into_inner().into();
+    let mut update_blog: UpdateBlog = body.into_inner().into();
+    // If-Match 中的 ETag 对应读取时的内容，一致时以当时的版本作为前置条件
+    if let Some(if_match) = req.get_header::<IfMatch>() {
+        match blog_service.get(blog_id).await {
+            Ok(Some(current)) if precondition::if_match(&if_match, &current) => {
+                update_blog.version = update_blog.version.or(Some(current.version));
+            }
+            Ok(Some(current)) => {
+                return Ok(precondition::conflict("Blog has been modified", current));
+            }
+            Ok(None) => {
+                return Ok(
+                    HttpResponse::NotFound().json(ApiResponse::<()>::error("Blog not found"))
+                );
+            }
+            Err(e) => {
+                return Ok(HttpResponse::InternalServerError()
+                    .json(ApiResponse::<()>::error(&e.to_string())));
+            }
+        }
+    }
     match blog_service.update(blog_id, update_blog).await {
-        Ok(Some(blog)) => Ok(HttpResponse::Ok().json(ApiResponse::success(blog))),
-        Ok(None) => Ok(HttpResponse::NotFound().json(ApiResponse::<()>::error("Blog not found"))),
-        Err(e) => {
-            Ok(HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&e.to_string())))
+        Ok(VersionedUpdate::Updated(blog)) => {
+            Ok(HttpResponse::Ok().json(ApiResponse::success(blog)))
+        }
+        Ok(VersionedUpdate::NotFound) => {
+            Ok(HttpResponse::NotFound().json(ApiResponse::<()>::error("Blog not found")))
+        }
+        Ok(VersionedUpdate::Conflict(current)) => {
+            Ok(precondition::conflict("Blog has been modified", current))
         }
+        Err(e) => Ok(error_response(&e)),
     }
 }
 
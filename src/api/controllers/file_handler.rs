@@ -1,20 +1,502 @@
 
-use actix_web::web;
+use std::future::Future;
+use std::ops::Range;
+
+use actix_files::HttpRange;
+use actix_web::{
+    body::SizedStream,
+    http::header::{self, EntityTag, Header, IfNoneMatch, IfRange},
+    web, HttpRequest, HttpResponse,
+};
+use futures_util::{stream, StreamExt, TryStreamExt};
 use actix_multipart::form::{
         MultipartForm
     };
 
 use crate::{
-    api::dto::{file::UploadForm, response::ApiResponse},
+    api::{
+        dto::{
+            file::{
+                FileVisibilityDTO, ImageVariantQuery, InitUploadDTO, SignUrlDTO, SignedFileQuery,
+                UploadChunkQuery, UploadForm, UploadOptionsQuery,
+            },
+            response::ApiResponse,
+        },
+        middleware::jwt::is_authenticated,
+    },
     domain::{
-        error::ApiError, models::file::Files, services::file::FileService
+        error::{ApiError, CommonError},
+        models::file::{
+            File, FileContent, FileDeletion, FileIntegrityReport, Files, UploadOptions,
+            UploadSession,
+        },
+        repositories::{file::FileQueryParams, repository::ResultPaging, storage::ByteStream},
+        services::file::FileService,
     },
 };
 
+// 保留图片元数据只允许管理员使用
+fn upload_options(
+    req: &HttpRequest,
+    query: &UploadOptionsQuery,
+) -> Result<UploadOptions, ApiError> {
+    let keep_metadata = query.keep_metadata.unwrap_or(false);
+    if keep_metadata && !is_authenticated(req) {
+        return Err(CommonError {
+            message: "保留图片元数据需要管理员权限".to_string(),
+            code: 403,
+        }
+        .into());
+    }
+    Ok(UploadOptions {
+        keep_metadata,
+        visibility: query.visibility.unwrap_or_default(),
+    })
+}
+
 pub async fn upload_file_handler(
+    req: HttpRequest,
+    query: web::Query<UploadOptionsQuery>,
     file_service: web::Data<dyn FileService>,
     MultipartForm(form): MultipartForm<UploadForm>,
 ) -> Result<ApiResponse<Files>, ApiError> {
-    let file_urls = file_service.upload_files(form.files).await?;
+    let options = upload_options(&req, &query)?;
+    let file_urls = file_service.upload_files(form.files, options).await?;
     Ok(ApiResponse::success(file_urls.into()))
 }
+
+// 上传文件按内容hash命名，同一URL的内容不会变化；但文件可能之后被设为私有，
+// 共享缓存最多在 max-age 内继续提供旧的公开响应，因此不使用长期的 immutable 缓存
+const STATIC_CACHE_CONTROL: &str = "public, max-age=3600";
+
+// 提供上传文件访问，/static/{hash}?w=640&fmt=webp 返回图片变体，私有文件返回 404
+pub async fn serve_file_handler(
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<ImageVariantQuery>,
+    file_service: web::Data<dyn FileService>,
+) -> Result<HttpResponse, ApiError> {
+    let name = path.into_inner();
+    match file_service.read_file(&name, query.variant()).await? {
+        Some(content) => {
+            file_response(&req, content.clone(), STATIC_CACHE_CONTROL, |range| {
+                file_service.open_file(&content, range)
+            })
+            .await
+        }
+        None => Ok(HttpResponse::NotFound().json(ApiResponse::not_found("File not found"))),
+    }
+}
+
+// 通过签名URL访问文件（包括私有文件），/private/{name}?expires=...&signature=...
+pub async fn serve_signed_file_handler(
+    req: HttpRequest,
+    path: web::Path<String>,
+    signed: web::Query<SignedFileQuery>,
+    query: web::Query<ImageVariantQuery>,
+    file_service: web::Data<dyn FileService>,
+) -> Result<HttpResponse, ApiError> {
+    let name = path.into_inner();
+    let content = file_service
+        .read_signed_file(&name, signed.expires, &signed.signature, query.variant())
+        .await?;
+    match content {
+        Some(content) => {
+            // 只允许浏览器在签名有效期内缓存
+            let max_age = (signed.expires - chrono::Utc::now().timestamp()).max(0);
+            let cache_control = format!("private, max-age={}", max_age);
+            file_response(&req, content.clone(), &cache_control, |range| {
+                file_service.open_file(&content, range)
+            })
+            .await
+        }
+        None => Ok(HttpResponse::NotFound().json(ApiResponse::not_found("File not found"))),
+    }
+}
+
+// 请求的内容区间
+#[derive(Debug, PartialEq)]
+enum RangeRequest {
+    Full,
+    Partial(Range<u64>),
+    NotSatisfiable,
+}
+
+// If-Range 与当前 ETag 不一致时忽略 Range；多个区间时返回完整内容，不使用 multipart/byteranges
+fn requested_range(req: &HttpRequest, etag: &EntityTag, total: u64) -> RangeRequest {
+    let range = req
+        .headers()
+        .get(header::RANGE)
+        .and_then(|value| value.to_str().ok())
+        .filter(|_| match IfRange::parse(req) {
+            Ok(IfRange::EntityTag(tag)) => tag.strong_eq(etag),
+            Ok(IfRange::Date(_)) => false,
+            Err(_) => true,
+        });
+    let Some(range) = range else {
+        return RangeRequest::Full;
+    };
+    match HttpRange::parse(range, total) {
+        Ok(ranges) => match ranges.as_slice() {
+            [range] => RangeRequest::Partial(range.start..range.start + range.length),
+            [] => RangeRequest::NotSatisfiable,
+            _ => RangeRequest::Full,
+        },
+        Err(_) => RangeRequest::NotSatisfiable,
+    }
+}
+
+// 文件响应：支持 If-None-Match 返回 304，以及视频拖动进度使用的 Range 请求；
+// 内容通过 open 按区间从存储流式读取，不在内存中缓存整个文件
+async fn file_response<F, Fut>(
+    req: &HttpRequest,
+    content: FileContent,
+    cache_control: &str,
+    open: F,
+) -> Result<HttpResponse, ApiError>
+where
+    F: FnOnce(Range<u64>) -> Fut,
+    Fut: Future<Output = Result<Option<ByteStream>, CommonError>>,
+{
+    let etag = EntityTag::new_strong(content.file_hash.clone());
+
+    let not_modified = match IfNoneMatch::parse(req) {
+        Ok(IfNoneMatch::Any) => true,
+        Ok(IfNoneMatch::Items(tags)) => tags.iter().any(|tag| tag.weak_eq(&etag)),
+        Err(_) => false,
+    };
+    if not_modified {
+        return Ok(HttpResponse::NotModified()
+            .insert_header(header::ETag(etag))
+            .insert_header((header::CACHE_CONTROL, cache_control))
+            .finish());
+    }
+
+    let total = content.size;
+    let (mut response, range) = match requested_range(req, &etag, total) {
+        RangeRequest::Full => (HttpResponse::Ok(), 0..total),
+        RangeRequest::Partial(range) => {
+            let mut response = HttpResponse::PartialContent();
+            response.insert_header((
+                header::CONTENT_RANGE,
+                format!("bytes {}-{}/{}", range.start, range.end - 1, total),
+            ));
+            (response, range)
+        }
+        RangeRequest::NotSatisfiable => {
+            return Ok(HttpResponse::RangeNotSatisfiable()
+                .insert_header((header::CONTENT_RANGE, format!("bytes */{}", total)))
+                .finish())
+        }
+    };
+
+    let length = range.end - range.start;
+    let body = if length == 0 {
+        stream::empty().boxed()
+    } else {
+        // 查询记录后存储中的文件被删除
+        match open(range).await? {
+            Some(body) => body,
+            None => {
+                return Ok(HttpResponse::NotFound().json(ApiResponse::not_found("File not found")))
+            }
+        }
+    };
+    let body = body.map_err(|e| {
+        let e: CommonError = e.into();
+        std::io::Error::other(e.message)
+    });
+    Ok(response
+        .content_type(content.content_type)
+        .insert_header(header::ETag(etag))
+        .insert_header((header::CACHE_CONTROL, cache_control))
+        .insert_header((header::ACCEPT_RANGES, "bytes"))
+        .body(SizedStream::new(length, body)))
+}
+
+pub async fn list_files_handler(
+    file_service: web::Data<dyn FileService>,
+    post_data: web::Json<FileQueryParams>,
+) -> Result<ApiResponse<ResultPaging<File>>, ApiError> {
+    let files = file_service.list(post_data.into_inner()).await?;
+    Ok(ApiResponse::success(files))
+}
+
+pub async fn get_file_handler(
+    path: web::Path<i32>,
+    file_service: web::Data<dyn FileService>,
+) -> Result<HttpResponse, ApiError> {
+    let file_id = path.into_inner();
+    match file_service.get_detail(file_id).await {
+        Ok(Some(detail)) => Ok(HttpResponse::Ok().json(ApiResponse::success(detail))),
+        Ok(None) => Ok(HttpResponse::NotFound().json(ApiResponse::<()>::error("File not found"))),
+        Err(e) => {
+            Ok(HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&e.to_string())))
+        }
+    }
+}
+
+pub async fn delete_file_handler(
+    path: web::Path<i32>,
+    file_service: web::Data<dyn FileService>,
+) -> Result<HttpResponse, ApiError> {
+    let file_id = path.into_inner();
+    match file_service.delete(file_id).await {
+        Ok(FileDeletion::Deleted) => {
+            Ok(HttpResponse::Ok().json(ApiResponse::success(serde_json::json!({"success": true}))))
+        }
+        Ok(FileDeletion::NotFound) => {
+            Ok(HttpResponse::NotFound().json(ApiResponse::<()>::error("File not found")))
+        }
+        // 仍被引用时返回引用位置
+        Ok(FileDeletion::Referenced(references)) => Ok(HttpResponse::Conflict().json(ApiResponse {
+            code: 409,
+            message: "File is still referenced".to_string(),
+            data: serde_json::json!({ "references": references }),
+        })),
+        Err(e) => {
+            Ok(HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&e.to_string())))
+        }
+    }
+}
+
+pub async fn list_orphan_files_handler(
+    file_service: web::Data<dyn FileService>,
+) -> Result<ApiResponse<Vec<File>>, ApiError> {
+    let files = file_service.find_orphans().await?;
+    Ok(ApiResponse::success(files))
+}
+
+pub async fn cleanup_orphan_files_handler(
+    file_service: web::Data<dyn FileService>,
+) -> Result<ApiResponse<Vec<File>>, ApiError> {
+    let files = file_service.cleanup_orphans().await?;
+    Ok(ApiResponse::success(files))
+}
+
+// 校验存储中所有文件的hash
+pub async fn check_file_integrity_handler(
+    file_service: web::Data<dyn FileService>,
+) -> Result<ApiResponse<FileIntegrityReport>, ApiError> {
+    let report = file_service.check_integrity().await?;
+    Ok(ApiResponse::success(report))
+}
+
+// 创建分片上传会话
+pub async fn init_upload_handler(
+    req: HttpRequest,
+    query: web::Query<UploadOptionsQuery>,
+    body: web::Json<InitUploadDTO>,
+    file_service: web::Data<dyn FileService>,
+) -> Result<ApiResponse<UploadSession>, ApiError> {
+    let options = upload_options(&req, &query)?;
+    let body = body.into_inner();
+    let session = file_service
+        .init_upload(body.file_name, body.size, options)
+        .await?;
+    Ok(ApiResponse::success(session))
+}
+
+// 查询分片上传进度，续传时从 received 处继续
+pub async fn get_upload_handler(
+    path: web::Path<String>,
+    file_service: web::Data<dyn FileService>,
+) -> Result<HttpResponse, ApiError> {
+    let upload_id = path.into_inner();
+    match file_service.get_upload(&upload_id).await? {
+        Some(session) => Ok(HttpResponse::Ok().json(ApiResponse::success(session))),
+        None => Ok(HttpResponse::NotFound().json(ApiResponse::<()>::error("Upload not found"))),
+    }
+}
+
+// 写入分片，请求体为分片的原始字节
+pub async fn upload_chunk_handler(
+    path: web::Path<String>,
+    query: web::Query<UploadChunkQuery>,
+    body: web::Bytes,
+    file_service: web::Data<dyn FileService>,
+) -> Result<HttpResponse, ApiError> {
+    let upload_id = path.into_inner();
+    match file_service
+        .append_chunk(&upload_id, query.offset, &body)
+        .await?
+    {
+        Some(session) => Ok(HttpResponse::Ok().json(ApiResponse::success(session))),
+        None => Ok(HttpResponse::NotFound().json(ApiResponse::<()>::error("Upload not found"))),
+    }
+}
+
+pub async fn complete_upload_handler(
+    path: web::Path<String>,
+    file_service: web::Data<dyn FileService>,
+) -> Result<HttpResponse, ApiError> {
+    let upload_id = path.into_inner();
+    match file_service.complete_upload(&upload_id).await? {
+        Some(file) => Ok(HttpResponse::Ok().json(ApiResponse::success(file))),
+        None => Ok(HttpResponse::NotFound().json(ApiResponse::<()>::error("Upload not found"))),
+    }
+}
+
+// 为文件生成带过期时间的签名URL，私有文件只能通过该URL访问
+pub async fn sign_file_url_handler(
+    path: web::Path<i32>,
+    body: Option<web::Json<SignUrlDTO>>,
+    file_service: web::Data<dyn FileService>,
+) -> Result<HttpResponse, ApiError> {
+    let file_id = path.into_inner();
+    let expires_in = body.and_then(|body| body.expires_in);
+    match file_service.sign_url(file_id, expires_in).await? {
+        Some(signed_url) => Ok(HttpResponse::Ok().json(ApiResponse::success(signed_url))),
+        None => Ok(HttpResponse::NotFound().json(ApiResponse::<()>::error("File not found"))),
+    }
+}
+
+// 修改文件可见性
+pub async fn set_file_visibility_handler(
+    path: web::Path<i32>,
+    body: web::Json<FileVisibilityDTO>,
+    file_service: web::Data<dyn FileService>,
+) -> Result<HttpResponse, ApiError> {
+    let file_id = path.into_inner();
+    match file_service
+        .set_visibility(file_id, body.visibility)
+        .await?
+    {
+        Some(file) => Ok(HttpResponse::Ok().json(ApiResponse::success(file))),
+        None => Ok(HttpResponse::NotFound().json(ApiResponse::<()>::error("File not found"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{body::to_bytes, http::StatusCode, test::TestRequest, web::Bytes};
+
+    use super::*;
+    use crate::api::middleware::jwt::generate_jwt_token;
+    use crate::domain::models::file::FileVisibility;
+
+    const VIDEO_SIZE: u64 = 1000;
+
+    fn video() -> FileContent {
+        FileContent {
+            storage_key: "abc.mp4".to_string(),
+            size: VIDEO_SIZE,
+            content_type: "video/mp4".to_string(),
+            file_hash: "abc".to_string(),
+            visibility: FileVisibility::Public,
+        }
+    }
+
+    async fn respond(req: TestRequest) -> (HttpResponse, Bytes) {
+        let data: Vec<u8> = (0..VIDEO_SIZE).map(|i| i as u8).collect();
+        let res = file_response(&req.to_http_request(), video(), "no-cache", |range| {
+            let chunk = Bytes::copy_from_slice(&data[range.start as usize..range.end as usize]);
+            async move { Ok(Some(stream::iter([Ok(chunk)]).boxed())) }
+        })
+        .await
+        .unwrap();
+        let (res, body) = res.into_parts();
+        let body = to_bytes(body).await.unwrap();
+        (res.set_body(()).map_into_boxed_body(), body)
+    }
+
+    fn content_range(res: &HttpResponse) -> Option<&str> {
+        res.headers()
+            .get(header::CONTENT_RANGE)
+            .and_then(|v| v.to_str().ok())
+    }
+
+    #[actix_web::test]
+    async fn serves_full_video_without_range() {
+        let (res, body) = respond(TestRequest::default()).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(body.len() as u64, VIDEO_SIZE);
+        assert_eq!(res.headers().get(header::ACCEPT_RANGES).unwrap(), "bytes");
+        assert_eq!(res.headers().get(header::CONTENT_TYPE).unwrap(), "video/mp4");
+    }
+
+    #[actix_web::test]
+    async fn serves_partial_video() {
+        let req = TestRequest::default().insert_header((header::RANGE, "bytes=100-199"));
+        let (res, body) = respond(req).await;
+        assert_eq!(res.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(content_range(&res), Some("bytes 100-199/1000"));
+        assert_eq!(body.len(), 100);
+        assert_eq!(body[0], 100);
+
+        let req = TestRequest::default().insert_header((header::RANGE, "bytes=-10"));
+        let (res, body) = respond(req).await;
+        assert_eq!(res.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(content_range(&res), Some("bytes 990-999/1000"));
+        assert_eq!(body.len(), 10);
+    }
+
+    #[actix_web::test]
+    async fn rejects_unsatisfiable_range() {
+        let req = TestRequest::default().insert_header((header::RANGE, "bytes=2000-3000"));
+        let (res, body) = respond(req).await;
+        assert_eq!(res.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(content_range(&res), Some("bytes */1000"));
+        assert!(body.is_empty());
+    }
+
+    #[actix_web::test]
+    async fn serves_full_video_for_multiple_ranges() {
+        let req = TestRequest::default().insert_header((header::RANGE, "bytes=0-9,20-29"));
+        let (res, body) = respond(req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(content_range(&res), None);
+        assert_eq!(body.len() as u64, VIDEO_SIZE);
+    }
+
+    #[actix_web::test]
+    async fn ignores_range_when_if_range_does_not_match() {
+        let req = TestRequest::default()
+            .insert_header((header::RANGE, "bytes=0-9"))
+            .insert_header((header::IF_RANGE, "\"other\""));
+        let (res, _) = respond(req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let req = TestRequest::default()
+            .insert_header((header::RANGE, "bytes=0-9"))
+            .insert_header((header::IF_RANGE, "\"abc\""));
+        let (res, _) = respond(req).await;
+        assert_eq!(res.status(), StatusCode::PARTIAL_CONTENT);
+    }
+
+    #[actix_web::test]
+    async fn returns_not_modified_for_matching_etag() {
+        let req = TestRequest::default().insert_header((header::IF_NONE_MATCH, "\"abc\""));
+        let (res, body) = respond(req).await;
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+        assert!(body.is_empty());
+    }
+
+    #[actix_web::test]
+    async fn keep_metadata_requires_admin() {
+        let query = UploadOptionsQuery {
+            keep_metadata: Some(true),
+            visibility: None,
+        };
+        let anonymous = TestRequest::default().to_http_request();
+        let res = actix_web::ResponseError::error_response(
+            &upload_options(&anonymous, &query).unwrap_err(),
+        );
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+        let token = generate_jwt_token().unwrap();
+        let admin = TestRequest::default()
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_http_request();
+        assert!(upload_options(&admin, &query).unwrap().keep_metadata);
+
+        // 不保留元数据时匿名上传不受影响
+        let query = UploadOptionsQuery {
+            keep_metadata: None,
+            visibility: Some(FileVisibility::Private),
+        };
+        let options = upload_options(&anonymous, &query).unwrap();
+        assert!(!options.keep_metadata);
+        assert_eq!(options.visibility, FileVisibility::Private);
+    }
+}
@@ -0,0 +1,31 @@
+use actix_web::{web, HttpResponse};
+
+use crate::{
+    api::dto::response::ApiResponse,
+    domain::{
+        models::health::{HealthReport, HealthStatus},
+        services::health::HealthService,
+    },
+};
+
+// 存活探针：进程能处理请求即返回 up，不检查外部依赖
+pub async fn liveness_handler() -> ApiResponse<HealthReport> {
+    ApiResponse::success(HealthReport {
+        status: HealthStatus::Up,
+        checks: Vec::new(),
+    })
+}
+
+// 就绪探针：任一依赖不可用时返回 503
+pub async fn readiness_handler(health_service: web::Data<dyn HealthService>) -> HttpResponse {
+    let report = health_service.readiness().await;
+    if report.is_up() {
+        HttpResponse::Ok().json(ApiResponse::success(report))
+    } else {
+        HttpResponse::ServiceUnavailable().json(ApiResponse {
+            code: 503,
+            message: "Service Unavailable".to_string(),
+            data: report,
+        })
+    }
+}
@@ -0,0 +1,18 @@
+use actix_web::{web, HttpResponse};
+
+use crate::{
+    api::dto::response::ApiResponse,
+    infrastructure::databases::postgresql::{record_pool_state, DBConn},
+    metrics,
+};
+
+// Prometheus 文本格式的指标
+pub async fn metrics_handler(pool: web::Data<DBConn>) -> HttpResponse {
+    record_pool_state(&pool);
+    match metrics::render() {
+        Ok((content_type, body)) => HttpResponse::Ok().content_type(content_type).body(body),
+        Err(e) => {
+            HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&e.to_string()))
+        }
+    }
+}
@@ -1,5 +1,7 @@
 pub mod blog_handler;
 pub mod file_handler;
+pub mod health_handler;
+pub mod metrics_handler;
 pub mod note_handler;
 pub mod service_context_handlers;
 pub mod snippet_handler;
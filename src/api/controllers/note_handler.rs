@@ -1,10 +1,16 @@
-use actix_web::{web, HttpResponse};
+use actix_web::{http::header::IfMatch, web, HttpMessage, HttpRequest, HttpResponse};
 
+use crate::api::cache::ResponseCache;
 use crate::api::dto::{
     note::{CreateNoteDTO, ListNotesDTO, UpdateNoteDTO},
-    response::ApiResponse,
+    response::{error_response, ApiResponse},
+};
+use crate::api::precondition;
+use crate::domain::{
+    error::ApiError,
+    models::{cache::CacheKey, note::UpdateNote, version::VersionedUpdate},
+    services::{cache::CacheService, note::NoteService},
 };
-use crate::domain::{error::ApiError, services::note::NoteService};
 
 pub async fn create_note_handler(
     body: web::Json<CreateNoteDTO>,
@@ -26,9 +32,7 @@ pub async fn list_notes_handler(
     let params = body.into_inner().into();
     match note_service.list(params).await {
         Ok(result) => Ok(HttpResponse::Ok().json(ApiResponse::success(result))),
-        Err(e) => {
-            Ok(HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&e.to_string())))
-        }
+        Err(e) => Ok(error_response(&e)),
     }
 }
 
@@ -47,10 +51,16 @@ pub async fn get_note_handler(
 }
 
 pub async fn get_all_notes_handler(
+    req: HttpRequest,
     note_service: web::Data<dyn NoteService>,
+    cache_service: web::Data<dyn CacheService>,
 ) -> Result<HttpResponse, ApiError> {
+    let cache = ResponseCache::new(&req, cache_service.get_ref(), CacheKey::Notes).await;
+    if let Some(response) = cache.cached() {
+        return Ok(response);
+    }
     match note_service.get_all().await {
-        Ok(notes) => Ok(HttpResponse::Ok().json(ApiResponse::success(notes))),
+        Ok(notes) => Ok(cache.respond(notes).await),
         Err(e) => {
             Ok(HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&e.to_string())))
         }
@@ -58,15 +68,42 @@ pub async fn get_all_notes_handler(
 }
 
 pub async fn update_note_handler(
+    req: HttpRequest,
     path: web::Path<i32>,
     body: web::Json<UpdateNoteDTO>,
     note_service: web::Data<dyn NoteService>,
 ) -> Result<HttpResponse, ApiError> {
     let note_id = path.into_inner();
-    let update_note = body.into_inner().into();
+    let mut update_note: UpdateNote = body.into_inner().into();
+    if let Some(if_match) = req.get_header::<IfMatch>() {
+        match note_service.get(note_id).await {
+            Ok(Some(current)) if precondition::if_match(&if_match, &current) => {
+                update_note.version = update_note.version.or(Some(current.version));
+            }
+            Ok(Some(current)) => {
+                return Ok(precondition::conflict("Note has been modified", current));
+            }
+            Ok(None) => {
+                return Ok(
+                    HttpResponse::NotFound().json(ApiResponse::<()>::error("Note not found"))
+                );
+            }
+            Err(e) => {
+                return Ok(HttpResponse::InternalServerError()
+                    .json(ApiResponse::<()>::error(&e.to_string())));
+            }
+        }
+    }
     match note_service.update(note_id, update_note).await {
-        Ok(Some(note)) => Ok(HttpResponse::Ok().json(ApiResponse::success(note))),
-        Ok(None) => Ok(HttpResponse::NotFound().json(ApiResponse::<()>::error("Note not found"))),
+        Ok(VersionedUpdate::Updated(note)) => {
+            Ok(HttpResponse::Ok().json(ApiResponse::success(note)))
+        }
+        Ok(VersionedUpdate::NotFound) => {
+            Ok(HttpResponse::NotFound().json(ApiResponse::<()>::error("Note not found")))
+        }
+        Ok(VersionedUpdate::Conflict(current)) => {
+            Ok(precondition::conflict("Note has been modified", current))
+        }
         Err(e) => {
             Ok(HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&e.to_string())))
         }
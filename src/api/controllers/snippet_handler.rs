@@ -1,10 +1,16 @@
-use actix_web::{web, HttpResponse};
+use actix_web::{http::header::IfMatch, web, HttpMessage, HttpRequest, HttpResponse};
 
+use crate::api::cache::ResponseCache;
 use crate::api::dto::{
-    response::ApiResponse,
+    response::{error_response, moved_permanently, ApiResponse},
     snippet::{CreateSnippetDTO, ListSnippetsDTO, UpdateSnippetDTO},
 };
-use crate::domain::{error::ApiError, services::snippet::SnippetService};
+use crate::api::precondition;
+use crate::domain::{
+    error::ApiError,
+    models::{cache::CacheKey, snippet::UpdateSnippet, version::VersionedUpdate},
+    services::{cache::CacheService, snippet::SnippetService},
+};
 
 pub async fn create_snippet_handler(
     body: web::Json<CreateSnippetDTO>,
@@ -13,9 +19,7 @@ pub async fn create_snippet_handler(
     let create_snippet = body.into_inner().into();
     match snippet_service.create(create_snippet).await {
         Ok(snippet) => Ok(HttpResponse::Ok().json(ApiResponse::success(snippet))),
-        Err(e) => {
-            Ok(HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&e.to_string())))
-        }
+        Err(e) => Ok(error_response(&e)),
     }
 }
 
@@ -26,9 +30,7 @@ pub async fn list_snippets_handler(
     let params = body.into_inner().into();
     match snippet_service.list(params).await {
         Ok(result) => Ok(HttpResponse::Ok().json(ApiResponse::success(result))),
-        Err(e) => {
-            Ok(HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&e.to_string())))
-        }
+        Err(e) => Ok(error_response(&e)),
     }
 }
 
@@ -49,15 +51,26 @@ pub async fn get_snippet_handler(
 }
 
 pub async fn get_snippet_by_slug_handler(
+    req: HttpRequest,
     path: web::Path<String>,
     snippet_service: web::Data<dyn SnippetService>,
 ) -> Result<HttpResponse, ApiError> {
     let slug = path.into_inner();
     match snippet_service.get_by_slug(&slug).await {
         Ok(Some(snippet)) => Ok(HttpResponse::Ok().json(ApiResponse::success(snippet))),
-        Ok(None) => {
-            Ok(HttpResponse::NotFound().json(ApiResponse::<()>::error("Snippet not found")))
-        }
+        // 旧 slug 永久重定向到当前 slug
+        Ok(None) => match snippet_service.find_redirect(&slug).await {
+            Ok(Some(current_slug)) => Ok(moved_permanently(
+                "/api/snippets/slug",
+                &current_slug,
+                req.query_string(),
+            )),
+            Ok(None) => {
+                Ok(HttpResponse::NotFound().json(ApiResponse::<()>::error("Snippet not found")))
+            }
+            Err(e) => Ok(HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error(&e.to_string()))),
+        },
         Err(e) => {
             Ok(HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&e.to_string())))
         }
@@ -65,10 +78,17 @@ pub async fn get_snippet_by_slug_handler(
 }
 
 pub async fn get_published_snippets_handler(
+    req: HttpRequest,
     snippet_service: web::Data<dyn SnippetService>,
+    cache_service: web::Data<dyn CacheService>,
 ) -> Result<HttpResponse, ApiError> {
+    let cache =
+        ResponseCache::new(&req, cache_service.get_ref(), CacheKey::PublishedSnippets).await;
+    if let Some(response) = cache.cached() {
+        return Ok(response);
+    }
     match snippet_service.get_published().await {
-        Ok(snippets) => Ok(HttpResponse::Ok().json(ApiResponse::success(snippets))),
+        Ok(snippets) => Ok(cache.respond(snippets).await),
         Err(e) => {
             Ok(HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&e.to_string())))
         }
@@ -76,20 +96,43 @@ pub async fn get_published_snippets_handler(
 }
 
 pub async fn update_snippet_handler(
+    req: HttpRequest,
     path: web::Path<i32>,
     body: web::Json<UpdateSnippetDTO>,
     snippet_service: web::Data<dyn SnippetService>,
 ) -> Result<HttpResponse, ApiError> {
     let snippet_id = path.into_inner();
-    let update_snippet = body.into_inner().into();
+    let mut update_snippet: UpdateSnippet = body.into_inner().into();
+    if let Some(if_match) = req.get_header::<IfMatch>() {
+        match snippet_service.get(snippet_id).await {
+            Ok(Some(current)) if precondition::if_match(&if_match, &current) => {
+                update_snippet.version = update_snippet.version.or(Some(current.version));
+            }
+            Ok(Some(current)) => {
+                return Ok(precondition::conflict("Snippet has been modified", current));
+            }
+            Ok(None) => {
+                return Ok(
+                    HttpResponse::NotFound().json(ApiResponse::<()>::error("Snippet not found"))
+                );
+            }
+            Err(e) => {
+                return Ok(HttpResponse::InternalServerError()
+                    .json(ApiResponse::<()>::error(&e.to_string())));
+            }
+        }
+    }
     match snippet_service.update(snippet_id, update_snippet).await {
-        Ok(Some(snippet)) => Ok(HttpResponse::Ok().json(ApiResponse::success(snippet))),
-        Ok(None) => {
+        Ok(VersionedUpdate::Updated(snippet)) => {
+            Ok(HttpResponse::Ok().json(ApiResponse::success(snippet)))
+        }
+        Ok(VersionedUpdate::NotFound) => {
             Ok(HttpResponse::NotFound().json(ApiResponse::<()>::error("Snippet not found")))
         }
-        Err(e) => {
-            Ok(HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&e.to_string())))
+        Ok(VersionedUpdate::Conflict(current)) => {
+            Ok(precondition::conflict("Snippet has been modified", current))
         }
+        Err(e) => Ok(error_response(&e)),
     }
 }
 
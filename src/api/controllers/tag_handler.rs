@@ -1,15 +1,23 @@
-use actix_web::web;
+use actix_web::{http::header::IfMatch, web, HttpMessage, HttpRequest, HttpResponse};
 
 use crate::{
-    api::dto::{
-        response::ApiResponse,
-        tag::{CreateTagDTO, UpdateTagDTO},
+    api::{
+        cache::ResponseCache,
+        dto::{
+            response::ApiResponse,
+            tag::{CreateTagDTO, UpdateTagDTO},
+        },
+        precondition,
     },
     domain::{
         error::ApiError,
-        models::tag::{Tag, TagType},
+        models::{
+            cache::CacheKey,
+            tag::{Tag, TagType, UpdateTag},
+            version::VersionedUpdate,
+        },
         repositories::{repository::ResultPaging, tag::TagQueryParams},
-        services::tag::TagService,
+        services::{cache::CacheService, tag::TagService},
     },
 };
 
@@ -39,15 +47,32 @@ pub async fn get_tag_handler(
 }
 
 pub async fn update_tag_handler(
+    req: HttpRequest,
     tag_service: web::Data<dyn TagService>,
     path: web::Path<i32>,
     post_data: web::Json<UpdateTagDTO>,
-) -> Result<ApiResponse<Option<Tag>>, ApiError> {
+) -> Result<HttpResponse, ApiError> {
     let tag_id = path.into_inner();
-    let updated_tag = tag_service
-        .update(tag_id, post_data.into_inner().into())
-        .await?;
-    Ok(ApiResponse::success(updated_tag))
+    let mut update_tag: UpdateTag = post_data.into_inner().into();
+    if let Some(if_match) = req.get_header::<IfMatch>() {
+        // 标签不存在时与更新一样返回 null
+        let Some(current) = tag_service.get(tag_id).await? else {
+            return Ok(HttpResponse::Ok().json(ApiResponse::success(None::<Tag>)));
+        };
+        if !precondition::if_match(&if_match, &current) {
+            return Ok(precondition::conflict("Tag has been modified", current));
+        }
+        update_tag.version = update_tag.version.or(Some(current.version));
+    }
+    match tag_service.update(tag_id, update_tag).await? {
+        VersionedUpdate::Updated(tag) => {
+            Ok(HttpResponse::Ok().json(ApiResponse::success(Some(tag))))
+        }
+        VersionedUpdate::NotFound => Ok(HttpResponse::Ok().json(ApiResponse::success(None::<Tag>))),
+        VersionedUpdate::Conflict(current) => {
+            Ok(precondition::conflict("Tag has been modified", current))
+        }
+    }
 }
 
 pub async fn delete_tag_handler(
@@ -75,9 +100,23 @@ pub struct GetAllTagsQuery {
 }
 
 pub async fn get_all_tags_handler(
+    req: HttpRequest,
     tag_service: web::Data<dyn TagService>,
+    cache_service: web::Data<dyn CacheService>,
     query: web::Query<GetAllTagsQuery>,
-) -> Result<ApiResponse<Vec<Tag>>, ApiError> {
+) -> Result<HttpResponse, ApiError> {
+    let cache = ResponseCache::new(
+        &req,
+        cache_service.get_ref(),
+        CacheKey::Tags {
+            tag_type: query.tag_type.clone(),
+        },
+    )
+    .await;
+    if let Some(response) = cache.cached() {
+        return Ok(response);
+    }
+
     let tag_type = query.tag_type.as_ref().and_then(|t| match t.as_str() {
         "ALL" => Some(TagType::All),
         "BLOG" => Some(TagType::Blog),
@@ -87,5 +126,5 @@ pub async fn get_all_tags_handler(
     });
 
     let tags = tag_service.get_all(tag_type).await?;
-    Ok(ApiResponse::success(tags))
+    Ok(cache.respond(tags).await)
 }
@@ -1,11 +1,12 @@
-use crate::domain::models::blog::{CreateBlog, UpdateBlog};
+use crate::domain::models::blog::{Blog, BlogFields, BlogNeighbor, CreateBlog, UpdateBlog};
 use crate::domain::repositories::blog::BlogQueryParams;
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize)]
 pub struct CreateBlogDTO {
     pub title: String,
-    pub slug: String,
+    // 可选，未提供时根据标题自动生成
+    pub slug: Option<String>,
     pub description: String,
     pub body: String,
     pub cover: Option<String>,
@@ -18,13 +19,15 @@ impl Into<CreateBlog> for CreateBlogDTO {
     fn into(self) -> CreateBlog {
         CreateBlog {
             title: self.title,
-            slug: self.slug,
+            slug: self.slug.unwrap_or_default(),
             description: self.description,
             body: self.body,
             cover: self.cover,
             author: self.author,
             published: self.published.unwrap_or(false),
             tag_ids: self.tags.unwrap_or_default(),
+            created_at: None,
+            updated_at: None,
         }
     }
 }
@@ -39,6 +42,8 @@ pub struct UpdateBlogDTO {
     pub author: Option<String>,
     pub published: Option<bool>,
     pub tags: Option<Vec<i32>>,
+    // 读取时的版本，也可以通过 If-Match 请求头传入 ETag
+    pub version: Option<i32>,
 }
 
 impl Into<UpdateBlog> for UpdateBlogDTO {
@@ -52,6 +57,7 @@ impl Into<UpdateBlog> for UpdateBlogDTO {
             author: self.author,
             published: self.published,
             tag_ids: self.tags,
+            version: self.version,
         }
     }
 }
@@ -60,6 +66,9 @@ impl Into<UpdateBlog> for UpdateBlogDTO {
 pub struct ListBlogsDTO {
     pub limit: Option<i64>,
     pub offset: Option<i64>,
+    pub cursor: Option<String>,
+    #[serde(rename = "withTotal")]
+    pub with_total: Option<bool>,
     pub title: Option<String>,
     pub slug: Option<String>,
     pub published: Option<bool>,
@@ -67,6 +76,8 @@ pub struct ListBlogsDTO {
     #[serde(rename = "orderBy")]
     pub order_by: Option<String>,
     pub order: Option<String>,
+    // summary（默认，不含正文）或 full
+    pub fields: Option<BlogFields>,
 }
 
 impl Into<BlogQueryParams> for ListBlogsDTO {
@@ -74,6 +85,8 @@ impl Into<BlogQueryParams> for ListBlogsDTO {
         BlogQueryParams {
             limit: self.limit,
             offset: self.offset,
+            cursor: self.cursor,
+            with_total: self.with_total,
             title: self.title,
             slug: self.slug,
             published: self.published,
@@ -83,3 +96,29 @@ impl Into<BlogQueryParams> for ListBlogsDTO {
         }
     }
 }
+
+#[derive(Deserialize)]
+pub struct PublishedBlogQuery {
+    // 为 true 时在响应中附带上一篇/下一篇
+    pub neighbors: Option<bool>,
+}
+
+#[derive(Serialize)]
+pub struct PublishedBlogDTO {
+    #[serde(flatten)]
+    pub blog: Blog,
+    pub previous: Option<BlogNeighbor>,
+    pub next: Option<BlogNeighbor>,
+}
+
+#[derive(Deserialize)]
+pub struct BlogArchiveQuery {
+    // 按标签slug过滤，可选
+    pub tag: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct BlogFieldsQuery {
+    // summary（默认，不含正文）或 full
+    pub fields: Option<BlogFields>,
+}
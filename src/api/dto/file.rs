@@ -1,10 +1,71 @@
 use actix_multipart::form::{tempfile::TempFile, MultipartForm};
+use serde::Deserialize;
 
-
-
+use crate::domain::models::file::{FileVisibility, ImageFormat, ImageVariant};
 
 #[derive(Debug, MultipartForm)]
 pub struct UploadForm {
     #[multipart(rename = "file")]
     pub files: Vec<TempFile>,
 }
+
+// 图片变体参数，如 /static/{hash}?w=640&fmt=webp
+#[derive(Debug, Deserialize)]
+pub struct ImageVariantQuery {
+    pub w: Option<u32>,
+    pub fmt: Option<ImageFormat>,
+}
+
+impl ImageVariantQuery {
+    pub fn variant(&self) -> Option<ImageVariant> {
+        if self.w.is_none() && self.fmt.is_none() {
+            return None;
+        }
+        Some(ImageVariant {
+            width: self.w,
+            format: self.fmt,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InitUploadDTO {
+    #[serde(alias = "fileName")]
+    pub file_name: String,
+    pub size: i64,
+}
+
+// 上传选项，keep_metadata=true 保留图片的 EXIF 等元数据，需要管理员令牌
+#[derive(Debug, Deserialize)]
+pub struct UploadOptionsQuery {
+    #[serde(alias = "keepMetadata")]
+    pub keep_metadata: Option<bool>,
+    // 默认 public，private 文件只能通过签名URL访问
+    pub visibility: Option<FileVisibility>,
+}
+
+// 签名URL参数
+#[derive(Debug, Deserialize)]
+pub struct SignedFileQuery {
+    // 过期时间（Unix 时间戳，秒）
+    pub expires: i64,
+    pub signature: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SignUrlDTO {
+    // 有效期（秒），不传时使用 uploads.signed_url_ttl_secs
+    #[serde(alias = "expiresIn")]
+    pub expires_in: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FileVisibilityDTO {
+    pub visibility: FileVisibility,
+}
+
+// 分片写入位置，必须等于会话中已接收的字节数
+#[derive(Debug, Deserialize)]
+pub struct UploadChunkQuery {
+    pub offset: i64,
+}
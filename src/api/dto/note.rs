@@ -15,6 +15,8 @@ impl Into<CreateNote> for CreateNoteDTO {
             body: self.body,
             published: self.published.unwrap_or(false),
             tag_ids: self.tags.unwrap_or_default(),
+            created_at: None,
+            updated_at: None,
         }
     }
 }
@@ -24,6 +26,7 @@ pub struct UpdateNoteDTO {
     pub body: Option<String>,
     pub published: Option<bool>,
     pub tags: Option<Vec<i32>>,
+    pub version: Option<i32>,
 }
 
 impl Into<UpdateNote> for UpdateNoteDTO {
@@ -32,6 +35,7 @@ impl Into<UpdateNote> for UpdateNoteDTO {
             body: self.body,
             published: self.published,
             tag_ids: self.tags,
+            version: self.version,
         }
     }
 }
@@ -40,6 +44,9 @@ impl Into<UpdateNote> for UpdateNoteDTO {
 pub struct ListNotesDTO {
     pub limit: Option<i64>,
     pub offset: Option<i64>,
+    pub cursor: Option<String>,
+    #[serde(rename = "withTotal")]
+    pub with_total: Option<bool>,
     pub body: Option<String>,
     pub published: Option<bool>,
     pub tags: Option<Vec<i32>>,
@@ -53,6 +60,8 @@ impl Into<NoteQueryParams> for ListNotesDTO {
         NoteQueryParams {
             limit: self.limit,
             offset: self.offset,
+            cursor: self.cursor,
+            with_total: self.with_total,
             body: self.body,
             published: self.published,
             tags: self.tags,
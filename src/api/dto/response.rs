@@ -1,6 +1,19 @@
-use actix_web::{HttpResponse, Responder};
+use actix_web::{
+    http::{header, StatusCode},
+    HttpResponse, Responder,
+};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
 use serde::Serialize;
 
+use crate::domain::error::CommonError;
+
+// 路径段中需要编码的字符，RFC 3986 的非保留字符 - _ . ~ 保持原样
+const PATH_SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
 #[derive(Debug, Serialize)]
 pub struct ApiResponse<T> {
     pub code: u32,
@@ -34,6 +47,41 @@ impl ApiResponse<()> {
             data: (),
         }
     }
+
+    pub fn not_found(message: &str) -> Self {
+        ApiResponse {
+            code: 404,
+            message: message.to_string(),
+            data: (),
+        }
+    }
+}
+
+// 按 CommonError 的错误码返回，如无效游标等校验错误返回 400 而不是 500
+pub fn error_response(error: &CommonError) -> HttpResponse {
+    let status =
+        StatusCode::from_u16(error.code as u16).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    HttpResponse::build(status).json(ApiResponse {
+        code: status.as_u16() as u32,
+        message: error.to_string(),
+        data: (),
+    })
+}
+
+// slug 已变更时的永久重定向响应，Location 指向当前 slug，data 中返回当前 slug
+pub fn moved_permanently(base_path: &str, slug: &str, query_string: &str) -> HttpResponse {
+    let mut location = format!("{}/{}", base_path, utf8_percent_encode(slug, PATH_SEGMENT));
+    if !query_string.is_empty() {
+        location.push('?');
+        location.push_str(query_string);
+    }
+    HttpResponse::MovedPermanently()
+        .insert_header((header::LOCATION, location))
+        .json(ApiResponse {
+            code: 301,
+            message: "Moved Permanently".to_string(),
+            data: serde_json::json!({ "slug": slug }),
+        })
 }
 
 // 为ApiResponse实现Responder trait
@@ -44,3 +92,39 @@ impl<T: Serialize> Responder for ApiResponse<T> {
         HttpResponse::Ok().json(self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::{header, StatusCode};
+
+    use super::moved_permanently;
+
+    fn location(slug: &str, query_string: &str) -> String {
+        let response = moved_permanently("/api/blogs/slug", slug, query_string);
+        assert_eq!(response.status(), StatusCode::MOVED_PERMANENTLY);
+        response
+            .headers()
+            .get(header::LOCATION)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn keeps_unreserved_characters() {
+        assert_eq!(
+            location("hello-world_v1.2~beta", ""),
+            "/api/blogs/slug/hello-world_v1.2~beta"
+        );
+    }
+
+    #[test]
+    fn encodes_other_characters() {
+        assert_eq!(
+            location("a b/c?d", "page=2"),
+            "/api/blogs/slug/a%20b%2Fc%3Fd?page=2"
+        );
+        assert_eq!(location("你好", ""), "/api/blogs/slug/%E4%BD%A0%E5%A5%BD");
+    }
+}
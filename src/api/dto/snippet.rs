@@ -5,7 +5,8 @@ use serde::{Deserialize, Serialize};
 #[derive(Deserialize, Serialize)]
 pub struct CreateSnippetDTO {
     pub title: String,
-    pub slug: String,
+    // 可选，未提供时根据标题自动生成
+    pub slug: Option<String>,
     pub description: String,
     pub body: String,
     pub published: Option<bool>,
@@ -16,11 +17,13 @@ impl Into<CreateSnippet> for CreateSnippetDTO {
     fn into(self) -> CreateSnippet {
         CreateSnippet {
             title: self.title,
-            slug: self.slug,
+            slug: self.slug.unwrap_or_default(),
             description: self.description,
             body: self.body,
             published: self.published.unwrap_or(false),
             tag_ids: self.tags.unwrap_or_default(),
+            created_at: None,
+            updated_at: None,
         }
     }
 }
@@ -33,6 +36,7 @@ pub struct UpdateSnippetDTO {
     pub body: Option<String>,
     pub published: Option<bool>,
     pub tags: Option<Vec<i32>>,
+    pub version: Option<i32>,
 }
 
 impl Into<UpdateSnippet> for UpdateSnippetDTO {
@@ -44,6 +48,7 @@ impl Into<UpdateSnippet> for UpdateSnippetDTO {
             body: self.body,
             published: self.published,
             tag_ids: self.tags,
+            version: self.version,
         }
     }
 }
@@ -52,6 +57,9 @@ impl Into<UpdateSnippet> for UpdateSnippetDTO {
 pub struct ListSnippetsDTO {
     pub limit: Option<i64>,
     pub offset: Option<i64>,
+    pub cursor: Option<String>,
+    #[serde(rename = "withTotal")]
+    pub with_total: Option<bool>,
     pub title: Option<String>,
     pub slug: Option<String>,
     pub published: Option<bool>,
@@ -66,6 +74,8 @@ impl Into<SnippetQueryParams> for ListSnippetsDTO {
         SnippetQueryParams {
             limit: self.limit,
             offset: self.offset,
+            cursor: self.cursor,
+            with_total: self.with_total,
             title: self.title,
             slug: self.slug,
             published: self.published,
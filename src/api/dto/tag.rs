@@ -28,7 +28,8 @@ impl Into<TagTypeModel> for TagType {
 #[derive(Deserialize, Serialize)]
 pub struct CreateTagDTO {
     pub name: String,
-    pub slug: String,
+    // 可选，未提供时根据名称自动生成
+    pub slug: Option<String>,
     #[serde(rename = "type")]
     pub tag_type: TagType,
     pub icon: Option<String>,
@@ -40,10 +41,12 @@ impl Into<CreateTag> for CreateTagDTO {
     fn into(self) -> CreateTag {
         CreateTag {
             name: self.name,
-            slug: self.slug,
+            slug: self.slug.unwrap_or_default(),
             tag_type: self.tag_type.into(),
             icon: self.icon,
             icon_dark: self.icon_dark,
+            created_at: None,
+            updated_at: None,
         }
     }
 }
@@ -57,6 +60,7 @@ pub struct UpdateTagDTO {
     pub icon: Option<String>,
     #[serde(rename = "iconDark")]
     pub icon_dark: Option<String>,
+    pub version: Option<i32>,
 }
 
 impl Into<crate::domain::models::tag::UpdateTag> for UpdateTagDTO {
@@ -67,6 +71,7 @@ impl Into<crate::domain::models::tag::UpdateTag> for UpdateTagDTO {
             tag_type: self.tag_type.map(|t| t.into()),
             icon: self.icon,
             icon_dark: self.icon_dark,
+            version: self.version,
         }
     }
 }
@@ -50,6 +50,7 @@ impl Into<ResultPaging<TodoDTO>> for ResultPaging<Todo> {
         ResultPaging {
             total: self.total,
             items: self.items.into_iter().map(|todo| todo.into()).collect(),
+            next_cursor: self.next_cursor,
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,254 @@
+use std::future::{ready, Ready};
+use std::time::SystemTime;
+
+use actix_web::{
+    body::{to_bytes, BoxBody, MessageBody},
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::{
+        header::{
+            self, EntityTag, HeaderValue, HttpDate, IfModifiedSince, IfNoneMatch, LastModified,
+            TryIntoHeaderValue, ETAG,
+        },
+        Method, StatusCode,
+    },
+    Error, HttpMessage, HttpResponse,
+};
+use chrono::{DateTime, Utc};
+use futures_util::future::LocalBoxFuture;
+use sha2::{Digest, Sha256};
+
+// 内容接口的 GET 响应
+const CONTENT_PATHS: &[&str] = &["/api/blogs", "/api/notes", "/api/snippets", "/api/tags"];
+
+// 根据响应中内容的版本生成强 ETag，单个资源的响应同时返回 Last-Modified；
+// 请求的 If-None-Match 或 If-Modified-Since 与当前内容一致时返回 304
+pub struct ConditionalGet;
+
+impl<S, B> Transform<S, ServiceRequest> for ConditionalGet
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = ConditionalGetService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ConditionalGetService { service }))
+    }
+}
+
+pub struct ConditionalGetService<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for ConditionalGetService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let applies = req.method() == Method::GET
+            && CONTENT_PATHS
+                .iter()
+                .any(|prefix| req.path().starts_with(prefix));
+        if !applies {
+            let fut = self.service.call(req);
+            return Box::pin(async move { Ok(fut.await?.map_into_boxed_body()) });
+        }
+
+        let if_none_match = req.get_header::<IfNoneMatch>();
+        let if_modified_since = req.get_header::<IfModifiedSince>();
+        let fut = self.service.call(req);
+        Box::pin(async move {
+            let res = fut.await?;
+            if res.status() != StatusCode::OK {
+                return Ok(res.map_into_boxed_body());
+            }
+
+            let (req, res) = res.into_parts();
+            let (res, body) = res.into_parts();
+            let body = to_bytes(body).await.map_err(|e| {
+                let e: Box<dyn std::error::Error> = e.into();
+                actix_web::error::ErrorInternalServerError(e.to_string())
+            })?;
+
+            // 不是 ApiResponse 格式的响应不处理
+            let Some(data) = serde_json::from_slice::<serde_json::Value>(&body)
+                .ok()
+                .and_then(|mut value| value.get_mut("data").map(serde_json::Value::take))
+            else {
+                return Ok(ServiceResponse::new(
+                    req,
+                    res.set_body(body).map_into_boxed_body(),
+                ));
+            };
+            let etag = entity_tag(&data);
+            let last_modified = last_modified(&data);
+            // 同时存在时以 If-None-Match 为准
+            let not_modified = match (if_none_match, if_modified_since) {
+                (Some(IfNoneMatch::Any), _) => true,
+                (Some(IfNoneMatch::Items(tags)), _) => tags.iter().any(|tag| tag.weak_eq(&etag)),
+                (None, Some(IfModifiedSince(since))) => {
+                    last_modified.is_some_and(|modified| modified <= SystemTime::from(since))
+                }
+                (None, None) => false,
+            };
+
+            let mut res = if not_modified {
+                HttpResponse::NotModified().finish()
+            } else {
+                res.set_body(body).map_into_boxed_body()
+            };
+            let headers = res.headers_mut();
+            if let Ok(value) = etag.try_into_value() {
+                headers.insert(ETAG, value);
+            }
+            if let Some(modified) = last_modified {
+                if let Ok(value) = LastModified(HttpDate::from(modified)).try_into_value() {
+                    headers.insert(header::LAST_MODIFIED, value);
+                }
+            }
+            // 浏览器每次使用缓存前都向服务端确认，避免按 Last-Modified 启发式缓存而显示旧内容
+            if !headers.contains_key(header::CACHE_CONTROL) {
+                headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+            }
+            Ok(ServiceResponse::new(req, res))
+        })
+    }
+}
+
+// 由响应 data 中内容的版本生成 ETag：带 version 和 updated_at 的内容（博客、笔记、代码片段、标签）
+// 只取 id、version 和 updated_at，正文等字段的变更都会增加版本号；其他对象（附件、上一篇/下一篇、
+// 归档条目、分页信息）取其标量字段。结果取 SHA-256 前 16 字节
+pub fn entity_tag(data: &serde_json::Value) -> EntityTag {
+    let mut hasher = Sha256::new();
+    hash_versions(data, &mut hasher);
+    let digest = hasher.finalize();
+    let hex: String = digest[..16].iter().map(|b| format!("{:02x}", b)).collect();
+    EntityTag::new_strong(hex)
+}
+
+fn hash_versions(value: &serde_json::Value, hasher: &mut Sha256) {
+    match value {
+        serde_json::Value::Object(map) => {
+            let versioned = map.contains_key("version") && map.contains_key("updated_at");
+            hasher.update(b"{");
+            for (key, value) in map {
+                let nested = value.is_object() || value.is_array();
+                if nested || !versioned || matches!(key.as_str(), "id" | "version" | "updated_at") {
+                    hasher.update(key.as_bytes());
+                    hasher.update(b":");
+                    hash_versions(value, hasher);
+                    hasher.update(b",");
+                }
+            }
+            hasher.update(b"}");
+        }
+        serde_json::Value::Array(items) => {
+            hasher.update(b"[");
+            for item in items {
+                hash_versions(item, hasher);
+                hasher.update(b",");
+            }
+            hasher.update(b"]");
+        }
+        scalar => hasher.update(scalar.to_string().as_bytes()),
+    }
+}
+
+// 单个资源（data 中包含 updated_at）取其自身及嵌套标签中最新的 updated_at，精确到秒；
+// 列表中删除的内容无法反映在最大修改时间中，列表只使用 ETag
+fn last_modified(data: &serde_json::Value) -> Option<SystemTime> {
+    data.get("updated_at")?;
+    let latest = latest_updated_at(data)?;
+    Some(SystemTime::from(DateTime::<Utc>::from_timestamp(
+        latest.timestamp(),
+        0,
+    )?))
+}
+
+fn latest_updated_at(value: &serde_json::Value) -> Option<DateTime<Utc>> {
+    match value {
+        serde_json::Value::Object(map) => {
+            let own = map
+                .get("updated_at")
+                .and_then(|v| v.as_str())
+                .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+                .map(|v| v.with_timezone(&Utc));
+            map.values().filter_map(latest_updated_at).chain(own).max()
+        }
+        serde_json::Value::Array(items) => items.iter().filter_map(latest_updated_at).max(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use serde_json::json;
+
+    use super::{entity_tag, last_modified};
+
+    fn blog(version: i32, body: &str, tag_version: i32) -> serde_json::Value {
+        json!({
+            "id": 1,
+            "title": "Hello",
+            "body": body,
+            "tags": [{ "id": 7, "name": "rust", "version": tag_version, "updated_at": "2026-10-19T08:00:00Z" }],
+            "attachments": [{ "file_id": 3, "url": "/static/a.png", "visibility": "public" }],
+            "updated_at": format!("2026-10-19T09:00:0{}.500Z", version),
+            "version": version,
+        })
+    }
+
+    #[test]
+    fn etag_follows_versions() {
+        let etag = entity_tag(&blog(1, "body", 1));
+        assert_eq!(etag, entity_tag(&blog(1, "body", 1)));
+        assert!(!etag.weak);
+        // 正文只随版本号变化，不需要参与计算
+        assert_eq!(etag, entity_tag(&blog(1, "same version", 1)));
+        assert_ne!(etag, entity_tag(&blog(2, "body", 1)));
+        assert_ne!(etag, entity_tag(&blog(1, "body", 2)));
+
+        let mut private = blog(1, "body", 1);
+        private["attachments"][0]["visibility"] = json!("private");
+        assert_ne!(etag, entity_tag(&private));
+    }
+
+    #[test]
+    fn list_etag_changes_when_items_change() {
+        let list = |items: Vec<serde_json::Value>| json!({ "total": items.len(), "items": items });
+        let etag = entity_tag(&list(vec![blog(1, "a", 1), blog(2, "b", 1)]));
+        assert_ne!(etag, entity_tag(&list(vec![blog(1, "a", 1)])));
+        assert_ne!(
+            etag,
+            entity_tag(&list(vec![blog(2, "b", 1), blog(1, "a", 1)]))
+        );
+
+        let archive = |title: &str| json!([{ "year": 2026, "months": [{ "entries": [{ "id": 1, "title": title }] }] }]);
+        assert_ne!(entity_tag(&archive("a")), entity_tag(&archive("b")));
+    }
+
+    #[test]
+    fn last_modified_uses_latest_nested_update() {
+        let mut data = blog(1, "body", 1);
+        data["tags"][0]["updated_at"] = json!("2026-10-19T10:00:00.900Z");
+        let expected = SystemTime::UNIX_EPOCH + Duration::from_secs(1_792_404_000);
+        assert_eq!(last_modified(&data), Some(expected));
+        // 列表只使用 ETag
+        assert_eq!(last_modified(&json!({ "items": [data] })), None);
+    }
+}
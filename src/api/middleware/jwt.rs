@@ -2,11 +2,14 @@ use std::future::{ready, Ready};
 
 use actix_web::{
     dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
-    Error, HttpResponse, Result,
+    web, Error, HttpRequest, Result,
 };
 use futures_util::future::LocalBoxFuture;
 use jwt_simple::prelude::*;
 
+use super::{MetricsAllowList, METRICS_PATH};
+use crate::constants::jwt::JWT_KEY_FILE;
+
 pub struct JwtMiddleware;
 
 impl<S, B> Transform<S, ServiceRequest> for JwtMiddleware
@@ -43,17 +46,9 @@ where
     forward_ready!(service);
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
-        let method = req.method().as_str();
-        let path = req.path();
-
         // 检查是否需要JWT验证的路径和方法
-        let needs_auth = match method {
-            "POST" if path.contains("/create") => true,
-            "PUT" => true,
-            "DELETE" => true,
-            "PATCH" => true,
-            _ => false,
-        };
+        let needs_auth =
+            needs_auth(req.method().as_str(), req.path()) && !metrics_allowed(req.request());
 
         if !needs_auth {
             // 不需要验证，直接调用下一个服务
@@ -121,6 +116,49 @@ where
     }
 }
 
+// 按路径和方法判断请求是否需要JWT验证
+fn needs_auth(method: &str, path: &str) -> bool {
+    // /api/files/{id} 中的文件id
+    let file_id = path
+        .strip_prefix("/api/files/")
+        .filter(|id| id.parse::<i32>().is_ok());
+    match method {
+        _ if super::is_ops_path(path) => false,
+        "GET" if path == METRICS_PATH => true,
+        "POST" if path.contains("/create") => true,
+        // 文件列表、详情和孤立文件列表包括私有文件
+        "POST" if path == "/api/files/list" => true,
+        "GET" if path == "/api/files/orphans" => true,
+        "GET" if file_id.is_some() => true,
+        // 文件完整性检查会读取所有文件
+        "POST" if path == "/api/files/integrity" => true,
+        // 签名URL可以访问私有文件
+        "POST" if path.starts_with("/api/files/") && path.ends_with("/signed-url") => true,
+        "PUT" => true,
+        "DELETE" => true,
+        "PATCH" => true,
+        _ => false,
+    }
+}
+
+// 来源地址在允许列表中的 /metrics 请求不需要JWT
+fn metrics_allowed(req: &HttpRequest) -> bool {
+    req.path() == METRICS_PATH
+        && req.peer_addr().is_some_and(|addr| {
+            req.app_data::<web::Data<MetricsAllowList>>()
+                .is_some_and(|allow_list| allow_list.allows(addr.ip()))
+        })
+}
+
+// 请求是否携带有效的JWT令牌，用于不强制验证、但部分参数只允许管理员使用的接口
+pub fn is_authenticated(req: &HttpRequest) -> bool {
+    req.headers()
+        .get("Authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| verify_jwt_token(token).is_ok())
+}
+
 // JWT令牌验证函数
 fn verify_jwt_token(token: &str) -> Result<JWTClaims<NoCustomClaims>, jwt_simple::Error> {
     // 从文件读取JWT密钥（与用户服务使用相同的密钥）
@@ -132,10 +170,8 @@ fn verify_jwt_token(token: &str) -> Result<JWTClaims<NoCustomClaims>, jwt_simple
 
 // 从文件读取JWT密钥（与用户服务保持一致）
 fn get_jwt_key_from_file() -> Result<HS256Key, jwt_simple::Error> {
-    let key_file_path = "./jwt_key.bin";
-
-    // 读取密钥文件
-    let key_bytes = std::fs::read(key_file_path)
+    // 读取密钥文件，每次读取以便轮换密钥后立即生效
+    let key_bytes = std::fs::read(JWT_KEY_FILE)
         .map_err(|e| jwt_simple::Error::msg(format!("Failed to read JWT key file: {}", e)))?;
 
     Ok(HS256Key::from_bytes(&key_bytes))
@@ -148,3 +184,64 @@ pub fn generate_jwt_token() -> Result<String, jwt_simple::Error> {
     let claims = Claims::create(Duration::from_hours(24)); // 24小时有效期
     key.authenticate(claims)
 }
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{test::TestRequest, web};
+
+    use super::{metrics_allowed, needs_auth, MetricsAllowList};
+
+    #[test]
+    fn private_file_routes_require_auth() {
+        assert!(needs_auth("POST", "/api/files/list"));
+        assert!(needs_auth("GET", "/api/files/42"));
+        assert!(needs_auth("GET", "/api/files/orphans"));
+        assert!(needs_auth("POST", "/api/files/42/signed-url"));
+        assert!(needs_auth("PATCH", "/api/files/42/visibility"));
+    }
+
+    #[test]
+    fn public_routes_skip_auth() {
+        assert!(!needs_auth("GET", "/static/abc"));
+        assert!(!needs_auth("GET", "/private/abc.png"));
+        assert!(!needs_auth("POST", "/api/files/upload"));
+        assert!(!needs_auth("GET", "/api/files/uploads/abc"));
+        assert!(!needs_auth("POST", "/api/blogs/list"));
+        assert!(!needs_auth("GET", "/api/blogs/42"));
+    }
+
+    #[test]
+    fn metrics_require_auth_unless_allowed() {
+        assert!(!needs_auth("GET", "/healthz"));
+        assert!(!needs_auth("GET", "/readyz"));
+        assert!(needs_auth("GET", "/metrics"));
+
+        let allow_list = web::Data::new(MetricsAllowList(vec!["127.0.0.1".parse().unwrap()]));
+        let request = |peer: &str| {
+            TestRequest::get()
+                .uri("/metrics")
+                .peer_addr(peer.parse().unwrap())
+                .app_data(allow_list.clone())
+                .to_http_request()
+        };
+        assert!(metrics_allowed(&request("127.0.0.1:50000")));
+        assert!(metrics_allowed(&request("[::ffff:127.0.0.1]:50000")));
+        assert!(!metrics_allowed(&request("10.0.0.8:50000")));
+
+        // 伪造的转发头不影响判断
+        let forwarded = TestRequest::get()
+            .uri("/metrics")
+            .peer_addr("10.0.0.8:50000".parse().unwrap())
+            .insert_header(("X-Forwarded-For", "127.0.0.1"))
+            .app_data(allow_list.clone())
+            .to_http_request();
+        assert!(!metrics_allowed(&forwarded));
+
+        let other_path = TestRequest::get()
+            .uri("/api/blogs/42")
+            .peer_addr("127.0.0.1:50000".parse().unwrap())
+            .app_data(allow_list)
+            .to_http_request();
+        assert!(!metrics_allowed(&other_path));
+    }
+}
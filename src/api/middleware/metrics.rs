@@ -0,0 +1,70 @@
+use std::future::{ready, Ready};
+use std::time::Instant;
+
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    Error,
+};
+use futures_util::future::LocalBoxFuture;
+
+use crate::metrics::{HTTP_REQUESTS_TOTAL, HTTP_REQUEST_DURATION};
+
+// 按路由模板和状态码记录请求数和耗时，未匹配路由的请求统一记为 unmatched，避免标签数量无限增长
+pub struct RequestMetrics;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestMetrics
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RequestMetricsService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestMetricsService { service }))
+    }
+}
+
+pub struct RequestMetricsService<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestMetricsService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let start = Instant::now();
+        let method = req.method().to_string();
+        let route = req
+            .match_pattern()
+            .unwrap_or_else(|| "unmatched".to_string());
+        let fut = self.service.call(req);
+        Box::pin(async move {
+            let res = fut.await;
+            // 中间件返回的错误（如认证失败）按错误对应的状态码记录
+            let status = match &res {
+                Ok(res) => res.status(),
+                Err(e) => e.as_response_error().status_code(),
+            };
+            let labels = [method.as_str(), route.as_str(), status.as_str()];
+            HTTP_REQUESTS_TOTAL.with_label_values(&labels).inc();
+            HTTP_REQUEST_DURATION
+                .with_label_values(&labels)
+                .observe(start.elapsed().as_secs_f64());
+            res
+        })
+    }
+}
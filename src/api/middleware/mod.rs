@@ -1,14 +1,41 @@
+pub mod conditional;
 pub mod jwt;
+pub mod metrics;
 
+pub use conditional::ConditionalGet;
 pub use jwt::{generate_jwt_token, JwtMiddleware};
+pub use metrics::RequestMetrics;
 
 // ServiceContextMaintenanceCheck middleware (existing)
 use actix_web::{
     dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
-    Error,
+    web, Error,
 };
 use futures_util::future::LocalBoxFuture;
 use std::future::{ready, Ready};
+use std::net::IpAddr;
+
+use crate::domain::services::service_context::ServiceContextService;
+
+// 存活、就绪探针，跳过认证和维护模式检查
+pub fn is_ops_path(path: &str) -> bool {
+    matches!(path, "/healthz" | "/readyz")
+}
+
+// 指标路径，跳过维护模式检查；来源地址不在 MetricsAllowList 中时需要JWT
+pub const METRICS_PATH: &str = "/metrics";
+
+// 不需要JWT即可访问 /metrics 的来源地址，对应配置 app.metrics_allow_ips
+#[derive(Debug, Clone, Default)]
+pub struct MetricsAllowList(pub Vec<IpAddr>);
+
+impl MetricsAllowList {
+    // 按连接的对端地址判断，不信任 X-Forwarded-For 等可伪造的请求头
+    pub fn allows(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        self.0.iter().any(|allowed| allowed.to_canonical() == ip)
+    }
+}
 
 pub struct ServiceContextMaintenanceCheck;
 
@@ -46,6 +73,20 @@ where
     forward_ready!(service);
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
+        if is_ops_path(req.path()) || req.path() == METRICS_PATH {
+            return Box::pin(self.service.call(req));
+        }
+        // 维护模式下只有携带有效JWT的管理员可以访问
+        let maintenance = req
+            .app_data::<web::Data<dyn ServiceContextService>>()
+            .is_some_and(|service| service.is_maintenance_active());
+        if maintenance && !jwt::is_authenticated(req.request()) {
+            return Box::pin(async move {
+                Err(actix_web::error::ErrorServiceUnavailable(
+                    "Service is under maintenance",
+                ))
+            });
+        }
         let fut = self.service.call(req);
         Box::pin(async move {
             let res = fut.await?;
@@ -1,3 +1,5 @@
+pub mod cache;
 pub mod controllers;
 pub mod dto;
-pub mod middleware;
\ No newline at end of file
+pub mod middleware;
+pub mod precondition;
\ No newline at end of file
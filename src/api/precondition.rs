@@ -0,0 +1,87 @@
+use actix_web::{
+    http::header::{ETag, EntityTag, IfMatch},
+    HttpResponse,
+};
+use serde::Serialize;
+
+use crate::api::{dto::response::ApiResponse, middleware::conditional::entity_tag};
+
+// 资源在 GET /api/{类型}/{id} 响应中的 ETag，由 id、version 和 updated_at（包括关联的标签）生成
+pub fn current_etag<T: Serialize>(current: &T) -> EntityTag {
+    entity_tag(&serde_json::to_value(current).unwrap_or_default())
+}
+
+// If-Match 与资源当前的 ETag 一致时返回 true，`*` 匹配任何已存在的资源
+pub fn if_match<T: Serialize>(if_match: &IfMatch, current: &T) -> bool {
+    match if_match {
+        IfMatch::Any => true,
+        IfMatch::Items(tags) => {
+            let etag = current_etag(current);
+            tags.iter().any(|tag| tag.strong_eq(&etag))
+        }
+    }
+}
+
+// 版本冲突时返回 409，data 为服务端当前内容（包括 version），ETag 可直接用于重新提交
+pub fn conflict<T: Serialize>(message: &str, current: T) -> HttpResponse {
+    HttpResponse::Conflict()
+        .insert_header(ETag(current_etag(&current)))
+        .json(ApiResponse {
+            code: 409,
+            message: message.to_string(),
+            data: current,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{
+        body::to_bytes,
+        http::{
+            header::{self, EntityTag, IfMatch},
+            StatusCode,
+        },
+    };
+    use serde_json::json;
+
+    use super::{conflict, current_etag, if_match};
+
+    fn note(version: i32) -> serde_json::Value {
+        json!({
+            "id": 1,
+            "body": "hello",
+            "tags": [],
+            "updated_at": "2026-10-19T09:00:00Z",
+            "version": version,
+        })
+    }
+
+    #[test]
+    fn if_match_compares_current_version() {
+        let read = current_etag(&note(1));
+        assert!(if_match(&IfMatch::Items(vec![read.clone()]), &note(1)));
+        assert!(!if_match(&IfMatch::Items(vec![read.clone()]), &note(2)));
+        assert!(if_match(&IfMatch::Any, &note(2)));
+        // 弱 ETag 不能用于前置条件
+        let weak = EntityTag::new_weak(read.tag().to_string());
+        assert!(!if_match(&IfMatch::Items(vec![weak]), &note(1)));
+    }
+
+    #[actix_web::test]
+    async fn conflict_returns_current_content() {
+        let response = conflict("Note has been modified", note(3));
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let etag = response
+            .headers()
+            .get(header::ETAG)
+            .unwrap()
+            .to_str()
+            .unwrap();
+        assert_eq!(etag, current_etag(&note(3)).to_string());
+
+        let body = to_bytes(response.into_body()).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["code"], 409);
+        assert_eq!(body["data"]["version"], 3);
+    }
+}
@@ -0,0 +1,574 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use zwj_blog::{
+    container::Container,
+    domain::{
+        error::CommonError,
+        models::{
+            blog::{Blog, CreateBlog},
+            note::{CreateNote, Note},
+            snippet::{CreateSnippet, Snippet},
+            tag::{CreateTag, Tag},
+        },
+        repositories::{
+            blog::BlogQueryParams, repository::ResultPaging, snippet::SnippetQueryParams,
+        },
+    },
+};
+
+const EXPORT_VERSION: u32 = 1;
+const PAGE_SIZE: i64 = 100;
+
+// 导出文件格式，标签通过 slug 关联，导入时重新分配 id
+#[derive(Serialize, Deserialize)]
+pub struct ContentExport {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub tags: Vec<Tag>,
+    pub blogs: Vec<Blog>,
+    pub notes: Vec<Note>,
+    pub snippets: Vec<Snippet>,
+}
+
+impl ContentExport {
+    pub fn summary(&self) -> String {
+        format!(
+            "{} tags, {} blogs, {} notes, {} snippets",
+            self.tags.len(),
+            self.blogs.len(),
+            self.notes.len(),
+            self.snippets.len()
+        )
+    }
+}
+
+#[derive(Default)]
+pub struct ImportReport {
+    created: [usize; 4],
+    skipped: [usize; 4],
+}
+
+impl fmt::Display for ImportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kinds = ["tags", "blogs", "notes", "snippets"];
+        for (i, kind) in kinds.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(
+                f,
+                "{}: {} created, {} skipped",
+                kind, self.created[i], self.skipped[i]
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// 较早的导出文件中标签没有时间字段，反序列化为默认值，此时使用导入时间
+fn exported_time(time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    Some(time).filter(|time| time.timestamp() > 0)
+}
+
+// 导出和导入用到的内容操作，由 Container 中的服务实现
+#[async_trait]
+pub trait ContentStore: Sync {
+    async fn tags(&self) -> Result<Vec<Tag>, CommonError>;
+    async fn create_tag(&self, tag: CreateTag) -> Result<Tag, CommonError>;
+    // 按游标分页读取所有博客，包括未发布的
+    async fn blogs(&self, cursor: Option<String>) -> Result<ResultPaging<Blog>, CommonError>;
+    async fn blog_exists(&self, slug: &str) -> Result<bool, CommonError>;
+    async fn create_blog(&self, blog: CreateBlog) -> Result<Blog, CommonError>;
+    async fn notes(&self) -> Result<Vec<Note>, CommonError>;
+    async fn create_note(&self, note: CreateNote) -> Result<Note, CommonError>;
+    async fn snippets(&self, cursor: Option<String>) -> Result<ResultPaging<Snippet>, CommonError>;
+    async fn snippet_exists(&self, slug: &str) -> Result<bool, CommonError>;
+    async fn create_snippet(&self, snippet: CreateSnippet) -> Result<Snippet, CommonError>;
+}
+
+#[async_trait]
+impl ContentStore for Container {
+    async fn tags(&self) -> Result<Vec<Tag>, CommonError> {
+        self.tag_service.get_all(None).await
+    }
+
+    async fn create_tag(&self, tag: CreateTag) -> Result<Tag, CommonError> {
+        self.tag_service.create(tag).await
+    }
+
+    async fn blogs(&self, cursor: Option<String>) -> Result<ResultPaging<Blog>, CommonError> {
+        self.blog_service
+            .list(BlogQueryParams {
+                limit: Some(PAGE_SIZE),
+                offset: None,
+                cursor,
+                with_total: Some(false),
+                title: None,
+                slug: None,
+                published: None,
+                tags: None,
+                order_by: None,
+                order: None,
+            })
+            .await
+    }
+
+    async fn blog_exists(&self, slug: &str) -> Result<bool, CommonError> {
+        Ok(self.blog_service.get_by_slug(slug).await?.is_some())
+    }
+
+    async fn create_blog(&self, blog: CreateBlog) -> Result<Blog, CommonError> {
+        self.blog_service.create(blog).await
+    }
+
+    async fn notes(&self) -> Result<Vec<Note>, CommonError> {
+        self.note_service.get_all().await
+    }
+
+    async fn create_note(&self, note: CreateNote) -> Result<Note, CommonError> {
+        self.note_service.create(note).await
+    }
+
+    async fn snippets(&self, cursor: Option<String>) -> Result<ResultPaging<Snippet>, CommonError> {
+        self.snippet_service
+            .list(SnippetQueryParams {
+                limit: Some(PAGE_SIZE),
+                offset: None,
+                cursor,
+                with_total: Some(false),
+                title: None,
+                slug: None,
+                published: None,
+                tags: None,
+                order_by: None,
+                order: None,
+            })
+            .await
+    }
+
+    async fn snippet_exists(&self, slug: &str) -> Result<bool, CommonError> {
+        Ok(self.snippet_service.get_by_slug(slug).await?.is_some())
+    }
+
+    async fn create_snippet(&self, snippet: CreateSnippet) -> Result<Snippet, CommonError> {
+        self.snippet_service.create(snippet).await
+    }
+}
+
+pub async fn export(store: &impl ContentStore) -> Result<ContentExport, String> {
+    let tags = store.tags().await.map_err(|e| e.to_string())?;
+
+    let mut blogs = Vec::new();
+    let mut cursor = None;
+    loop {
+        let page = store.blogs(cursor).await.map_err(|e| e.to_string())?;
+        blogs.extend(page.items);
+        match page.next_cursor {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+
+    let mut snippets = Vec::new();
+    let mut cursor = None;
+    loop {
+        let page = store.snippets(cursor).await.map_err(|e| e.to_string())?;
+        snippets.extend(page.items);
+        match page.next_cursor {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+
+    let notes = store.notes().await.map_err(|e| e.to_string())?;
+
+    Ok(ContentExport {
+        version: EXPORT_VERSION,
+        exported_at: Utc::now(),
+        tags,
+        blogs,
+        notes,
+        snippets,
+    })
+}
+
+// 已存在的内容（相同 slug，笔记为相同正文）会被跳过；保留导出的创建和更新时间
+pub async fn import(
+    store: &impl ContentStore,
+    export: ContentExport,
+) -> Result<ImportReport, String> {
+    if export.version != EXPORT_VERSION {
+        return Err(format!("Unsupported export version {}", export.version));
+    }
+    let mut report = ImportReport::default();
+
+    let mut tag_ids: HashMap<String, i32> = store
+        .tags()
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|tag| (tag.slug, tag.id))
+        .collect();
+    for tag in export.tags {
+        if tag_ids.contains_key(&tag.slug) {
+            report.skipped[0] += 1;
+            continue;
+        }
+        let created = store
+            .create_tag(CreateTag {
+                name: tag.name,
+                slug: tag.slug,
+                tag_type: tag.tag_type,
+                icon: tag.icon,
+                icon_dark: tag.icon_dark,
+                created_at: exported_time(tag.created_at),
+                updated_at: exported_time(tag.updated_at),
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+        tag_ids.insert(created.slug, created.id);
+        report.created[0] += 1;
+    }
+    let map_tags = |tags: &[Tag]| -> Vec<i32> {
+        tags.iter()
+            .filter_map(|tag| tag_ids.get(&tag.slug).copied())
+            .collect()
+    };
+
+    for blog in export.blogs {
+        if store
+            .blog_exists(&blog.slug)
+            .await
+            .map_err(|e| e.to_string())?
+        {
+            report.skipped[1] += 1;
+            continue;
+        }
+        store
+            .create_blog(CreateBlog {
+                tag_ids: map_tags(&blog.tags),
+                title: blog.title,
+                slug: blog.slug,
+                description: blog.description,
+                body: blog.body,
+                cover: blog.cover,
+                author: blog.author,
+                published: blog.published,
+                created_at: Some(blog.created_at),
+                updated_at: Some(blog.updated_at),
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+        report.created[1] += 1;
+    }
+
+    let existing_notes: Vec<String> = store
+        .notes()
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|note| note.body)
+        .collect();
+    for note in export.notes {
+        if existing_notes.contains(&note.body) {
+            report.skipped[2] += 1;
+            continue;
+        }
+        store
+            .create_note(CreateNote {
+                tag_ids: map_tags(&note.tags),
+                body: note.body,
+                published: note.published,
+                created_at: Some(note.created_at),
+                updated_at: Some(note.updated_at),
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+        report.created[2] += 1;
+    }
+
+    for snippet in export.snippets {
+        if store
+            .snippet_exists(&snippet.slug)
+            .await
+            .map_err(|e| e.to_string())?
+        {
+            report.skipped[3] += 1;
+            continue;
+        }
+        store
+            .create_snippet(CreateSnippet {
+                tag_ids: map_tags(&snippet.tags),
+                title: snippet.title,
+                slug: snippet.slug,
+                description: snippet.description,
+                body: snippet.body,
+                published: snippet.published,
+                created_at: Some(snippet.created_at),
+                updated_at: Some(snippet.updated_at),
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+        report.created[3] += 1;
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use chrono::{DateTime, TimeZone, Utc};
+    use zwj_blog::domain::{
+        error::CommonError,
+        models::{
+            blog::{Blog, CreateBlog},
+            note::{CreateNote, Note},
+            snippet::{CreateSnippet, Snippet},
+            tag::{CreateTag, Tag, TagType},
+        },
+        repositories::repository::ResultPaging,
+    };
+
+    use super::{export, import, ContentExport, ContentStore};
+
+    // 内存中的内容，博客和代码片段每页返回一条，用于覆盖游标翻页
+    #[derive(Default)]
+    struct MemoryContent {
+        tags: Mutex<Vec<Tag>>,
+        blogs: Mutex<Vec<Blog>>,
+        notes: Mutex<Vec<Note>>,
+        snippets: Mutex<Vec<Snippet>>,
+    }
+
+    impl MemoryContent {
+        fn tags_of(&self, ids: &[i32]) -> Vec<Tag> {
+            let tags = self.tags.lock().unwrap();
+            tags.iter()
+                .filter(|t| ids.contains(&t.id))
+                .cloned()
+                .collect()
+        }
+    }
+
+    fn page<T: Clone>(items: &[T], cursor: Option<String>) -> ResultPaging<T> {
+        let index: usize = cursor.map_or(0, |c| c.parse().unwrap());
+        ResultPaging {
+            total: None,
+            items: items.get(index).cloned().into_iter().collect(),
+            next_cursor: (index + 1 < items.len()).then(|| (index + 1).to_string()),
+        }
+    }
+
+    fn time(created: Option<DateTime<Utc>>) -> DateTime<Utc> {
+        created.unwrap_or_else(Utc::now)
+    }
+
+    #[async_trait]
+    impl ContentStore for MemoryContent {
+        async fn tags(&self) -> Result<Vec<Tag>, CommonError> {
+            Ok(self.tags.lock().unwrap().clone())
+        }
+
+        async fn create_tag(&self, tag: CreateTag) -> Result<Tag, CommonError> {
+            let mut tags = self.tags.lock().unwrap();
+            let tag = Tag {
+                id: tags.len() as i32 + 100,
+                name: tag.name,
+                slug: tag.slug,
+                tag_type: tag.tag_type,
+                icon: tag.icon,
+                icon_dark: tag.icon_dark,
+                created_at: time(tag.created_at),
+                updated_at: time(tag.updated_at),
+                version: 1,
+            };
+            tags.push(tag.clone());
+            Ok(tag)
+        }
+
+        async fn blogs(&self, cursor: Option<String>) -> Result<ResultPaging<Blog>, CommonError> {
+            Ok(page(&self.blogs.lock().unwrap(), cursor))
+        }
+
+        async fn blog_exists(&self, slug: &str) -> Result<bool, CommonError> {
+            Ok(self.blogs.lock().unwrap().iter().any(|b| b.slug == slug))
+        }
+
+        async fn create_blog(&self, blog: CreateBlog) -> Result<Blog, CommonError> {
+            let tags = self.tags_of(&blog.tag_ids);
+            let mut blogs = self.blogs.lock().unwrap();
+            let blog = Blog {
+                id: blogs.len() as i32 + 1,
+                title: blog.title,
+                slug: blog.slug,
+                description: blog.description,
+                body: blog.body,
+                cover: blog.cover,
+                author: blog.author,
+                published: blog.published,
+                tags,
+                attachments: Vec::new(),
+                created_at: time(blog.created_at),
+                updated_at: time(blog.updated_at),
+                version: 1,
+            };
+            blogs.push(blog.clone());
+            Ok(blog)
+        }
+
+        async fn notes(&self) -> Result<Vec<Note>, CommonError> {
+            Ok(self.notes.lock().unwrap().clone())
+        }
+
+        async fn create_note(&self, note: CreateNote) -> Result<Note, CommonError> {
+            let tags = self.tags_of(&note.tag_ids);
+            let mut notes = self.notes.lock().unwrap();
+            let note = Note {
+                id: notes.len() as i32 + 1,
+                body: note.body,
+                published: note.published,
+                tags,
+                created_at: time(note.created_at),
+                updated_at: time(note.updated_at),
+                version: 1,
+            };
+            notes.push(note.clone());
+            Ok(note)
+        }
+
+        async fn snippets(
+            &self,
+            cursor: Option<String>,
+        ) -> Result<ResultPaging<Snippet>, CommonError> {
+            Ok(page(&self.snippets.lock().unwrap(), cursor))
+        }
+
+        async fn snippet_exists(&self, slug: &str) -> Result<bool, CommonError> {
+            Ok(self.snippets.lock().unwrap().iter().any(|s| s.slug == slug))
+        }
+
+        async fn create_snippet(&self, snippet: CreateSnippet) -> Result<Snippet, CommonError> {
+            let tags = self.tags_of(&snippet.tag_ids);
+            let mut snippets = self.snippets.lock().unwrap();
+            let snippet = Snippet {
+                id: snippets.len() as i32 + 1,
+                title: snippet.title,
+                slug: snippet.slug,
+                description: snippet.description,
+                body: snippet.body,
+                published: snippet.published,
+                tags,
+                attachments: Vec::new(),
+                created_at: time(snippet.created_at),
+                updated_at: time(snippet.updated_at),
+                version: 1,
+            };
+            snippets.push(snippet.clone());
+            Ok(snippet)
+        }
+    }
+
+    async fn source() -> MemoryContent {
+        let at = Utc.with_ymd_and_hms(2025, 3, 1, 8, 0, 0).unwrap();
+        let content = MemoryContent::default();
+        let rust = content
+            .create_tag(CreateTag {
+                name: "Rust".to_string(),
+                slug: "rust".to_string(),
+                tag_type: TagType::All,
+                icon: Some("/static/rust.png".to_string()),
+                icon_dark: None,
+                created_at: Some(at),
+                updated_at: Some(at),
+            })
+            .await
+            .unwrap();
+        for slug in ["first-post", "draft-post"] {
+            content
+                .create_blog(CreateBlog {
+                    title: slug.to_string(),
+                    slug: slug.to_string(),
+                    description: String::new(),
+                    body: format!("# {}", slug),
+                    cover: None,
+                    author: None,
+                    published: slug == "first-post",
+                    tag_ids: vec![rust.id],
+                    created_at: Some(at),
+                    updated_at: Some(at + chrono::Duration::days(1)),
+                })
+                .await
+                .unwrap();
+        }
+        content
+            .create_note(CreateNote {
+                body: "note".to_string(),
+                published: true,
+                tag_ids: vec![rust.id],
+                created_at: Some(at),
+                updated_at: Some(at),
+            })
+            .await
+            .unwrap();
+        content
+            .create_snippet(CreateSnippet {
+                title: "snippet".to_string(),
+                slug: "snippet".to_string(),
+                description: String::new(),
+                body: "fn main() {}".to_string(),
+                published: false,
+                tag_ids: vec![],
+                created_at: Some(at),
+                updated_at: Some(at),
+            })
+            .await
+            .unwrap();
+        content
+    }
+
+    #[actix_web::test]
+    async fn export_import_round_trip() {
+        let exported = export(&source().await).await.unwrap();
+        assert_eq!(exported.summary(), "1 tags, 2 blogs, 1 notes, 1 snippets");
+
+        // 经过 JSON 序列化后导入到空库，再次导出的内容与原内容一致
+        let json = serde_json::to_string(&exported).unwrap();
+        let target = MemoryContent::default();
+        let parsed: ContentExport = serde_json::from_str(&json).unwrap();
+        let report = import(&target, parsed).await.unwrap();
+        assert_eq!(report.created, [1, 2, 1, 1]);
+        assert_eq!(report.skipped, [0, 0, 0, 0]);
+
+        let imported = export(&target).await.unwrap();
+        let mut expected = serde_json::to_value(&exported).unwrap();
+        let mut actual = serde_json::to_value(&imported).unwrap();
+        // 导入时重新分配 id，标签 id 不同
+        for value in [&mut expected, &mut actual] {
+            value["exported_at"] = serde_json::Value::Null;
+            for tag in value["tags"].as_array_mut().unwrap() {
+                tag["id"] = serde_json::Value::Null;
+            }
+            for kind in ["blogs", "notes"] {
+                for item in value[kind].as_array_mut().unwrap() {
+                    for tag in item["tags"].as_array_mut().unwrap() {
+                        tag["id"] = serde_json::Value::Null;
+                    }
+                }
+            }
+        }
+        assert_eq!(actual, expected);
+
+        // 再次导入时全部跳过
+        let parsed: ContentExport = serde_json::from_str(&json).unwrap();
+        let report = import(&target, parsed).await.unwrap();
+        assert_eq!(report.created, [0, 0, 0, 0]);
+        assert_eq!(report.skipped, [1, 2, 1, 1]);
+    }
+}
@@ -0,0 +1,232 @@
+mod content;
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use zwj_blog::{
+    config::AppConfig, container::Container, domain::models::service_context::ServiceContext,
+    infrastructure::databases::postgresql::run_migrations, logging,
+};
+
+// 运维管理命令，与服务使用相同的配置和 Container
+#[derive(Parser)]
+#[command(
+    name = "zwj_blog-admin",
+    about = "Administration commands for zwj_blog",
+    after_help = "The project has no search index, so there is no reindex command."
+)]
+struct Cli {
+    /// Configuration file, defaults to config.yaml
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Apply pending database migrations
+    Migrate,
+    /// Generate a new JWT signing key; issued tokens stop working immediately
+    RotateJwtKey,
+    /// Manage wallets allowed to sign in
+    Admins {
+        #[command(subcommand)]
+        command: AdminsCommand,
+    },
+    /// Turn maintenance mode on or off, or show its state
+    Maintenance {
+        #[command(subcommand)]
+        command: MaintenanceCommand,
+    },
+    /// Recompute SHA-256 hashes of files uploaded before the hash migration; run after `migrate`
+    BackfillHashes,
+    /// Delete uploads that are not referenced by any content
+    GcUploads {
+        /// Only list orphaned uploads without deleting them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Export tags, blogs, notes and snippets as JSON
+    Export {
+        /// Output file, defaults to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Import content exported by `export`, skipping entries that already exist
+    Import {
+        /// File written by `export`
+        file: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum AdminsCommand {
+    /// List wallets from the config file and the database
+    List,
+    /// Add a wallet to the database whitelist
+    Add { address: String },
+    /// Remove a wallet from the database whitelist
+    Remove { address: String },
+}
+
+#[derive(Subcommand)]
+enum MaintenanceCommand {
+    On,
+    Off,
+    Status,
+}
+
+#[actix_web::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let config = match AppConfig::load(cli.config.as_deref()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Failed to load configuration: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let _tracing = logging::init_tracing(&config);
+
+    match run(cli.command, &config).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(command: Command, config: &AppConfig) -> Result<(), String> {
+    // 迁移使用单独的连接，不需要创建 Container
+    if let Command::Migrate = command {
+        let applied = run_migrations(config).map_err(|e| e.to_string())?;
+        if applied.is_empty() {
+            println!("Database is up to date");
+        }
+        for version in applied {
+            println!("Applied {}", version);
+        }
+        return Ok(());
+    }
+
+    let container = Container::new(config);
+    match command {
+        Command::Migrate => unreachable!(),
+        Command::RotateJwtKey => {
+            container
+                .user_service
+                .rotate_jwt_key()
+                .await
+                .map_err(|e| e.to_string())?;
+            println!("JWT key rotated, existing tokens are no longer valid");
+        }
+        Command::Admins { command } => admins(&container, command).await?,
+        Command::Maintenance { command } => {
+            let service = &container.service_context_service;
+            let maintenance = match command {
+                MaintenanceCommand::On => Some(true),
+                MaintenanceCommand::Off => Some(false),
+                MaintenanceCommand::Status => None,
+            };
+            let context = match maintenance {
+                Some(maintenance) => service.update(ServiceContext { id: 1, maintenance }),
+                None => service.get_service_context(),
+            };
+            println!(
+                "Maintenance mode is {}",
+                if context.maintenance { "on" } else { "off" }
+            );
+        }
+        Command::BackfillHashes => {
+            let count = container
+                .file_service
+                .backfill_hashes(&|| false)
+                .await
+                .map_err(|e| e.to_string())?;
+            println!("Backfilled SHA-256 hashes for {} files", count);
+        }
+        Command::GcUploads { dry_run } => {
+            let file_service = &container.file_service;
+            let files = if dry_run {
+                file_service.find_orphans().await
+            } else {
+                file_service.cleanup_orphans().await
+            }
+            .map_err(|e| e.to_string())?;
+            for file in &files {
+                println!("{}\t{}", file.id, file.file_path);
+            }
+            println!(
+                "{} {} orphaned uploads",
+                if dry_run { "Found" } else { "Deleted" },
+                files.len()
+            );
+        }
+        Command::Export { output } => {
+            let export = content::export(&container).await?;
+            let json = serde_json::to_string_pretty(&export).map_err(|e| e.to_string())?;
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, json).map_err(|e| e.to_string())?;
+                    eprintln!("{} written to {}", export.summary(), path.display());
+                }
+                None => println!("{}", json),
+            }
+        }
+        Command::Import { file } => {
+            let json = std::fs::read_to_string(&file).map_err(|e| e.to_string())?;
+            let export = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+            let report = content::import(&container, export).await?;
+            println!("{}", report);
+        }
+    }
+    Ok(())
+}
+
+async fn admins(container: &Container, command: AdminsCommand) -> Result<(), String> {
+    let user_service = &container.user_service;
+    match command {
+        AdminsCommand::List => {
+            let wallets = user_service
+                .list_admin_wallets()
+                .await
+                .map_err(|e| e.to_string())?;
+            for wallet in wallets {
+                println!(
+                    "{}\t{}",
+                    wallet.address,
+                    serde_json::to_value(wallet.source)
+                        .ok()
+                        .and_then(|v| v.as_str().map(str::to_string))
+                        .unwrap_or_default()
+                );
+            }
+        }
+        AdminsCommand::Add { address } => {
+            let added = user_service
+                .add_admin_wallet(address.clone())
+                .await
+                .map_err(|e| e.to_string())?;
+            if added {
+                println!("Added {}", address);
+            } else {
+                println!("{} is already an admin", address);
+            }
+        }
+        AdminsCommand::Remove { address } => {
+            let removed = user_service
+                .remove_admin_wallet(address.clone())
+                .await
+                .map_err(|e| e.to_string())?;
+            if removed {
+                println!("Removed {}", address);
+            } else {
+                println!("{} is not in the database whitelist", address);
+            }
+        }
+    }
+    Ok(())
+}
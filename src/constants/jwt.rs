@@ -0,0 +1,2 @@
+// JWT签名密钥文件，用户服务签发和中间件验证共用
+pub const JWT_KEY_FILE: &str = "./jwt_key.bin";
@@ -1 +1,2 @@
+pub mod jwt;
 pub mod nonce;
\ No newline at end of file
@@ -1,4 +1,5 @@
 use crate::config::AppConfig;
+use crate::domain::models::file::UploadSession;
 use crate::domain::repositories::blog::BlogRepository;
 use crate::domain::repositories::file::FileRepository;
 use crate::domain::repositories::note::NoteRepository;
@@ -8,14 +9,16 @@ use crate::domain::repositories::tag::TagRepository;
 use crate::domain::repositories::todo::TodoRepository;
 use crate::domain::repositories::user::UserRepository;
 use crate::domain::services::blog::BlogService;
+use crate::domain::services::cache::CacheService;
 use crate::domain::services::file::FileService;
+use crate::domain::services::health::HealthService;
 use crate::domain::services::note::NoteService;
 use crate::domain::services::service_context::ServiceContextService;
 use crate::domain::services::snippet::SnippetService;
 use crate::domain::services::tag::TagService;
 use crate::domain::services::todo::TodoService;
 use crate::domain::services::user::UserService;
-use crate::infrastructure::databases::postgresql::db_pool;
+use crate::infrastructure::databases::postgresql::{db_pool, DBConn};
 use crate::infrastructure::repositories::blog::BlogDieselRepository;
 use crate::infrastructure::repositories::file::FileDieselRepository;
 use crate::infrastructure::repositories::note::NoteDieselRepository;
@@ -24,7 +27,11 @@ use crate::infrastructure::repositories::snippet::SnippetDieselRepository;
 use crate::infrastructure::repositories::tag::TagDieselRepository;
 use crate::infrastructure::repositories::todo::TodoDieselRepository;
 use crate::infrastructure::repositories::user::UserDieselRepository;
+use crate::infrastructure::services::cache::RedisCacheService;
+use crate::infrastructure::services::health::HealthServiceImpl;
 use crate::infrastructure::services::service_context::ServiceContextServiceImpl;
+use crate::infrastructure::storage::storage_backend;
+use crate::infrastructure::tasks::TaskSupervisor;
 use crate::services::blog::BlogServiceImpl;
 use crate::services::file::FileServiceImpl;
 use crate::services::note::NoteServiceImpl;
@@ -34,8 +41,11 @@ use crate::services::todo::TodoServiceImpl;
 use crate::services::user::UserServiceImpl;
 use redis::Client;
 use std::sync::Arc;
+use std::time::Duration;
 
 pub struct Container {
+    pub config: AppConfig,
+    pub pool: Arc<DBConn>,
     pub todo_service: Arc<dyn TodoService>,
     pub user_service: Arc<dyn UserService>,
     pub file_service: Arc<dyn FileService>,
@@ -43,50 +53,75 @@ pub struct Container {
     pub blog_service: Arc<dyn BlogService>,
     pub note_service: Arc<dyn NoteService>,
     pub snippet_service: Arc<dyn SnippetService>,
+    pub cache_service: Arc<dyn CacheService>,
     pub service_context_service: Arc<dyn ServiceContextService>,
+    pub health_service: Arc<dyn HealthService>,
+    pub task_supervisor: Arc<TaskSupervisor>,
 }
 
 impl Container {
     pub fn new(config: &AppConfig) -> Self {
         let pool = Arc::new(db_pool(config));
+        let redis_url = format!(
+            "redis://{}:{}@{}:{}/{}",
+            config.redis.username,
+            config.redis.password,
+            config.redis.host,
+            config.redis.port,
+            config.redis.db
+        );
+        let redis_client =
+            Arc::new(Client::open(redis_url.as_str()).expect("Failed to create Redis client"));
         let todo_repository: Arc<dyn TodoRepository> =
             Arc::new(TodoDieselRepository::new(pool.clone()));
         let todo_service = Arc::new(TodoServiceImpl {
             repository: todo_repository,
         });
-        let file_repository: Arc<dyn FileRepository> =
-            Arc::new(FileDieselRepository::new(pool.clone()));
+        let task_supervisor = Arc::new(TaskSupervisor::new(Duration::from_secs(
+            config.app.shutdown_grace_secs,
+        )));
+        let file_repository: Arc<dyn FileRepository> = Arc::new(FileDieselRepository::new(
+            pool.clone(),
+            storage_backend(config),
+            config.images.clone(),
+            task_supervisor.clone(),
+        ));
+
+        let cache_service: Arc<dyn CacheService> = Arc::new(RedisCacheService::new(
+            Arc::new(RedisClientRepository::new(redis_client.clone())),
+            &config.cache,
+        ));
 
-        let file_service = Arc::new(FileServiceImpl::new(file_repository));
+        let upload_session_repository: Arc<dyn RedisRepository<UploadSession>> =
+            Arc::new(RedisClientRepository::new(redis_client.clone()));
+        let file_service = Arc::new(FileServiceImpl::new(
+            file_repository,
+            upload_session_repository,
+            config.uploads.clone(),
+            cache_service.clone(),
+        ));
 
         let tag_repository: Arc<dyn TagRepository> =
             Arc::new(TagDieselRepository::new(pool.clone()));
-        let tag_service = Arc::new(TagServiceImpl::new(tag_repository));
+        let tag_service = Arc::new(TagServiceImpl::new(tag_repository, cache_service.clone()));
 
         let blog_repository: Arc<dyn BlogRepository> =
             Arc::new(BlogDieselRepository::new(pool.clone()));
-        let blog_service = Arc::new(BlogServiceImpl::new(blog_repository));
+        let blog_service = Arc::new(BlogServiceImpl::new(blog_repository, cache_service.clone()));
 
         let note_repository: Arc<dyn NoteRepository> =
             Arc::new(NoteDieselRepository::new(pool.clone()));
-        let note_service = Arc::new(NoteServiceImpl::new(note_repository));
+        let note_service = Arc::new(NoteServiceImpl::new(note_repository, cache_service.clone()));
 
         let snippet_repository: Arc<dyn SnippetRepository> =
             Arc::new(SnippetDieselRepository::new(pool.clone()));
-        let snippet_service = Arc::new(SnippetServiceImpl::new(snippet_repository));
+        let snippet_service = Arc::new(SnippetServiceImpl::new(
+            snippet_repository,
+            cache_service.clone(),
+        ));
 
-        let redis_url = format!(
-            "redis://{}:{}@{}:{}/{}",
-            config.redis.username,
-            config.redis.password,
-            config.redis.host,
-            config.redis.port,
-            config.redis.db
-        );
-        let redis_client =
-            Arc::new(Client::open(redis_url.as_str()).expect("Failed to create Redis client"));
         let redis_repository: Arc<dyn RedisRepository<String>> =
-            Arc::new(RedisClientRepository::new(redis_client));
+            Arc::new(RedisClientRepository::new(redis_client.clone()));
         let user_repository: Arc<dyn UserRepository> =
             Arc::new(UserDieselRepository::new(pool.clone()));
         let user_service = Arc::new(UserServiceImpl {
@@ -95,7 +130,10 @@ impl Container {
             auth: config.auth.address.clone(),
         });
         let service_context_service = Arc::new(ServiceContextServiceImpl::new(pool.clone()));
+        let health_service = Arc::new(HealthServiceImpl::new(pool.clone(), redis_client, config));
         Container {
+            config: config.clone(),
+            pool,
             todo_service,
             user_service,
             file_service,
@@ -103,7 +141,10 @@ impl Container {
             blog_service,
             note_service,
             snippet_service,
+            cache_service,
             service_context_service,
+            health_service,
+            task_supervisor,
         }
     }
 }
@@ -1,9 +1,18 @@
 use crate::api::controllers::blog_handler::{
-    blog_exists_handler, create_blog_handler, delete_blog_handler, get_blog_by_slug_handler,
-    get_blog_handler, get_published_blog_by_slug_handler, get_published_blogs_handler,
-    list_blogs_handler, toggle_blog_published_handler, update_blog_handler,
+    blog_exists_handler, create_blog_handler, delete_blog_handler, get_blog_archive_handler,
+    get_blog_by_slug_handler, get_blog_handler, get_published_blog_by_slug_handler,
+    get_published_blogs_handler, list_blogs_handler, toggle_blog_published_handler,
+    update_blog_handler,
 };
-use crate::api::controllers::file_handler::upload_file_handler;
+use crate::api::controllers::file_handler::{
+    check_file_integrity_handler, cleanup_orphan_files_handler, complete_upload_handler,
+    delete_file_handler, get_file_handler, get_upload_handler, init_upload_handler,
+    list_files_handler, list_orphan_files_handler, serve_file_handler, serve_signed_file_handler,
+    set_file_visibility_handler, sign_file_url_handler, upload_chunk_handler,
+    upload_file_handler,
+};
+use crate::api::controllers::health_handler::{liveness_handler, readiness_handler};
+use crate::api::controllers::metrics_handler::metrics_handler;
 use crate::api::controllers::note_handler::{
     create_note_handler, delete_note_handler, get_all_notes_handler, get_note_handler,
     list_notes_handler, note_exists_handler, toggle_note_published_handler, update_note_handler,
@@ -21,10 +30,11 @@ use crate::api::controllers::todo_handler::{
     create_todo_handler, delete_todo_handler, get_todo_handler, list_todos_handler,
 };
 use crate::api::controllers::user_handler::{get_nonce_handler, verify_signature_handler};
-use crate::api::middleware::JwtMiddleware;
+use crate::api::middleware::{JwtMiddleware, MetricsAllowList};
+use crate::api::middleware::{ConditionalGet, RequestMetrics};
 use crate::api::middleware::ServiceContextMaintenanceCheck;
 use crate::container::Container;
-use actix_files as fs;
+use actix_multipart::form::MultipartFormConfig;
 use actix_web::body::MessageBody;
 use actix_web::dev::{ServiceFactory, ServiceRequest, ServiceResponse};
 use actix_web::Error;
@@ -50,7 +60,13 @@ pub fn create_app(
     let blog_service = container.blog_service.clone();
     let note_service = container.note_service.clone();
     let snippet_service = container.snippet_service.clone();
+    let cache_service = container.cache_service.clone();
     let service_context_service = container.service_context_service.clone();
+    let health_service = container.health_service.clone();
+    let multipart_config =
+        MultipartFormConfig::default().total_limit(container.config.uploads.max_request_size);
+    // 分片上传的请求体大小
+    let payload_config = web::PayloadConfig::new(container.config.uploads.chunk_size);
 
     App::new()
         .app_data(web::Data::from(todo_service.clone()))
@@ -60,10 +76,25 @@ pub fn create_app(
         .app_data(web::Data::from(blog_service.clone()))
         .app_data(web::Data::from(note_service.clone()))
         .app_data(web::Data::from(snippet_service.clone()))
+        .app_data(web::Data::from(cache_service.clone()))
         .app_data(web::Data::from(service_context_service.clone()))
+        .app_data(web::Data::from(health_service.clone()))
+        .app_data(web::Data::from(container.pool.clone()))
+        .app_data(web::Data::new(MetricsAllowList(
+            container.config.app.metrics_allow_ips.clone(),
+        )))
+        .app_data(multipart_config)
+        .app_data(payload_config)
+        .wrap(ConditionalGet)
         .wrap(TracingLogger::default())
         .wrap(ServiceContextMaintenanceCheck)
         .wrap(JwtMiddleware)
+        .wrap(RequestMetrics)
+        // 存活和就绪探针，不需要认证，也不受维护模式影响
+        .route("/healthz", web::get().to(liveness_handler))
+        .route("/readyz", web::get().to(readiness_handler))
+        // 指标不受维护模式影响，来源地址不在 app.metrics_allow_ips 中时需要JWT
+        .route("/metrics", web::get().to(metrics_handler))
         .service(
             web::scope("/api")
                 .service(
@@ -78,7 +109,28 @@ pub fn create_app(
                         .route("/nonce", web::post().to(get_nonce_handler))
                         .route("/verify", web::post().to(verify_signature_handler)),
                 )
-                .service(web::scope("/files").route("/upload", web::post().to(upload_file_handler)))
+                .service(
+                    web::scope("/files")
+                        .route("/upload", web::post().to(upload_file_handler))
+                        .route("/uploads", web::post().to(init_upload_handler))
+                        .route("/uploads/{upload_id}", web::get().to(get_upload_handler))
+                        .route(
+                            "/uploads/{upload_id}/chunks",
+                            web::post().to(upload_chunk_handler),
+                        )
+                        .route(
+                            "/uploads/{upload_id}/complete",
+                            web::post().to(complete_upload_handler),
+                        )
+                        .route("/list", web::post().to(list_files_handler))
+                        .route("/orphans", web::get().to(list_orphan_files_handler))
+                        .route("/orphans", web::delete().to(cleanup_orphan_files_handler))
+                        .route("/integrity", web::post().to(check_file_integrity_handler))
+                        .route("/{id}", web::get().to(get_file_handler))
+                        .route("/{id}", web::delete().to(delete_file_handler))
+                        .route("/{id}/signed-url", web::post().to(sign_file_url_handler))
+                        .route("/{id}/visibility", web::patch().to(set_file_visibility_handler)),
+                )
                 .service(
                     web::scope("/tags")
                         .route("/create", web::post().to(create_tag_handler))
@@ -94,6 +146,7 @@ pub fn create_app(
                         .route("/create", web::post().to(create_blog_handler))
                         .route("/list", web::post().to(list_blogs_handler))
                         .route("/published", web::get().to(get_published_blogs_handler))
+                        .route("/archive", web::get().to(get_blog_archive_handler))
                         .route("/slug/{slug}", web::get().to(get_blog_by_slug_handler))
                         .route(
                             "/published/slug/{slug}",
@@ -138,6 +191,9 @@ pub fn create_app(
                         ),
                 ),
         )
-        // 静态文件服务器 - 提供上传的文件访问
-        .service(fs::Files::new("/static", "uploads")) //uploads是文件的映射路径
+        // 上传文件访问，从存储后端读取，支持图片变体参数
+        .route("/static/{name}", web::get().to(serve_file_handler))
+        .route("/static/{name}", web::head().to(serve_file_handler))
+        .route("/private/{name}", web::get().to(serve_signed_file_handler))
+        .route("/private/{name}", web::head().to(serve_signed_file_handler))
 }
@@ -8,7 +8,10 @@ pub struct CommonError {
 
 impl From<&str> for CommonError {
     fn from(error: &str) -> CommonError {
-        CommonError { message: error.to_string(), code: 500 }
+        CommonError {
+            message: error.to_string(),
+            code: 500,
+        }
     }
 }
 
@@ -35,7 +38,9 @@ impl std::fmt::Display for ApiError {
 
 impl actix_web::ResponseError for ApiError {
     fn error_response(&self) -> actix_web::HttpResponse {
-        actix_web::HttpResponse::InternalServerError().json(&self.0)
+        let status = actix_web::http::StatusCode::from_u16(self.0.code as u16)
+            .unwrap_or(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR);
+        actix_web::HttpResponse::build(status).json(&self.0)
     }
 }
 
@@ -45,19 +50,30 @@ pub enum RepositoryError {
     SerializationError(String),
     NotFound(String),
     ValidationError(String),
+    StorageError(String),
+    // slug 唯一约束冲突
+    SlugConflict(String),
+    // 与已有数据冲突，如相同内容的文件已以另一种可见性存在
+    Conflict(String),
 }
 
 impl Into<CommonError> for RepositoryError {
     fn into(self) -> CommonError {
+        // 校验失败属于客户端错误
+        let code = match &self {
+            RepositoryError::ValidationError(_) => 400,
+            RepositoryError::SlugConflict(_) | RepositoryError::Conflict(_) => 409,
+            _ => 500,
+        };
         let message = match self {
             RepositoryError::DatabaseError(msg) => format!("数据库错误: {}", msg),
             RepositoryError::SerializationError(msg) => format!("序列化错误: {}", msg),
             RepositoryError::NotFound(msg) => format!("未找到: {}", msg),
             RepositoryError::ValidationError(msg) => format!("验证错误: {}", msg),
+            RepositoryError::StorageError(msg) => format!("存储错误: {}", msg),
+            RepositoryError::SlugConflict(msg) => format!("slug 已存在: {}", msg),
+            RepositoryError::Conflict(msg) => format!("冲突: {}", msg),
         };
-        CommonError {
-            message,
-            code: 500,
-        }
+        CommonError { message, code }
     }
 }
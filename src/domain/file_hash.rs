@@ -0,0 +1,71 @@
+use sha2::{Digest, Sha256};
+
+pub const SHA256: &str = "sha256";
+// 迁移前上传的文件使用的算法，回填后不再使用
+pub const MD5: &str = "md5";
+
+// 增量计算内容hash，文件内容按块流式读取，不需要整体读入内存
+pub enum ContentHasher {
+    Sha256(Sha256),
+    Md5(md5::Context),
+}
+
+impl ContentHasher {
+    // 不支持的算法返回 None
+    pub fn new(algorithm: &str) -> Option<Self> {
+        match algorithm {
+            SHA256 => Some(ContentHasher::Sha256(Sha256::new())),
+            MD5 => Some(ContentHasher::Md5(md5::Context::new())),
+            _ => None,
+        }
+    }
+
+    pub fn update(&mut self, bytes: &[u8]) {
+        match self {
+            ContentHasher::Sha256(hasher) => hasher.update(bytes),
+            ContentHasher::Md5(context) => context.consume(bytes),
+        }
+    }
+
+    // 十六进制的hash
+    pub fn finalize(self) -> String {
+        match self {
+            ContentHasher::Sha256(hasher) => format!("{:x}", hasher.finalize()),
+            ContentHasher::Md5(context) => format!("{:x}", context.finalize()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ContentHasher, MD5, SHA256};
+
+    fn hash_chunks(algorithm: &str, chunks: &[&[u8]]) -> String {
+        let mut hasher = ContentHasher::new(algorithm).unwrap();
+        for chunk in chunks {
+            hasher.update(chunk);
+        }
+        hasher.finalize()
+    }
+
+    #[test]
+    fn incremental_hash_matches_whole_content() {
+        assert_eq!(
+            hash_chunks(SHA256, &[b"hello ", b"", b"world"]),
+            "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
+        );
+        assert_eq!(
+            hash_chunks(MD5, &[b"hel", b"lo world"]),
+            "5eb63bbbe01eeed093cb22bb8f5acdc3"
+        );
+        assert_eq!(
+            hash_chunks(SHA256, &[]),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_algorithm() {
+        assert!(ContentHasher::new("sha1").is_none());
+    }
+}
@@ -1,5 +1,8 @@
 pub mod constants;
 pub mod error;
+pub mod file_hash;
 pub mod models;
 pub mod repositories;
 pub mod services;
+pub mod signed_url;
+pub mod slug;
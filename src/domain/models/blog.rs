@@ -1,3 +1,4 @@
+use crate::domain::models::file::Attachment;
 use crate::domain::models::tag::Tag;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -13,8 +14,41 @@ pub struct Blog {
     pub author: Option<String>,
     pub published: bool,
     pub tags: Vec<Tag>,
+    // 封面和正文中使用的上传文件
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    // 每次更新加一；早期的导出文件中没有该字段
+    #[serde(default)]
+    pub version: i32,
+}
+
+// 列表用的博客摘要，不包含正文
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct BlogSummary {
+    pub id: i32,
+    pub title: String,
+    pub slug: String,
+    pub description: String,
+    pub cover: Option<String>,
+    pub author: Option<String>,
+    pub published: bool,
+    pub tags: Vec<Tag>,
+    // 封面和正文中使用的上传文件
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// 列表接口返回的字段范围：默认摘要，full 时包含正文
+#[derive(Clone, Copy, Deserialize, Serialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BlogFields {
+    #[default]
+    Summary,
+    Full,
 }
 
 #[derive(Clone)]
@@ -27,6 +61,9 @@ pub struct CreateBlog {
     pub author: Option<String>,
     pub published: bool,
     pub tag_ids: Vec<i32>,
+    // 导入导出的内容时保留原始时间，为空时使用数据库的当前时间
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Clone)]
@@ -39,4 +76,44 @@ pub struct UpdateBlog {
     pub author: Option<String>,
     pub published: Option<bool>,
     pub tag_ids: Option<Vec<i32>>,
+    // 期望的当前版本，不一致时不更新
+    pub version: Option<i32>,
+}
+
+// 上一篇/下一篇导航用的轻量博客信息
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct BlogNeighbor {
+    pub id: i32,
+    pub title: String,
+    pub slug: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Deserialize, Serialize, Debug, Default)]
+pub struct BlogNeighbors {
+    pub previous: Option<BlogNeighbor>,
+    pub next: Option<BlogNeighbor>,
+}
+
+// 归档页用的轻量博客信息
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct BlogArchiveEntry {
+    pub id: i32,
+    pub title: String,
+    pub slug: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct BlogArchiveMonth {
+    pub month: i32,
+    pub count: i64,
+    pub entries: Vec<BlogArchiveEntry>,
+}
+
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct BlogArchiveYear {
+    pub year: i32,
+    pub count: i64,
+    pub months: Vec<BlogArchiveMonth>,
 }
@@ -0,0 +1,74 @@
+use std::time::Duration;
+
+// 缓存条目所属的分组，分组内任一内容变更时整组失效
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CacheGroup {
+    Blogs,
+    Notes,
+    Snippets,
+    Tags,
+}
+
+impl CacheGroup {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CacheGroup::Blogs => "blogs",
+            CacheGroup::Notes => "notes",
+            CacheGroup::Snippets => "snippets",
+            CacheGroup::Tags => "tags",
+        }
+    }
+}
+
+// 可缓存的公开响应
+#[derive(Clone, Debug)]
+pub enum CacheKey {
+    PublishedBlogs { full: bool },
+    PublishedBlog { slug: String, neighbors: bool },
+    PublishedSnippets,
+    Notes,
+    Tags { tag_type: Option<String> },
+}
+
+impl CacheKey {
+    // 用于 Redis key 和指标标签
+    pub fn name(&self) -> &'static str {
+        match self {
+            CacheKey::PublishedBlogs { .. } => "published_blogs",
+            CacheKey::PublishedBlog { .. } => "published_blog",
+            CacheKey::PublishedSnippets => "published_snippets",
+            CacheKey::Notes => "notes",
+            CacheKey::Tags { .. } => "tags",
+        }
+    }
+
+    // 内容中包含标签信息，标签变更时同样失效
+    pub fn groups(&self) -> &'static [CacheGroup] {
+        match self {
+            CacheKey::PublishedBlogs { .. } | CacheKey::PublishedBlog { .. } => {
+                &[CacheGroup::Blogs, CacheGroup::Tags]
+            }
+            CacheKey::PublishedSnippets => &[CacheGroup::Snippets, CacheGroup::Tags],
+            CacheKey::Notes => &[CacheGroup::Notes, CacheGroup::Tags],
+            CacheKey::Tags { .. } => &[CacheGroup::Tags],
+        }
+    }
+
+    // 同一接口不同参数的区分部分
+    pub fn variant(&self) -> String {
+        match self {
+            CacheKey::PublishedBlogs { full } => if *full { "full" } else { "summary" }.to_string(),
+            CacheKey::PublishedBlog { slug, neighbors } => format!("{}:{}", neighbors, slug),
+            CacheKey::PublishedSnippets | CacheKey::Notes => String::new(),
+            CacheKey::Tags { tag_type } => tag_type.clone().unwrap_or_default(),
+        }
+    }
+}
+
+// 一次缓存查询的结果。写入时沿用查询时的 key，查询之后发生的失效不会被旧数据覆盖
+pub struct CacheLookup {
+    // 缓存未启用或不可用时为空，不再写入
+    pub entry: Option<String>,
+    pub ttl: Duration,
+    pub body: Option<String>,
+}
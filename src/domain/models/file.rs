@@ -1,4 +1,5 @@
-use chrono::NaiveDateTime;
+use actix_multipart::form::tempfile::TempFile;
+use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -9,12 +10,184 @@ pub struct File {
     pub upload_time: NaiveDateTime,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    // 图片变体所属的原始文件，原始文件为空
+    pub original_id: Option<i32>,
+    pub variant: Option<String>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    // 根据文件内容检测的类型
+    pub mime_type: Option<String>,
+    pub byte_size: Option<i64>,
+    pub original_filename: Option<String>,
+    // file_hash 的算法：sha256，迁移前上传的文件回填前为 md5
+    pub hash_algorithm: String,
+    // 存储中的文件名
+    pub storage_key: String,
+    pub visibility: FileVisibility,
 }
 
 #[derive(Clone, Debug)]
 pub struct CreateFile {
     pub file_hash: String,
     pub file_path: String,
+    pub original_id: Option<i32>,
+    pub variant: Option<String>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    // 根据文件内容检测的类型
+    pub mime_type: Option<String>,
+    pub byte_size: Option<i64>,
+    pub original_filename: Option<String>,
+    pub hash_algorithm: String,
+    pub storage_key: String,
+    pub visibility: FileVisibility,
+}
+
+// 通过校验的上传文件
+#[derive(Debug)]
+pub struct UploadFile {
+    pub file: TempFile,
+    pub metadata: UploadMetadata,
+}
+
+// 上传选项
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UploadOptions {
+    // 保留图片的 EXIF 等元数据，仅管理员可用
+    pub keep_metadata: bool,
+    pub visibility: FileVisibility,
+}
+
+// 上传校验得到的文件信息
+#[derive(Clone, Debug)]
+pub struct UploadMetadata {
+    pub original_filename: String,
+    pub mime_type: String,
+    // 检测类型对应的扩展名，不使用客户端文件名中的扩展名
+    pub extension: String,
+    pub byte_size: i64,
+    // 保存前清除图片的 EXIF/GPS 等元数据
+    pub strip_metadata: bool,
+    pub visibility: FileVisibility,
+}
+
+// 分片上传会话，保存在 Redis 中
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UploadSession {
+    pub upload_id: String,
+    pub file_name: String,
+    pub total_size: i64,
+    // 已接收的字节数，即下一个分片的偏移量
+    pub received: i64,
+    pub chunk_size: i64,
+    pub created_at: DateTime<Utc>,
+    // 管理员上传时可以保留图片元数据
+    #[serde(default)]
+    pub keep_metadata: bool,
+    #[serde(default)]
+    pub visibility: FileVisibility,
+}
+
+// 文件可见性：私有文件不能通过 /static 访问，只能通过签名URL访问
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileVisibility {
+    #[default]
+    Public,
+    Private,
+}
+
+impl FileVisibility {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FileVisibility::Public => "public",
+            FileVisibility::Private => "private",
+        }
+    }
+}
+
+// 私有文件的签名访问地址
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SignedUrl {
+    pub url: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+// 引用文件的内容
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileReferenceKind {
+    Blog,
+    Snippet,
+    Note,
+    Tag,
+}
+
+// 文件在内容中的用途：博客封面、正文中的文件或标签图标
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AttachmentRole {
+    Cover,
+    Inline,
+    Icon,
+}
+
+impl AttachmentRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttachmentRole::Cover => "cover",
+            AttachmentRole::Inline => "inline",
+            AttachmentRole::Icon => "icon",
+        }
+    }
+}
+
+// 内容关联的文件，包含渲染需要的地址、尺寸和类型
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Attachment {
+    pub file_id: i32,
+    pub role: AttachmentRole,
+    pub url: String,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub mime_type: Option<String>,
+    pub visibility: FileVisibility,
+}
+
+// 引用文件的内容及文件在其中的用途
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FileReference {
+    pub kind: FileReferenceKind,
+    pub id: i32,
+    pub title: String,
+    pub role: AttachmentRole,
+}
+
+// 文件详情：变体和引用位置
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FileDetail {
+    #[serde(flatten)]
+    pub file: File,
+    pub variants: Vec<File>,
+    pub references: Vec<FileReference>,
+}
+
+// 删除文件的结果，仍被引用时拒绝删除
+#[derive(Clone, Debug)]
+pub enum FileDeletion {
+    NotFound,
+    Referenced(Vec<FileReference>),
+    Deleted,
+}
+
+// 文件完整性检查结果
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct FileIntegrityReport {
+    pub checked: usize,
+    // 存储中不存在的文件
+    pub missing: Vec<File>,
+    // 内容与hash不一致的文件
+    pub corrupted: Vec<File>,
 }
 
 // API响应用的文件信息
@@ -28,3 +201,64 @@ pub struct FileInfo {
 pub struct Files {
     pub files: Vec<FileInfo>,
 }
+
+// 存储中的文件，内容在响应时按需流式读取
+#[derive(Clone, Debug)]
+pub struct FileContent {
+    pub storage_key: String,
+    pub size: u64,
+    pub content_type: String,
+    // 文件内容的hash，用作 ETag
+    pub file_hash: String,
+    pub visibility: FileVisibility,
+}
+
+// 支持生成变体的图片格式
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFormat {
+    Jpeg,
+    Png,
+    Webp,
+    Avif,
+}
+
+impl ImageFormat {
+    pub fn from_extension(extension: &str) -> Option<ImageFormat> {
+        match extension.to_ascii_lowercase().as_str() {
+            "jpg" | "jpeg" => Some(ImageFormat::Jpeg),
+            "png" => Some(ImageFormat::Png),
+            "webp" => Some(ImageFormat::Webp),
+            "avif" => Some(ImageFormat::Avif),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "jpg",
+            ImageFormat::Png => "png",
+            ImageFormat::Webp => "webp",
+            ImageFormat::Avif => "avif",
+        }
+    }
+}
+
+// 图片变体：宽度为空时保持原尺寸，格式为空时保持原格式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ImageVariant {
+    pub width: Option<u32>,
+    pub format: Option<ImageFormat>,
+}
+
+impl ImageVariant {
+    // 变体名称，如 w640.webp、orig.avif
+    pub fn name(&self, original_format: ImageFormat) -> String {
+        let size = self
+            .width
+            .map(|w| format!("w{}", w))
+            .unwrap_or_else(|| "orig".to_string());
+        let format = self.format.unwrap_or(original_format);
+        format!("{}.{}", size, format.extension())
+    }
+}
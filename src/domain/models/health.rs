@@ -0,0 +1,40 @@
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Up,
+    Down,
+}
+
+// 单个依赖的检查结果，detail 为错误信息或连接池等附加状态
+#[derive(Clone, Debug, Serialize)]
+pub struct DependencyHealth {
+    pub name: String,
+    pub status: HealthStatus,
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct HealthReport {
+    pub status: HealthStatus,
+    pub checks: Vec<DependencyHealth>,
+}
+
+impl HealthReport {
+    // 所有依赖都正常时整体为 up
+    pub fn new(checks: Vec<DependencyHealth>) -> Self {
+        let status = if checks.iter().all(|c| c.status == HealthStatus::Up) {
+            HealthStatus::Up
+        } else {
+            HealthStatus::Down
+        };
+        HealthReport { status, checks }
+    }
+
+    pub fn is_up(&self) -> bool {
+        self.status == HealthStatus::Up
+    }
+}
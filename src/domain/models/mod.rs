@@ -1,7 +1,11 @@
 pub mod blog;
+pub mod cache;
 pub mod file;
+pub mod health;
 pub mod note;
 pub mod service_context;
 pub mod snippet;
 pub mod tag;
-pub mod todo;
\ No newline at end of file
+pub mod todo;
+pub mod user;
+pub mod version;
\ No newline at end of file
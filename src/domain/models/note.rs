@@ -10,6 +10,8 @@ pub struct Note {
     pub tags: Vec<Tag>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub version: i32,
 }
 
 #[derive(Clone)]
@@ -17,6 +19,9 @@ pub struct CreateNote {
     pub body: String,
     pub published: bool,
     pub tag_ids: Vec<i32>,
+    // 导入导出的内容时保留原始时间，为空时使用数据库的当前时间
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Clone)]
@@ -24,4 +29,5 @@ pub struct UpdateNote {
     pub body: Option<String>,
     pub published: Option<bool>,
     pub tag_ids: Option<Vec<i32>>,
+    pub version: Option<i32>,
 }
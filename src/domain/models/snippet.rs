@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use crate::domain::models::file::Attachment;
 use crate::domain::models::tag::Tag;
 
 #[derive(Clone, Deserialize, Serialize, Debug)]
@@ -11,8 +12,13 @@ pub struct Snippet {
     pub body: String,
     pub published: bool,
     pub tags: Vec<Tag>,
+    // 正文中使用的上传文件
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub version: i32,
 }
 
 #[derive(Clone)]
@@ -23,6 +29,9 @@ pub struct CreateSnippet {
     pub body: String,
     pub published: bool,
     pub tag_ids: Vec<i32>,
+    // 导入导出的内容时保留原始时间，为空时使用数据库的当前时间
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Clone)]
@@ -33,4 +42,5 @@ pub struct UpdateSnippet {
     pub body: Option<String>,
     pub published: Option<bool>,
     pub tag_ids: Option<Vec<i32>>,
+    pub version: Option<i32>,
 }
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Deserialize, Serialize, Debug)]
@@ -15,6 +16,13 @@ pub struct Tag {
     pub tag_type: TagType,
     pub icon: Option<String>,
     pub icon_dark: Option<String>,
+    // 早期的导出文件中没有时间字段
+    #[serde(default)]
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub version: i32,
 }
 
 #[derive(Clone)]
@@ -24,6 +32,9 @@ pub struct CreateTag {
     pub tag_type: TagType,
     pub icon: Option<String>,
     pub icon_dark: Option<String>,
+    // 导入导出的内容时保留原始时间，为空时使用数据库的当前时间
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Clone)]
@@ -33,4 +44,5 @@ pub struct UpdateTag {
     pub tag_type: Option<TagType>,
     pub icon: Option<String>,
     pub icon_dark: Option<String>,
+    pub version: Option<i32>,
 }
@@ -0,0 +1,17 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+// 允许登录的管理员钱包，source 为 config 时来自配置文件，不能通过管理命令删除
+#[derive(Clone, Debug, Serialize)]
+pub struct AdminWallet {
+    pub address: String,
+    pub source: AdminWalletSource,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AdminWalletSource {
+    Config,
+    Database,
+}
@@ -0,0 +1,7 @@
+// 带版本前置条件的更新结果
+pub enum VersionedUpdate<T> {
+    Updated(T),
+    NotFound,
+    // 期望的版本与当前版本不一致，返回服务端当前内容
+    Conflict(T),
+}
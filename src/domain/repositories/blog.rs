@@ -2,7 +2,8 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
 use crate::domain::{
-    models::blog::{Blog, CreateBlog, UpdateBlog},
+    models::blog::{Blog, BlogArchiveYear, BlogNeighbors, BlogSummary, CreateBlog, UpdateBlog},
+    models::version::VersionedUpdate,
     repositories::repository::{
         QueryParams, RepositoryResult, ResultPaging, DEFAULT_LIMIT, DEFAULT_OFFSET,
     },
@@ -12,6 +13,8 @@ use crate::domain::{
 pub struct BlogQueryParams {
     pub limit: Option<i64>,
     pub offset: Option<i64>,
+    pub cursor: Option<String>,
+    pub with_total: Option<bool>,
     pub title: Option<String>,
     pub slug: Option<String>,
     pub published: Option<bool>,
@@ -27,17 +30,43 @@ impl QueryParams for BlogQueryParams {
     fn offset(&self) -> i64 {
         self.offset.or(DEFAULT_OFFSET).unwrap_or_default()
     }
+    fn cursor(&self) -> Option<&str> {
+        self.cursor.as_deref()
+    }
+    fn with_total(&self) -> bool {
+        self.with_total.unwrap_or(self.cursor.is_none())
+    }
 }
 
 #[async_trait]
 pub trait BlogRepository: Send + Sync {
     async fn create(&self, new_blog: &CreateBlog) -> RepositoryResult<Blog>;
     async fn list(&self, params: BlogQueryParams) -> RepositoryResult<ResultPaging<Blog>>;
+    // 与 list 相同的过滤和分页，但不查询正文
+    async fn list_summaries(
+        &self,
+        params: BlogQueryParams,
+    ) -> RepositoryResult<ResultPaging<BlogSummary>>;
     async fn get(&self, blog_id: i32) -> RepositoryResult<Option<Blog>>;
     async fn get_by_slug(&self, slug: &str) -> RepositoryResult<Option<Blog>>;
     async fn get_published(&self) -> RepositoryResult<Vec<Blog>>;
+    async fn get_published_summaries(&self) -> RepositoryResult<Vec<BlogSummary>>;
     async fn get_published_by_slug(&self, slug: &str) -> RepositoryResult<Option<Blog>>;
-    async fn update(&self, blog_id: i32, update_blog: &UpdateBlog) -> RepositoryResult<Option<Blog>>;
+    // 旧 slug 对应的已发布博客的当前 slug
+    async fn find_published_redirect(&self, old_slug: &str) -> RepositoryResult<Option<String>>;
+    // slug 已被使用，包括仍用于重定向的旧 slug
+    async fn slug_exists(&self, slug: &str) -> RepositoryResult<bool>;
+    // 按发布时间查找已发布博客的上一篇（更早）和下一篇（更新）
+    async fn get_published_neighbors(&self, blog: &Blog) -> RepositoryResult<BlogNeighbors>;
+    // 按年、月归档已发布博客，可按标签slug过滤
+    async fn get_archive(&self, tag_slug: Option<String>)
+        -> RepositoryResult<Vec<BlogArchiveYear>>;
+    // update_blog.version 与当前版本不一致时返回 Conflict
+    async fn update(
+        &self,
+        blog_id: i32,
+        update_blog: &UpdateBlog,
+    ) -> RepositoryResult<VersionedUpdate<Blog>>;
     async fn delete(&self, blog_id: i32) -> RepositoryResult<bool>;
     async fn exists(&self, blog_id: i32) -> RepositoryResult<bool>;
     async fn toggle_published(&self, blog_id: i32) -> RepositoryResult<Option<Blog>>;
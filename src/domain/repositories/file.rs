@@ -1,21 +1,120 @@
 use std::collections::HashMap;
+use std::ops::Range;
+use std::path::Path;
 
-use actix_multipart::form::tempfile::TempFile;
 use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
 
 use crate::domain::{
-    models::file::{CreateFile, File},
-    repositories::repository::RepositoryResult,
+    models::file::{
+        CreateFile, File, FileContent, FileReference, FileVisibility, ImageVariant, UploadFile,
+        UploadMetadata,
+    },
+    repositories::{
+        repository::{QueryParams, RepositoryResult, ResultPaging, DEFAULT_LIMIT, DEFAULT_OFFSET},
+        storage::ByteStream,
+    },
 };
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FileQueryParams {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub cursor: Option<String>,
+    #[serde(rename = "withTotal")]
+    pub with_total: Option<bool>,
+    // 按类型前缀过滤，如 image/
+    #[serde(rename = "mimeType")]
+    pub mime_type: Option<String>,
+}
+
+impl QueryParams for FileQueryParams {
+    fn limit(&self) -> i64 {
+        self.limit.or(DEFAULT_LIMIT).unwrap_or_default()
+    }
+    fn offset(&self) -> i64 {
+        self.offset.or(DEFAULT_OFFSET).unwrap_or_default()
+    }
+    fn cursor(&self) -> Option<&str> {
+        self.cursor.as_deref()
+    }
+    fn with_total(&self) -> bool {
+        self.with_total.unwrap_or(self.cursor.is_none())
+    }
+}
+
 #[async_trait]
 pub trait FileRepository: Send + Sync {
     // 上传文件并返回文件名和URL的映射
-    async fn save_files(&self, files: Vec<TempFile>) -> RepositoryResult<HashMap<String, String>>;
+    async fn save_files(&self, files: Vec<UploadFile>)
+        -> RepositoryResult<HashMap<String, String>>;
+
+    // 保存已计算hash的文件（如分片上传合并后的文件），与 save_files 使用相同的去重逻辑，返回URL
+    async fn save_file(
+        &self,
+        source: &Path,
+        file_hash: String,
+        metadata: UploadMetadata,
+    ) -> RepositoryResult<String>;
 
     // 根据hash查找文件记录
     async fn find_by_hash(&self, file_hash: &str) -> RepositoryResult<Option<File>>;
 
     // 创建新的文件记录
     async fn create(&self, new_file: &CreateFile) -> RepositoryResult<File>;
+
+    // 读取文件内容，name 为文件hash或存储中的文件名
+    async fn load(&self, name: &str) -> RepositoryResult<Option<FileContent>>;
+
+    // 读取图片变体，不存在时按需生成；include_private 为 false 时私有文件按不存在处理，不会生成变体
+    async fn load_variant(
+        &self,
+        name: &str,
+        variant: &ImageVariant,
+        include_private: bool,
+    ) -> RepositoryResult<Option<FileContent>>;
+
+    // 流式读取文件内容中的字节区间
+    async fn open(
+        &self,
+        content: &FileContent,
+        range: Range<u64>,
+    ) -> RepositoryResult<Option<ByteStream>>;
+
+    // 分页列出原始文件（不含图片变体）
+    async fn list(&self, params: FileQueryParams) -> RepositoryResult<ResultPaging<File>>;
+
+    async fn get(&self, file_id: i32) -> RepositoryResult<Option<File>>;
+
+    async fn list_variants(&self, original_id: i32) -> RepositoryResult<Vec<File>>;
+
+    // 查找在博客、代码片段、笔记和标签中引用该文件的位置
+    async fn find_references(&self, file: &File) -> RepositoryResult<Vec<FileReference>>;
+
+    // 查找没有被任何内容引用、且上传时间早于 uploaded_before 的原始文件
+    async fn find_orphans(&self, uploaded_before: NaiveDateTime) -> RepositoryResult<Vec<File>>;
+
+    // 删除文件及其变体的存储内容和数据库记录
+    async fn delete(&self, file_id: i32) -> RepositoryResult<bool>;
+
+    // 所有文件记录，包含图片变体
+    async fn list_all(&self) -> RepositoryResult<Vec<File>>;
+
+    // 流式读取文件在存储中的完整内容
+    async fn read(&self, file: &File) -> RepositoryResult<Option<ByteStream>>;
+
+    async fn update_hash(
+        &self,
+        file_id: i32,
+        file_hash: &str,
+        hash_algorithm: &str,
+    ) -> RepositoryResult<()>;
+
+    // 修改原始文件及其图片变体的可见性，文件不存在时返回 None
+    async fn update_visibility(
+        &self,
+        file_id: i32,
+        visibility: FileVisibility,
+    ) -> RepositoryResult<Option<File>>;
 }
@@ -4,6 +4,7 @@ pub mod note;
 pub mod redis;
 pub mod repository;
 pub mod snippet;
+pub mod storage;
 pub mod tag;
 pub mod todo;
 pub mod user;
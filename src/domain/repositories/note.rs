@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::domain::{
     models::note::{CreateNote, Note, UpdateNote},
+    models::version::VersionedUpdate,
     repositories::repository::{
         QueryParams, RepositoryResult, ResultPaging, DEFAULT_LIMIT, DEFAULT_OFFSET,
     },
@@ -12,6 +13,8 @@ use crate::domain::{
 pub struct NoteQueryParams {
     pub limit: Option<i64>,
     pub offset: Option<i64>,
+    pub cursor: Option<String>,
+    pub with_total: Option<bool>,
     pub body: Option<String>,
     pub published: Option<bool>,
     pub tags: Option<Vec<i32>>,
@@ -26,6 +29,12 @@ impl QueryParams for NoteQueryParams {
     fn offset(&self) -> i64 {
         self.offset.or(DEFAULT_OFFSET).unwrap_or_default()
     }
+    fn cursor(&self) -> Option<&str> {
+        self.cursor.as_deref()
+    }
+    fn with_total(&self) -> bool {
+        self.with_total.unwrap_or(self.cursor.is_none())
+    }
 }
 
 #[async_trait]
@@ -38,7 +47,7 @@ pub trait NoteRepository: Send + Sync {
         &self,
         note_id: i32,
         update_note: &UpdateNote,
-    ) -> RepositoryResult<Option<Note>>;
+    ) -> RepositoryResult<VersionedUpdate<Note>>;
     async fn delete(&self, note_id: i32) -> RepositoryResult<bool>;
     async fn exists(&self, note_id: i32) -> RepositoryResult<bool>;
     async fn toggle_published(&self, note_id: i32) -> RepositoryResult<Option<Note>>;
@@ -1,20 +1,37 @@
 use crate::domain::error::RepositoryError;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 pub type RepositoryResult<T> = Result<T, RepositoryError>;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ResultPaging<T> {
-    pub total: i64,
+    // 未请求总数时为空，省去额外的 count 查询
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>,
     pub items: Vec<T>,
+    // 下一页游标，没有更多数据时为空
+    #[serde(default)]
+    pub next_cursor: Option<String>,
 }
 
 pub const DEFAULT_OFFSET: Option<i64> = Some(0);
 pub const DEFAULT_LIMIT: Option<i64> = Some(25);
+// 单页最多返回的记录数，更大的 limit 按该值处理
+pub const MAX_PAGE_SIZE: i64 = 100;
 
 pub trait QueryParams: Send + Sync {
     fn limit(&self) -> i64;
     fn offset(&self) -> i64;
+    // 游标分页时传入上一页返回的 next_cursor，优先于 offset
+    fn cursor(&self) -> Option<&str> {
+        None
+    }
+    // 是否执行 count 查询返回总数
+    fn with_total(&self) -> bool {
+        true
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -31,3 +48,106 @@ impl QueryParams for QueryParamsImpl {
         self.offset.or(DEFAULT_OFFSET).unwrap_or_default()
     }
 }
+
+// 基于 (created_at, id) 的分页游标，对外编码为不透明字符串
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cursor {
+    pub created_at: DateTime<Utc>,
+    pub id: i32,
+}
+
+impl Cursor {
+    pub fn new(created_at: DateTime<Utc>, id: i32) -> Self {
+        Cursor { created_at, id }
+    }
+
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!("{}:{}", self.created_at.timestamp_micros(), self.id))
+    }
+
+    pub fn decode(value: &str) -> RepositoryResult<Cursor> {
+        let invalid = || RepositoryError::ValidationError("无效的分页游标".to_string());
+        let bytes = URL_SAFE_NO_PAD.decode(value).map_err(|_| invalid())?;
+        let raw = String::from_utf8(bytes).map_err(|_| invalid())?;
+        let (micros, id) = raw.split_once(':').ok_or_else(invalid)?;
+        let created_at = micros
+            .parse::<i64>()
+            .ok()
+            .and_then(DateTime::<Utc>::from_timestamp_micros)
+            .ok_or_else(invalid)?;
+        let id = id.parse::<i32>().map_err(|_| invalid())?;
+        Ok(Cursor { created_at, id })
+    }
+}
+
+// 实际使用的每页数量
+pub fn page_size(limit: i64) -> i64 {
+    limit.clamp(0, MAX_PAGE_SIZE)
+}
+
+// 分页查询的 LIMIT，多取一条用于判断是否还有下一页
+pub fn fetch_limit(limit: i64) -> i64 {
+    page_size(limit) + 1
+}
+
+// 查询时多取一条记录：截断到 limit，并在还有下一页时返回最后一条的游标
+pub fn take_next_cursor<T>(
+    rows: &mut Vec<T>,
+    limit: i64,
+    cursor_of: impl Fn(&T) -> Cursor,
+) -> Option<String> {
+    let limit = page_size(limit) as usize;
+    if rows.len() <= limit {
+        return None;
+    }
+    rows.truncate(limit);
+    rows.last().map(|row| cursor_of(row).encode())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::{fetch_limit, take_next_cursor, Cursor, RepositoryError, MAX_PAGE_SIZE};
+
+    #[test]
+    fn cursor_round_trip() {
+        let created_at = Utc.with_ymd_and_hms(2026, 10, 19, 8, 30, 0).unwrap()
+            + chrono::Duration::microseconds(123_456);
+        let cursor = Cursor::new(created_at, 42);
+        assert_eq!(Cursor::decode(&cursor.encode()).unwrap(), cursor);
+    }
+
+    #[test]
+    fn rejects_invalid_cursor() {
+        for value in ["", "not base64!", "MTIz", "YWJjOjE", "MTIzOmFiYw"] {
+            assert!(
+                matches!(Cursor::decode(value), Err(RepositoryError::ValidationError(_))),
+                "{value}"
+            );
+        }
+    }
+
+    #[test]
+    fn fetch_limit_is_capped() {
+        assert_eq!(fetch_limit(25), 26);
+        assert_eq!(fetch_limit(-5), 1);
+        assert_eq!(fetch_limit(MAX_PAGE_SIZE), MAX_PAGE_SIZE + 1);
+        assert_eq!(fetch_limit(i64::MAX), MAX_PAGE_SIZE + 1);
+    }
+
+    #[test]
+    fn next_cursor_only_when_more_rows() {
+        let at = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        let mut rows = vec![1, 2, 3];
+        assert_eq!(take_next_cursor(&mut rows, 3, |id| Cursor::new(at, *id)), None);
+        let next = take_next_cursor(&mut rows, 2, |id| Cursor::new(at, *id)).unwrap();
+        assert_eq!(rows, vec![1, 2]);
+        assert_eq!(Cursor::decode(&next).unwrap(), Cursor::new(at, 2));
+
+        // 超过上限的 limit 与 fetch_limit 一致地按上限截断
+        let mut rows: Vec<i32> = (0..=MAX_PAGE_SIZE as i32).collect();
+        assert!(take_next_cursor(&mut rows, i64::MAX, |id| Cursor::new(at, *id)).is_some());
+        assert_eq!(rows.len() as i64, MAX_PAGE_SIZE);
+    }
+}
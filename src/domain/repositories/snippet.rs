@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::domain::{
     models::snippet::{CreateSnippet, Snippet, UpdateSnippet},
+    models::version::VersionedUpdate,
     repositories::repository::{
         QueryParams, RepositoryResult, ResultPaging, DEFAULT_LIMIT, DEFAULT_OFFSET,
     },
@@ -12,6 +13,8 @@ use crate::domain::{
 pub struct SnippetQueryParams {
     pub limit: Option<i64>,
     pub offset: Option<i64>,
+    pub cursor: Option<String>,
+    pub with_total: Option<bool>,
     pub title: Option<String>,
     pub slug: Option<String>,
     pub published: Option<bool>,
@@ -27,6 +30,12 @@ impl QueryParams for SnippetQueryParams {
     fn offset(&self) -> i64 {
         self.offset.or(DEFAULT_OFFSET).unwrap_or_default()
     }
+    fn cursor(&self) -> Option<&str> {
+        self.cursor.as_deref()
+    }
+    fn with_total(&self) -> bool {
+        self.with_total.unwrap_or(self.cursor.is_none())
+    }
 }
 
 #[async_trait]
@@ -35,12 +44,16 @@ pub trait SnippetRepository: Send + Sync {
     async fn list(&self, params: SnippetQueryParams) -> RepositoryResult<ResultPaging<Snippet>>;
     async fn get(&self, snippet_id: i32) -> RepositoryResult<Option<Snippet>>;
     async fn get_by_slug(&self, slug: &str) -> RepositoryResult<Option<Snippet>>;
+    // 旧 slug 对应代码片段的当前 slug
+    async fn find_redirect(&self, old_slug: &str) -> RepositoryResult<Option<String>>;
+    // slug 已被使用，包括仍用于重定向的旧 slug
+    async fn slug_exists(&self, slug: &str) -> RepositoryResult<bool>;
     async fn get_published(&self) -> RepositoryResult<Vec<Snippet>>;
     async fn update(
         &self,
         snippet_id: i32,
         update_snippet: &UpdateSnippet,
-    ) -> RepositoryResult<Option<Snippet>>;
+    ) -> RepositoryResult<VersionedUpdate<Snippet>>;
     async fn delete(&self, snippet_id: i32) -> RepositoryResult<bool>;
     async fn exists(&self, snippet_id: i32) -> RepositoryResult<bool>;
     async fn toggle_published(&self, snippet_id: i32) -> RepositoryResult<Option<Snippet>>;
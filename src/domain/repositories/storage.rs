@@ -0,0 +1,39 @@
+use std::ops::Range;
+use std::path::Path;
+
+use actix_web::web::Bytes;
+use async_trait::async_trait;
+use futures_util::stream::BoxStream;
+
+use crate::domain::repositories::repository::RepositoryResult;
+
+// 分块读取的文件内容
+pub type ByteStream = BoxStream<'static, RepositoryResult<Bytes>>;
+
+// 上传文件的存储后端（本地磁盘、S3兼容对象存储等），key 为不带目录的文件名
+#[async_trait]
+pub trait StorageBackend: Send + Sync {
+    // 将本地临时文件写入存储
+    async fn put_file(&self, key: &str, source: &Path) -> RepositoryResult<()>;
+
+    // 直接写入内存中的内容
+    async fn put_bytes(&self, key: &str, bytes: Vec<u8>) -> RepositoryResult<()>;
+
+    // 读取文件内容，不存在时返回 None
+    async fn get(&self, key: &str) -> RepositoryResult<Option<Vec<u8>>>;
+
+    // 按字节区间流式读取，不存在时返回 None
+    async fn get_range(&self, key: &str, range: Range<u64>)
+        -> RepositoryResult<Option<ByteStream>>;
+
+    // 文件大小，不存在时返回 None
+    async fn size(&self, key: &str) -> RepositoryResult<Option<u64>>;
+
+    async fn exists(&self, key: &str) -> RepositoryResult<bool>;
+
+    // 删除文件，返回文件是否存在
+    async fn delete(&self, key: &str) -> RepositoryResult<bool>;
+
+    // 文件对外访问的URL，写入 files.file_path
+    fn url(&self, key: &str) -> String;
+}
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::domain::{
     models::tag::{CreateTag, Tag, TagType, UpdateTag},
+    models::version::VersionedUpdate,
     repositories::repository::{
         QueryParams, RepositoryResult, ResultPaging, DEFAULT_LIMIT, DEFAULT_OFFSET,
     },
@@ -12,6 +13,9 @@ use crate::domain::{
 pub struct TagQueryParams {
     pub limit: Option<i64>,
     pub offset: Option<i64>,
+    pub cursor: Option<String>,
+    #[serde(rename = "withTotal")]
+    pub with_total: Option<bool>,
     pub name: Option<String>,
     pub slug: Option<String>,
     pub tag_type: Option<TagType>,
@@ -24,6 +28,12 @@ impl QueryParams for TagQueryParams {
     fn offset(&self) -> i64 {
         self.offset.or(DEFAULT_OFFSET).unwrap_or_default()
     }
+    fn cursor(&self) -> Option<&str> {
+        self.cursor.as_deref()
+    }
+    fn with_total(&self) -> bool {
+        self.with_total.unwrap_or(self.cursor.is_none())
+    }
 }
 
 #[async_trait]
@@ -31,8 +41,13 @@ pub trait TagRepository: Send + Sync {
     async fn create(&self, new_tag: &CreateTag) -> RepositoryResult<Tag>;
     async fn list(&self, params: TagQueryParams) -> RepositoryResult<ResultPaging<Tag>>;
     async fn get(&self, tag_id: i32) -> RepositoryResult<Option<Tag>>;
-    async fn update(&self, tag_id: i32, update_tag: &UpdateTag) -> RepositoryResult<Option<Tag>>;
+    async fn update(
+        &self,
+        tag_id: i32,
+        update_tag: &UpdateTag,
+    ) -> RepositoryResult<VersionedUpdate<Tag>>;
     async fn delete(&self, tag_id: i32) -> RepositoryResult<bool>;
     async fn exists(&self, tag_id: i32) -> RepositoryResult<bool>;
+    async fn slug_exists(&self, slug: &str) -> RepositoryResult<bool>;
     async fn get_all(&self, tag_type: Option<TagType>) -> RepositoryResult<Vec<Tag>>;
 }
@@ -1,8 +1,20 @@
 use async_trait::async_trait;
 
+use crate::domain::models::user::AdminWallet;
 use crate::domain::repositories::repository::RepositoryResult;
 
 #[async_trait]
 pub trait UserRepository: Send + Sync {
     async fn gen_nonce(&self) -> RepositoryResult<String>;
+
+    // 数据库中的管理员钱包，不包含配置文件中的地址
+    async fn list_admin_wallets(&self) -> RepositoryResult<Vec<AdminWallet>>;
+
+    async fn is_admin_wallet(&self, address: &str) -> RepositoryResult<bool>;
+
+    // 已存在时返回 false
+    async fn add_admin_wallet(&self, address: &str) -> RepositoryResult<bool>;
+
+    // 不存在时返回 false
+    async fn remove_admin_wallet(&self, address: &str) -> RepositoryResult<bool>;
 }
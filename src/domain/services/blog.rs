@@ -2,7 +2,8 @@ use async_trait::async_trait;
 
 use crate::domain::{
     error::CommonError,
-    models::blog::{Blog, CreateBlog, UpdateBlog},
+    models::blog::{Blog, BlogArchiveYear, BlogNeighbors, BlogSummary, CreateBlog, UpdateBlog},
+    models::version::VersionedUpdate,
     repositories::{blog::BlogQueryParams, repository::ResultPaging},
 };
 
@@ -10,15 +11,27 @@ use crate::domain::{
 pub trait BlogService: 'static + Sync + Send {
     async fn create(&self, blog: CreateBlog) -> Result<Blog, CommonError>;
     async fn list(&self, params: BlogQueryParams) -> Result<ResultPaging<Blog>, CommonError>;
+    async fn list_summaries(
+        &self,
+        params: BlogQueryParams,
+    ) -> Result<ResultPaging<BlogSummary>, CommonError>;
     async fn get(&self, blog_id: i32) -> Result<Option<Blog>, CommonError>;
     async fn get_by_slug(&self, slug: &str) -> Result<Option<Blog>, CommonError>;
     async fn get_published(&self) -> Result<Vec<Blog>, CommonError>;
+    async fn get_published_summaries(&self) -> Result<Vec<BlogSummary>, CommonError>;
     async fn get_published_by_slug(&self, slug: &str) -> Result<Option<Blog>, CommonError>;
+    // 旧 slug 重定向到的当前 slug
+    async fn find_published_redirect(&self, slug: &str) -> Result<Option<String>, CommonError>;
+    async fn get_published_neighbors(&self, blog: &Blog) -> Result<BlogNeighbors, CommonError>;
+    async fn get_archive(
+        &self,
+        tag_slug: Option<String>,
+    ) -> Result<Vec<BlogArchiveYear>, CommonError>;
     async fn update(
         &self,
         blog_id: i32,
         update_blog: UpdateBlog,
-    ) -> Result<Option<Blog>, CommonError>;
+    ) -> Result<VersionedUpdate<Blog>, CommonError>;
     async fn delete(&self, blog_id: i32) -> Result<bool, CommonError>;
     async fn exists(&self, blog_id: i32) -> Result<bool, CommonError>;
     async fn toggle_published(&self, blog_id: i32) -> Result<Option<Blog>, CommonError>;
@@ -0,0 +1,13 @@
+use async_trait::async_trait;
+
+use crate::domain::models::cache::{CacheGroup, CacheKey, CacheLookup};
+
+// 公开读接口的响应缓存，缓存不可用时按未命中处理，不影响正常读取
+#[async_trait]
+pub trait CacheService: 'static + Sync + Send {
+    async fn lookup(&self, key: &CacheKey) -> CacheLookup;
+    // 保存未命中时查询到的响应 JSON
+    async fn store(&self, lookup: &CacheLookup, body: String);
+    // 使分组内的所有缓存失效
+    async fn evict(&self, groups: &[CacheGroup]);
+}
@@ -1,10 +1,86 @@
+use std::ops::Range;
+
 use actix_multipart::form::tempfile::TempFile;
 use async_trait::async_trait;
 
-use crate::domain::{error::CommonError, models::file::Files};
-
+use crate::domain::{
+    error::CommonError,
+    models::file::{
+        File, FileContent, FileDeletion, FileDetail, FileInfo, FileIntegrityReport, FileVisibility,
+        Files, ImageVariant, SignedUrl, UploadOptions, UploadSession,
+    },
+    repositories::{file::FileQueryParams, repository::ResultPaging, storage::ByteStream},
+};
 
 #[async_trait]
 pub trait FileService: 'static + Sync + Send {
-    async fn upload_files(&self, files: Vec<TempFile>) -> Result<Files, CommonError>;
-}
\ No newline at end of file
+    async fn upload_files(
+        &self,
+        files: Vec<TempFile>,
+        options: UploadOptions,
+    ) -> Result<Files, CommonError>;
+    // 读取文件，指定变体时返回（必要时生成）对应的图片变体
+    async fn read_file(
+        &self,
+        name: &str,
+        variant: Option<ImageVariant>,
+    ) -> Result<Option<FileContent>, CommonError>;
+    // 通过签名URL读取文件（包括私有文件），签名无效或已过期时返回 403
+    async fn read_signed_file(
+        &self,
+        name: &str,
+        expires: i64,
+        signature: &str,
+        variant: Option<ImageVariant>,
+    ) -> Result<Option<FileContent>, CommonError>;
+    // 流式读取 read_file/read_signed_file 返回的文件中的字节区间
+    async fn open_file(
+        &self,
+        content: &FileContent,
+        range: Range<u64>,
+    ) -> Result<Option<ByteStream>, CommonError>;
+    // 生成带过期时间的签名URL，expires_in 为空时使用默认有效期
+    async fn sign_url(
+        &self,
+        file_id: i32,
+        expires_in: Option<u64>,
+    ) -> Result<Option<SignedUrl>, CommonError>;
+    // 修改文件可见性，图片变体跟随原始文件
+    async fn set_visibility(
+        &self,
+        file_id: i32,
+        visibility: FileVisibility,
+    ) -> Result<Option<File>, CommonError>;
+    async fn list(&self, params: FileQueryParams) -> Result<ResultPaging<File>, CommonError>;
+    // 文件详情，包含图片变体和引用该文件的内容
+    async fn get_detail(&self, file_id: i32) -> Result<Option<FileDetail>, CommonError>;
+    // 删除文件，仍被引用时拒绝删除
+    async fn delete(&self, file_id: i32) -> Result<FileDeletion, CommonError>;
+    // 未被任何内容引用的文件（超过保留时间的）
+    async fn find_orphans(&self) -> Result<Vec<File>, CommonError>;
+    // 删除所有孤立文件，返回已删除的文件
+    async fn cleanup_orphans(&self) -> Result<Vec<File>, CommonError>;
+    // 将迁移前的 MD5 hash 按文件内容重新计算为 SHA-256，返回回填的数量；
+    // 单个文件失败时跳过，stop 返回 true 时在当前文件处理完后停止
+    async fn backfill_hashes(&self, stop: &(dyn Fn() -> bool + Sync))
+        -> Result<usize, CommonError>;
+    // 重新计算存储中的文件hash，报告缺失和损坏的文件
+    async fn check_integrity(&self) -> Result<FileIntegrityReport, CommonError>;
+    // 创建分片上传会话
+    async fn init_upload(
+        &self,
+        file_name: String,
+        total_size: i64,
+        options: UploadOptions,
+    ) -> Result<UploadSession, CommonError>;
+    async fn get_upload(&self, upload_id: &str) -> Result<Option<UploadSession>, CommonError>;
+    // 写入分片，offset 必须等于会话中已接收的字节数
+    async fn append_chunk(
+        &self,
+        upload_id: &str,
+        offset: i64,
+        chunk: &[u8],
+    ) -> Result<Option<UploadSession>, CommonError>;
+    // 合并完成的分片，校验后按普通上传的去重逻辑保存
+    async fn complete_upload(&self, upload_id: &str) -> Result<Option<FileInfo>, CommonError>;
+}
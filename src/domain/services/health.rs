@@ -0,0 +1,9 @@
+use async_trait::async_trait;
+
+use crate::domain::models::health::HealthReport;
+
+#[async_trait]
+pub trait HealthService: 'static + Sync + Send {
+    // 检查数据库、待执行迁移、Redis 和上传目录，供就绪探针使用
+    async fn readiness(&self) -> HealthReport;
+}
@@ -1,5 +1,7 @@
 pub mod blog;
+pub mod cache;
 pub mod file;
+pub mod health;
 pub mod note;
 pub mod service_context;
 pub mod snippet;
@@ -4,6 +4,7 @@ use async_trait::async_trait;
 use crate::domain::{
     error::CommonError,
     models::note::{CreateNote, Note, UpdateNote},
+    models::version::VersionedUpdate,
     repositories::{note::NoteQueryParams, repository::ResultPaging},
 };
 
@@ -17,7 +18,7 @@ pub trait NoteService: 'static + Sync + Send {
         &self,
         note_id: i32,
         update_note: UpdateNote,
-    ) -> Result<Option<Note>, CommonError>;
+    ) -> Result<VersionedUpdate<Note>, CommonError>;
     async fn delete(&self, note_id: i32) -> Result<bool, CommonError>;
     async fn exists(&self, note_id: i32) -> Result<bool, CommonError>;
     async fn toggle_published(&self, note_id: i32) -> Result<Option<Note>, CommonError>;
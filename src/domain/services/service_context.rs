@@ -1,7 +1,13 @@
+use async_trait::async_trait;
+
 use crate::domain::models::service_context::ServiceContext;
 
+#[async_trait]
 pub trait ServiceContextService: 'static + Sync + Send {
     fn get_service_context(&self) -> ServiceContext;
     fn update(&self, service_context: ServiceContext) -> ServiceContext;
+    // 返回后台任务最近一次读取的状态，修改后不一定立即生效
     fn is_maintenance_active(&self) -> bool;
+    // 从数据库重新读取维护模式状态，由后台任务定时调用
+    async fn refresh_maintenance(&self);
 }
@@ -4,6 +4,7 @@ use async_trait::async_trait;
 use crate::domain::{
     error::CommonError,
     models::snippet::{CreateSnippet, Snippet, UpdateSnippet},
+    models::version::VersionedUpdate,
     repositories::{repository::ResultPaging, snippet::SnippetQueryParams},
 };
 
@@ -13,12 +14,14 @@ pub trait SnippetService: 'static + Sync + Send {
     async fn list(&self, params: SnippetQueryParams) -> Result<ResultPaging<Snippet>, CommonError>;
     async fn get(&self, snippet_id: i32) -> Result<Option<Snippet>, CommonError>;
     async fn get_by_slug(&self, slug: &str) -> Result<Option<Snippet>, CommonError>;
+    // 旧 slug 重定向到的当前 slug
+    async fn find_redirect(&self, slug: &str) -> Result<Option<String>, CommonError>;
     async fn get_published(&self) -> Result<Vec<Snippet>, CommonError>;
     async fn update(
         &self,
         snippet_id: i32,
         update_snippet: UpdateSnippet,
-    ) -> Result<Option<Snippet>, CommonError>;
+    ) -> Result<VersionedUpdate<Snippet>, CommonError>;
     async fn delete(&self, snippet_id: i32) -> Result<bool, CommonError>;
     async fn exists(&self, snippet_id: i32) -> Result<bool, CommonError>;
     async fn toggle_published(&self, snippet_id: i32) -> Result<Option<Snippet>, CommonError>;
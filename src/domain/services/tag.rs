@@ -3,6 +3,7 @@ use async_trait::async_trait;
 use crate::domain::{
     error::CommonError,
     models::tag::{CreateTag, Tag},
+    models::version::VersionedUpdate,
     repositories::{repository::ResultPaging, tag::TagQueryParams},
 };
 
@@ -15,7 +16,7 @@ pub trait TagService: 'static + Sync + Send {
         &self,
         tag_id: i32,
         update_tag: crate::domain::models::tag::UpdateTag,
-    ) -> Result<Option<Tag>, CommonError>;
+    ) -> Result<VersionedUpdate<Tag>, CommonError>;
     async fn delete(&self, tag_id: i32) -> Result<bool, CommonError>;
     async fn exists(&self, tag_id: i32) -> Result<bool, CommonError>;
     async fn get_all(
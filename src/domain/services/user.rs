@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
-use crate::domain::error::CommonError;
+use crate::domain::{error::CommonError, models::user::AdminWallet};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AuthResponse {
@@ -17,4 +17,16 @@ pub trait UserService: 'static + Sync + Send {
         address: String,
         signature: String,
     ) -> Result<AuthResponse, CommonError>;
+
+    // 配置文件和数据库中的管理员钱包
+    async fn list_admin_wallets(&self) -> Result<Vec<AdminWallet>, CommonError>;
+
+    // 添加到数据库白名单，已存在时返回 false
+    async fn add_admin_wallet(&self, address: String) -> Result<bool, CommonError>;
+
+    // 从数据库白名单删除，配置文件中的地址不能删除
+    async fn remove_admin_wallet(&self, address: String) -> Result<bool, CommonError>;
+
+    // 生成新的JWT密钥，已签发的token立即失效
+    async fn rotate_jwt_key(&self) -> Result<(), CommonError>;
 }
@@ -0,0 +1,120 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+fn mac(secret: &[u8], name: &str, expires: i64) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(format!("{}:{}", name, expires).as_bytes());
+    mac
+}
+
+// 私有文件访问签名：HMAC-SHA256(文件名:过期时间戳)，URL安全的 base64
+pub fn sign(secret: &[u8], name: &str, expires: i64) -> String {
+    URL_SAFE_NO_PAD.encode(mac(secret, name, expires).finalize().into_bytes())
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SignatureError {
+    Invalid,
+    Expired,
+}
+
+// 校验签名和过期时间，now 为当前 Unix 时间戳；先校验签名，避免对伪造的请求暴露是否过期
+pub fn check(
+    secret: &[u8],
+    name: &str,
+    expires: i64,
+    signature: &str,
+    now: i64,
+) -> Result<(), SignatureError> {
+    if !verify(secret, name, expires, signature) {
+        return Err(SignatureError::Invalid);
+    }
+    if expires < now {
+        return Err(SignatureError::Expired);
+    }
+    Ok(())
+}
+
+// 校验签名（常量时间比较），不检查是否过期
+pub fn verify(secret: &[u8], name: &str, expires: i64, signature: &str) -> bool {
+    let Ok(signature) = URL_SAFE_NO_PAD.decode(signature) else {
+        return false;
+    };
+    mac(secret, name, expires).verify_slice(&signature).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check, sign, SignatureError};
+
+    const SECRET: &[u8] = b"secret";
+    const NAME: &str = "0123456789abcdef0123456789abcdef.pdf";
+    const EXPIRES: i64 = 1_792_400_000;
+
+    #[test]
+    fn accepts_until_expiry() {
+        let signature = sign(SECRET, NAME, EXPIRES);
+        assert_eq!(
+            check(SECRET, NAME, EXPIRES, &signature, EXPIRES - 60),
+            Ok(())
+        );
+        assert_eq!(check(SECRET, NAME, EXPIRES, &signature, EXPIRES), Ok(()));
+        assert_eq!(
+            check(SECRET, NAME, EXPIRES, &signature, EXPIRES + 1),
+            Err(SignatureError::Expired)
+        );
+    }
+
+    #[test]
+    fn rejects_tampered_requests() {
+        let signature = sign(SECRET, NAME, EXPIRES);
+        let now = EXPIRES - 60;
+        // 延长有效期、换成其他文件、换密钥
+        assert_eq!(
+            check(SECRET, NAME, EXPIRES + 3600, &signature, now),
+            Err(SignatureError::Invalid)
+        );
+        assert_eq!(
+            check(
+                SECRET,
+                "fedcba9876543210fedcba9876543210.pdf",
+                EXPIRES,
+                &signature,
+                now
+            ),
+            Err(SignatureError::Invalid)
+        );
+        assert_eq!(
+            check(b"other", NAME, EXPIRES, &signature, now),
+            Err(SignatureError::Invalid)
+        );
+
+        let mut tampered = signature.into_bytes();
+        tampered[0] = if tampered[0] == b'A' { b'B' } else { b'A' };
+        let tampered = String::from_utf8(tampered).unwrap();
+        assert_eq!(
+            check(SECRET, NAME, EXPIRES, &tampered, now),
+            Err(SignatureError::Invalid)
+        );
+        assert_eq!(
+            check(SECRET, NAME, EXPIRES, "not base64!", now),
+            Err(SignatureError::Invalid)
+        );
+        assert_eq!(
+            check(SECRET, NAME, EXPIRES, "", now),
+            Err(SignatureError::Invalid)
+        );
+    }
+
+    #[test]
+    fn expired_forgery_is_invalid() {
+        // 伪造的签名即使已过期也按无效处理
+        assert_eq!(
+            check(SECRET, NAME, EXPIRES, "AAAA", EXPIRES + 1),
+            Err(SignatureError::Invalid)
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use std::future::Future;
+
+use crate::domain::{error::RepositoryError, repositories::repository::RepositoryResult};
+
+// slug 列为 VARCHAR(191)
+pub const SLUG_MAX_LENGTH: usize = 191;
+// 生成的 slug 写入时被并发请求占用后重新生成的次数
+const SLUG_CONFLICT_RETRIES: usize = 3;
+
+// 标题转换为 slug：中文等非ASCII字符先音译（中文为拼音），再只保留小写字母和数字，用连字符连接
+pub fn slugify(text: &str) -> String {
+    let ascii = deunicode::deunicode(text);
+    let mut slug = String::with_capacity(ascii.len());
+    for ch in ascii.chars() {
+        if ch.is_ascii_alphanumeric() {
+            slug.push(ch.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = truncate(slug.trim_end_matches('-'), SLUG_MAX_LENGTH);
+    if slug.is_empty() {
+        "untitled".to_string()
+    } else {
+        slug
+    }
+}
+
+// 生成不重复的 slug，已存在时依次追加 -2、-3 ...
+pub async fn unique_slug<F, Fut>(text: &str, exists: F) -> RepositoryResult<String>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = RepositoryResult<bool>>,
+{
+    let base = slugify(text);
+    if !exists(base.clone()).await? {
+        return Ok(base);
+    }
+
+    let mut n = 2;
+    loop {
+        let suffix = format!("-{}", n);
+        let candidate = format!(
+            "{}{}",
+            truncate(&base, SLUG_MAX_LENGTH - suffix.len()),
+            suffix
+        );
+        if !exists(candidate.clone()).await? {
+            return Ok(candidate);
+        }
+        n += 1;
+    }
+}
+
+// 用生成的 slug 创建记录；检查和写入之间 slug 被其他请求占用时重新生成后重试
+pub async fn create_with_unique_slug<T, E, EFut, C, CFut>(
+    text: &str,
+    exists: E,
+    create: C,
+) -> RepositoryResult<T>
+where
+    E: Fn(String) -> EFut,
+    EFut: Future<Output = RepositoryResult<bool>>,
+    C: Fn(String) -> CFut,
+    CFut: Future<Output = RepositoryResult<T>>,
+{
+    let mut retries = 0;
+    loop {
+        let slug = unique_slug(text, &exists).await?;
+        match create(slug).await {
+            Err(RepositoryError::SlugConflict(_)) if retries < SLUG_CONFLICT_RETRIES => {
+                retries += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+fn truncate(slug: &str, max_len: usize) -> String {
+    // slug 只包含ASCII字符，可以直接按字节截断
+    slug[..slug.len().min(max_len)]
+        .trim_end_matches('-')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::collections::HashSet;
+
+    use super::{create_with_unique_slug, slugify, unique_slug, SLUG_MAX_LENGTH};
+    use crate::domain::error::RepositoryError;
+
+    #[test]
+    fn slugify_ascii() {
+        assert_eq!(slugify("Hello, World!"), "hello-world");
+        assert_eq!(slugify("  Rust 2024 -- Edition  "), "rust-2024-edition");
+        assert_eq!(slugify("!!!"), "untitled");
+        assert_eq!(slugify(""), "untitled");
+    }
+
+    #[test]
+    fn slugify_pinyin() {
+        assert_eq!(slugify("你好世界"), "ni-hao-shi-jie");
+        assert_eq!(slugify("Rust 入门"), "rust-ru-men");
+        assert_eq!(slugify("Café déjà vu"), "cafe-deja-vu");
+    }
+
+    #[test]
+    fn slugify_truncates() {
+        let slug = slugify(&"ab-".repeat(100));
+        assert!(slug.len() <= SLUG_MAX_LENGTH);
+        assert!(!slug.ends_with('-'));
+    }
+
+    #[actix_web::test]
+    async fn unique_slug_appends_suffix() {
+        let taken: HashSet<String> = ["hello-world", "hello-world-2"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let exists = |slug: String| {
+            let taken = taken.contains(&slug);
+            async move { Ok(taken) }
+        };
+        assert_eq!(
+            unique_slug("Hello World", exists).await.unwrap(),
+            "hello-world-3"
+        );
+        assert_eq!(unique_slug("Other", exists).await.unwrap(), "other");
+
+        let long = "a".repeat(SLUG_MAX_LENGTH);
+        let taken = long.clone();
+        let slug = unique_slug(&long, |slug: String| {
+            let exists = slug == taken;
+            async move { Ok(exists) }
+        })
+        .await
+        .unwrap();
+        assert_eq!(slug.len(), SLUG_MAX_LENGTH);
+        assert!(slug.ends_with("-2"));
+    }
+
+    #[actix_web::test]
+    async fn retries_on_slug_conflict() {
+        // 第一次写入时 hello 被并发请求占用
+        let taken = RefCell::new(HashSet::new());
+        let slug = create_with_unique_slug(
+            "Hello",
+            |slug: String| {
+                let exists = taken.borrow().contains(&slug);
+                async move { Ok(exists) }
+            },
+            |slug: String| {
+                let conflict = !taken.borrow_mut().insert(slug.clone()) || slug == "hello";
+                async move {
+                    if conflict {
+                        Err(RepositoryError::SlugConflict(slug))
+                    } else {
+                        Ok(slug)
+                    }
+                }
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(slug, "hello-2");
+    }
+
+    #[actix_web::test]
+    async fn gives_up_after_retries() {
+        let result: Result<String, _> = create_with_unique_slug(
+            "Hello",
+            |_| async { Ok(false) },
+            |slug: String| async move { Err(RepositoryError::SlugConflict(slug)) },
+        )
+        .await;
+        assert!(matches!(result, Err(RepositoryError::SlugConflict(_))));
+    }
+}
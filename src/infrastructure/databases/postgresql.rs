@@ -1,19 +1,132 @@
 use diesel;
+use diesel::connection::{set_default_instrumentation, Instrumentation, InstrumentationEvent};
 use diesel::pg::PgConnection;
+use diesel::prelude::*;
 use diesel::r2d2;
-use diesel::r2d2::ConnectionManager;
+use diesel::r2d2::event::{CheckoutEvent, TimeoutEvent};
+use diesel::r2d2::{ConnectionManager, HandleEvent};
+use diesel::sql_types::{BigInt, Bool};
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
 
-use crate::config::AppConfig;
+use crate::config::{redact_url_password, AppConfig};
+use crate::metrics::{
+    DB_POOL_CHECKOUT_DURATION, DB_POOL_CHECKOUT_TIMEOUTS, DB_POOL_CONNECTIONS,
+    DB_POOL_IDLE_CONNECTIONS, DB_POOL_MAX_SIZE,
+};
 
 pub type Pool<T> = r2d2::Pool<ConnectionManager<T>>;
 pub type PostgresPool = Pool<diesel::pg::PgConnection>;
 pub type DBConn = PostgresPool;
 
+// 编译进二进制的数据库迁移
+pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("./migrations");
+
+// 执行迁移时持有的 advisory lock，多个实例同时启动时只有一个在执行迁移
+const MIGRATION_LOCK_KEY: i64 = 0x7a776a5f626c6f67;
+
+#[derive(QueryableByName)]
+struct AdvisoryLock {
+    #[diesel(sql_type = Bool)]
+    locked: bool,
+}
+
+// 使用单独的连接执行未应用的迁移，返回本次应用的迁移版本
+pub fn run_migrations(
+    config: &AppConfig,
+) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut conn = PgConnection::establish(&config.database.url)?;
+
+    let lock: AdvisoryLock = diesel::sql_query("SELECT pg_try_advisory_lock($1) AS locked")
+        .bind::<BigInt, _>(MIGRATION_LOCK_KEY)
+        .get_result(&mut conn)?;
+    if !lock.locked {
+        tracing::info!("Waiting for another instance to finish migrations");
+        diesel::sql_query("SELECT pg_advisory_lock($1)")
+            .bind::<BigInt, _>(MIGRATION_LOCK_KEY)
+            .execute(&mut conn)?;
+    }
+
+    let result = conn
+        .run_pending_migrations(MIGRATIONS)
+        .map(|versions| versions.iter().map(|v| v.to_string()).collect());
+
+    // 显式释放锁，进程异常退出时连接断开也会自动释放
+    diesel::sql_query("SELECT pg_advisory_unlock($1)")
+        .bind::<BigInt, _>(MIGRATION_LOCK_KEY)
+        .execute(&mut conn)?;
+    result
+}
+
 pub fn db_pool(config: &AppConfig) -> DBConn {
     let database_url = &config.database.url;
-    tracing::info!("Connecting to database: {}", database_url);
+    tracing::info!(
+        "Connecting to database: {}",
+        redact_url_password(database_url)
+    );
+    if config.logging.otel.is_some() {
+        set_default_instrumentation(query_tracing).expect("Failed to enable query tracing");
+    }
     let manager = ConnectionManager::<PgConnection>::new(database_url);
     Pool::builder()
+        .event_handler(Box::new(PoolMetrics))
         .build(manager)
         .expect("Failed to create pool")
 }
+
+// 记录获取连接的等待时间和超时次数
+#[derive(Debug)]
+struct PoolMetrics;
+
+impl HandleEvent for PoolMetrics {
+    fn handle_checkout(&self, event: CheckoutEvent) {
+        DB_POOL_CHECKOUT_DURATION.observe(event.duration().as_secs_f64());
+    }
+
+    fn handle_timeout(&self, _event: TimeoutEvent) {
+        DB_POOL_CHECKOUT_TIMEOUTS.inc();
+    }
+}
+
+// 开启链路导出时为每条 SQL 创建 span，不记录绑定参数
+#[derive(Default)]
+struct QueryTracing {
+    spans: Vec<tracing::Span>,
+}
+
+fn query_tracing() -> Option<Box<dyn Instrumentation>> {
+    Some(Box::new(QueryTracing::default()))
+}
+
+impl Instrumentation for QueryTracing {
+    fn on_connection_event(&mut self, event: InstrumentationEvent<'_>) {
+        match event {
+            InstrumentationEvent::StartQuery { query, .. } => {
+                let sql = query.to_string();
+                let statement = sql.split(" -- binds:").next().unwrap_or_default();
+                self.spans.push(tracing::info_span!(
+                    "db.query",
+                    otel.kind = "client",
+                    db.system = "postgresql",
+                    db.statement = statement,
+                    otel.status_code = tracing::field::Empty,
+                    error = tracing::field::Empty,
+                ));
+            }
+            InstrumentationEvent::FinishQuery { error, .. } => {
+                if let (Some(span), Some(e)) = (self.spans.pop(), error) {
+                    span.record("otel.status_code", "ERROR");
+                    span.record("error", e.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// 连接池当前状态，在输出指标前更新
+pub fn record_pool_state(pool: &DBConn) {
+    let state = pool.state();
+    DB_POOL_CONNECTIONS.set(state.connections as i64);
+    DB_POOL_IDLE_CONNECTIONS.set(state.idle_connections as i64);
+    DB_POOL_MAX_SIZE.set(pool.max_size() as i64);
+}
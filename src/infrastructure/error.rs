@@ -1,6 +1,8 @@
 use crate::domain::error::RepositoryError;
-pub use actix_threadpool::{run, BlockingError};
+pub use crate::infrastructure::threadpool::run;
+pub use actix_threadpool::BlockingError;
 use diesel::r2d2;
+use diesel::result::DatabaseErrorKind;
 
 pub type AsyncPoolError<T> = BlockingError<T>;
 
@@ -30,3 +32,19 @@ impl<T: std::fmt::Debug> From<AsyncPoolError<T>> for DieselRepositoryError {
         DieselRepositoryError(RepositoryError::DatabaseError(error.to_string()))
     }
 }
+
+// slug 唯一约束（*_slug_key）冲突时返回 SlugConflict，调用方可以换一个 slug 重试
+pub fn slug_conflict_error(error: AsyncPoolError<diesel::result::Error>) -> RepositoryError {
+    match error {
+        BlockingError::Error(diesel::result::Error::DatabaseError(
+            DatabaseErrorKind::UniqueViolation,
+            info,
+        )) if info
+            .constraint_name()
+            .is_some_and(|name| name.ends_with("_slug_key")) =>
+        {
+            RepositoryError::SlugConflict(info.message().to_string())
+        }
+        error => DieselRepositoryError::from(error).into_inner(),
+    }
+}
@@ -0,0 +1,209 @@
+use std::io::Cursor;
+
+use image::codecs::{avif::AvifEncoder, jpeg::JpegEncoder, png::PngEncoder, webp::WebPEncoder};
+use image::metadata::Orientation;
+use image::{imageops::FilterType, DynamicImage, ImageDecoder, ImageReader};
+use img_parts::{jpeg::Jpeg, png::Png, webp::WebP, Bytes, DynImage, ImageEXIF, ImageICC};
+
+use crate::domain::models::file::ImageFormat;
+
+const JPEG_QUALITY: u8 = 85;
+const AVIF_SPEED: u8 = 8;
+const AVIF_QUALITY: u8 = 70;
+// 按方向旋转后重新编码原图时使用较高的质量
+const ORIENTED_JPEG_QUALITY: u8 = 92;
+
+// JPEG 中携带 EXIF/XMP（APP1）、IPTC（APP13）和注释的段
+const JPEG_METADATA_MARKERS: [u8; 3] = [0xE1, 0xED, 0xFE];
+const PNG_METADATA_CHUNKS: [[u8; 4]; 5] = [*b"eXIf", *b"tEXt", *b"zTXt", *b"iTXt", *b"tIME"];
+
+pub struct RenderedImage {
+    pub bytes: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+// 只解析文件头获取图片尺寸
+pub fn image_dimensions(path: &std::path::Path) -> Option<(u32, u32)> {
+    ImageReader::open(path)
+        .ok()?
+        .with_guessed_format()
+        .ok()?
+        .into_dimensions()
+        .ok()
+}
+
+// 按宽度等比缩放（不放大）并编码为指定格式
+pub fn render_variant(
+    source: &[u8],
+    width: Option<u32>,
+    format: ImageFormat,
+) -> Result<RenderedImage, String> {
+    let image = image::load_from_memory(source).map_err(|e| e.to_string())?;
+    let image = match width {
+        Some(width) if width < image.width() => image.resize(width, u32::MAX, FilterType::Lanczos3),
+        _ => image,
+    };
+
+    let mut bytes = Vec::new();
+    let writer = Cursor::new(&mut bytes);
+    let result = match format {
+        // JPEG 不支持透明通道
+        ImageFormat::Jpeg => DynamicImage::ImageRgb8(image.to_rgb8())
+            .write_with_encoder(JpegEncoder::new_with_quality(writer, JPEG_QUALITY)),
+        ImageFormat::Png => image.write_with_encoder(PngEncoder::new(writer)),
+        ImageFormat::Webp => DynamicImage::ImageRgba8(image.to_rgba8())
+            .write_with_encoder(WebPEncoder::new_lossless(writer)),
+        ImageFormat::Avif => DynamicImage::ImageRgba8(image.to_rgba8()).write_with_encoder(
+            AvifEncoder::new_with_speed_quality(writer, AVIF_SPEED, AVIF_QUALITY),
+        ),
+    };
+    result.map_err(|e| e.to_string())?;
+
+    Ok(RenderedImage {
+        bytes,
+        width: image.width(),
+        height: image.height(),
+    })
+}
+
+// 清除图片中的 EXIF/XMP 等元数据（包括 GPS 位置），保留 ICC 色彩配置
+// 带方向标记的图片先按方向旋转像素再重新编码，避免清除后显示方向错误；内容未变化时返回 None
+pub fn strip_metadata(source: &[u8], format: ImageFormat) -> Result<Option<Vec<u8>>, String> {
+    let stripped = match oriented(source, format)? {
+        Some(bytes) => bytes,
+        None => strip_segments(Bytes::copy_from_slice(source), format)?,
+    };
+    Ok((stripped != source).then_some(stripped))
+}
+
+// 直接删除元数据段，不重新编码像素
+fn strip_segments(source: Bytes, format: ImageFormat) -> Result<Vec<u8>, String> {
+    let stripped = match format {
+        ImageFormat::Jpeg => {
+            let mut jpeg = Jpeg::from_bytes(source).map_err(|e| e.to_string())?;
+            for marker in JPEG_METADATA_MARKERS {
+                jpeg.remove_segments_by_marker(marker);
+            }
+            jpeg.encoder().bytes()
+        }
+        ImageFormat::Png => {
+            let mut png = Png::from_bytes(source).map_err(|e| e.to_string())?;
+            for chunk in PNG_METADATA_CHUNKS {
+                png.remove_chunks_by_type(chunk);
+            }
+            png.encoder().bytes()
+        }
+        ImageFormat::Webp => {
+            let mut webp = WebP::from_bytes(source).map_err(|e| e.to_string())?;
+            webp.remove_chunks_by_id(img_parts::webp::CHUNK_XMP);
+            // 同时更新 VP8X 头中的元数据标记
+            webp.set_exif(None);
+            webp.encoder().bytes()
+        }
+        ImageFormat::Avif => source,
+    };
+    Ok(stripped.to_vec())
+}
+
+// 图片带有非默认方向时，返回旋转后重新编码的内容
+fn oriented(source: &[u8], format: ImageFormat) -> Result<Option<Vec<u8>>, String> {
+    let mut decoder = ImageReader::new(Cursor::new(source))
+        .with_guessed_format()
+        .map_err(|e| e.to_string())?
+        .into_decoder()
+        .map_err(|e| e.to_string())?;
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+    if orientation == Orientation::NoTransforms {
+        return Ok(None);
+    }
+    let icc_profile = decoder.icc_profile().ok().flatten();
+    let mut image = DynamicImage::from_decoder(decoder).map_err(|e| e.to_string())?;
+    image.apply_orientation(orientation);
+
+    let mut bytes = Vec::new();
+    let writer = Cursor::new(&mut bytes);
+    let result = match format {
+        ImageFormat::Jpeg => DynamicImage::ImageRgb8(image.to_rgb8())
+            .write_with_encoder(JpegEncoder::new_with_quality(writer, ORIENTED_JPEG_QUALITY)),
+        ImageFormat::Png => image.write_with_encoder(PngEncoder::new(writer)),
+        ImageFormat::Webp => DynamicImage::ImageRgba8(image.to_rgba8())
+            .write_with_encoder(WebPEncoder::new_lossless(writer)),
+        ImageFormat::Avif => return Ok(None),
+    };
+    result.map_err(|e| e.to_string())?;
+
+    // 重新编码会丢失 ICC 色彩配置，从原图中复制
+    match icc_profile {
+        Some(profile) => {
+            let mut image = DynImage::from_bytes(Bytes::from(bytes))
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| "unsupported image format".to_string())?;
+            image.set_icc_profile(Some(Bytes::from(profile)));
+            Ok(Some(image.encoder().bytes().to_vec()))
+        }
+        None => Ok(Some(bytes)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use img_parts::png::PngChunk;
+
+    use super::*;
+
+    // 只包含 GPS IFD（GPSLatitudeRef = "N"）的 TIFF 格式 EXIF
+    const GPS_EXIF: [u8; 44] = [
+        b'I', b'I', 42, 0, 8, 0, 0, 0, // 头部，IFD0 位于偏移 8
+        1, 0, 0x25, 0x88, 4, 0, 1, 0, 0, 0, 26, 0, 0, 0, 0, 0, 0, 0, // GPS IFD 指针
+        1, 0, 1, 0, 2, 0, 2, 0, 0, 0, b'N', 0, 0, 0, 0, 0, 0, 0, // GPSLatitudeRef
+    ];
+
+    fn encoded(format: ImageFormat) -> Vec<u8> {
+        let image = DynamicImage::new_rgb8(4, 4);
+        let png = {
+            let mut bytes = Vec::new();
+            image
+                .write_with_encoder(PngEncoder::new(Cursor::new(&mut bytes)))
+                .unwrap();
+            bytes
+        };
+        render_variant(&png, None, format).unwrap().bytes
+    }
+
+    #[test]
+    fn strips_gps_exif_from_jpeg_and_keeps_icc() {
+        let mut jpeg = Jpeg::from_bytes(encoded(ImageFormat::Jpeg).into()).unwrap();
+        jpeg.set_exif(Some(Bytes::from_static(&GPS_EXIF)));
+        jpeg.set_icc_profile(Some(Bytes::from_static(b"icc profile")));
+        let source = jpeg.encoder().bytes().to_vec();
+
+        let stripped = strip_metadata(&source, ImageFormat::Jpeg).unwrap().unwrap();
+        let jpeg = Jpeg::from_bytes(stripped.clone().into()).unwrap();
+        assert!(jpeg.exif().is_none());
+        assert_eq!(jpeg.icc_profile().unwrap(), &b"icc profile"[..]);
+        assert!(image::load_from_memory(&stripped).is_ok());
+    }
+
+    #[test]
+    fn strips_exif_and_text_chunks_from_png() {
+        let mut png = Png::from_bytes(encoded(ImageFormat::Png).into()).unwrap();
+        png.set_exif(Some(Bytes::from_static(&GPS_EXIF)));
+        png.chunks_mut().insert(
+            1,
+            PngChunk::new(*b"tEXt", Bytes::from_static(b"Comment\0secret")),
+        );
+        let source = png.encoder().bytes().to_vec();
+
+        let stripped = strip_metadata(&source, ImageFormat::Png).unwrap().unwrap();
+        let png = Png::from_bytes(stripped.into()).unwrap();
+        assert!(png.exif().is_none());
+        assert!(png.chunk_by_type(*b"tEXt").is_none());
+    }
+
+    #[test]
+    fn unchanged_images_are_not_rewritten() {
+        let source = encoded(ImageFormat::Jpeg);
+        assert_eq!(strip_metadata(&source, ImageFormat::Jpeg).unwrap(), None);
+    }
+}
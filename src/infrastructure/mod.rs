@@ -3,4 +3,8 @@ pub mod models;
 pub mod repositories;
 pub mod schema;
 pub mod error;
+pub mod images;
 pub mod services;
+pub mod storage;
+pub mod tasks;
+pub mod threadpool;
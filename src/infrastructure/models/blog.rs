@@ -1,4 +1,4 @@
-use crate::domain::models::blog::{Blog, CreateBlog};
+use crate::domain::models::blog::{Blog, BlogNeighbor, BlogSummary, CreateBlog};
 use crate::infrastructure::schema::blogs;
 use chrono::{DateTime, Utc};
 use diesel;
@@ -16,6 +16,44 @@ pub struct BlogDiesel {
     pub published: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub version: i32,
+}
+
+// 列表摘要查询，不选取 body 列
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = blogs)]
+pub struct BlogSummaryDiesel {
+    pub id: i32,
+    pub title: String,
+    pub slug: String,
+    pub description: String,
+    pub cover: Option<String>,
+    pub author: Option<String>,
+    pub published: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// 上一篇/下一篇查询只选取导航需要的列
+#[derive(Queryable)]
+pub struct BlogNeighborDiesel {
+    pub id: i32,
+    pub title: String,
+    pub slug: String,
+    pub created_at: DateTime<Utc>,
+}
+
+// 归档查询按月聚合的一行，entries 为该月博客的 JSON 数组
+#[derive(QueryableByName)]
+pub struct BlogArchiveMonthDiesel {
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    pub year: i32,
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    pub month: i32,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub count: i64,
+    #[diesel(sql_type = diesel::sql_types::Json)]
+    pub entries: serde_json::Value,
 }
 
 #[derive(Insertable)]
@@ -28,6 +66,9 @@ pub struct CreateBlogDiesel {
     pub cover: Option<String>,
     pub author: Option<String>,
     pub published: bool,
+    // 为空时使用列默认值
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
 impl From<CreateBlog> for CreateBlogDiesel {
@@ -40,6 +81,8 @@ impl From<CreateBlog> for CreateBlogDiesel {
             cover: blog.cover,
             author: blog.author,
             published: blog.published,
+            created_at: blog.created_at,
+            updated_at: blog.updated_at,
         }
     }
 }
@@ -56,8 +99,39 @@ impl Into<Blog> for BlogDiesel {
             author: self.author,
             published: self.published,
             tags: Vec::new(), // 将在repository层填充
+            attachments: Vec::new(),
             created_at: self.created_at,
             updated_at: self.updated_at,
+            version: self.version,
+        }
+    }
+}
+
+impl From<BlogNeighborDiesel> for BlogNeighbor {
+    fn from(neighbor: BlogNeighborDiesel) -> Self {
+        BlogNeighbor {
+            id: neighbor.id,
+            title: neighbor.title,
+            slug: neighbor.slug,
+            created_at: neighbor.created_at,
+        }
+    }
+}
+
+impl From<BlogSummaryDiesel> for BlogSummary {
+    fn from(summary: BlogSummaryDiesel) -> Self {
+        BlogSummary {
+            id: summary.id,
+            title: summary.title,
+            slug: summary.slug,
+            description: summary.description,
+            cover: summary.cover,
+            author: summary.author,
+            published: summary.published,
+            tags: Vec::new(), // 将在repository层填充
+            attachments: Vec::new(),
+            created_at: summary.created_at,
+            updated_at: summary.updated_at,
         }
     }
 }
@@ -1,4 +1,7 @@
-use crate::domain::models::file::{CreateFile, File};
+use crate::domain::models::file::{
+    Attachment, AttachmentRole, CreateFile, File, FileReference, FileReferenceKind,
+    FileVisibility,
+};
 use crate::infrastructure::schema::files;
 use diesel::prelude::*;
 
@@ -10,6 +13,56 @@ pub struct FileDiesel {
     pub upload_time: chrono::NaiveDateTime,
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
+    pub original_id: Option<i32>,
+    pub variant: Option<String>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub mime_type: Option<String>,
+    pub byte_size: Option<i64>,
+    pub original_filename: Option<String>,
+    pub hash_algorithm: String,
+    pub storage_key: String,
+    pub visibility: String,
+}
+
+// 引用查询返回的一行
+#[derive(QueryableByName)]
+pub struct FileReferenceDiesel {
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub kind: String,
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    pub id: i32,
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub title: String,
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub role: String,
+}
+
+// 内容关联文件查询返回的一行，owner_id 为内容的id
+#[derive(QueryableByName)]
+pub struct AttachmentDiesel {
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    pub owner_id: i32,
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub role: String,
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    pub file_id: i32,
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub file_path: String,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Integer>)]
+    pub width: Option<i32>,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Integer>)]
+    pub height: Option<i32>,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Text>)]
+    pub mime_type: Option<String>,
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub visibility: String,
+}
+
+#[derive(QueryableByName)]
+pub struct FileIdDiesel {
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    pub id: i32,
 }
 
 #[derive(Insertable)]
@@ -17,6 +70,16 @@ pub struct FileDiesel {
 pub struct CreateFileDiesel {
     pub file_hash: String,
     pub file_path: String,
+    pub original_id: Option<i32>,
+    pub variant: Option<String>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub mime_type: Option<String>,
+    pub byte_size: Option<i64>,
+    pub original_filename: Option<String>,
+    pub hash_algorithm: String,
+    pub storage_key: String,
+    pub visibility: String,
 }
 
 // 转换方法
@@ -29,6 +92,16 @@ impl From<File> for FileDiesel {
             upload_time: f.upload_time,
             created_at: f.created_at,
             updated_at: f.updated_at,
+            original_id: f.original_id,
+            variant: f.variant,
+            width: f.width,
+            height: f.height,
+            mime_type: f.mime_type,
+            byte_size: f.byte_size,
+            original_filename: f.original_filename,
+            hash_algorithm: f.hash_algorithm,
+            storage_key: f.storage_key,
+            visibility: f.visibility.as_str().to_string(),
         }
     }
 }
@@ -42,6 +115,16 @@ impl Into<File> for FileDiesel {
             upload_time: self.upload_time,
             created_at: self.created_at,
             updated_at: self.updated_at,
+            original_id: self.original_id,
+            variant: self.variant,
+            width: self.width,
+            height: self.height,
+            mime_type: self.mime_type,
+            byte_size: self.byte_size,
+            original_filename: self.original_filename,
+            hash_algorithm: self.hash_algorithm,
+            storage_key: self.storage_key,
+            visibility: visibility(&self.visibility),
         }
     }
 }
@@ -51,6 +134,62 @@ impl From<CreateFile> for CreateFileDiesel {
         CreateFileDiesel {
             file_hash: f.file_hash,
             file_path: f.file_path,
+            original_id: f.original_id,
+            variant: f.variant,
+            width: f.width,
+            height: f.height,
+            mime_type: f.mime_type,
+            byte_size: f.byte_size,
+            original_filename: f.original_filename,
+            hash_algorithm: f.hash_algorithm,
+            storage_key: f.storage_key,
+            visibility: f.visibility.as_str().to_string(),
+        }
+    }
+}
+
+fn visibility(value: &str) -> FileVisibility {
+    match value {
+        "public" => FileVisibility::Public,
+        _ => FileVisibility::Private,
+    }
+}
+
+impl From<FileReferenceDiesel> for FileReference {
+    fn from(r: FileReferenceDiesel) -> Self {
+        let kind = match r.kind.as_str() {
+            "blog" => FileReferenceKind::Blog,
+            "snippet" => FileReferenceKind::Snippet,
+            "note" => FileReferenceKind::Note,
+            _ => FileReferenceKind::Tag,
+        };
+        FileReference {
+            kind,
+            id: r.id,
+            title: r.title,
+            role: attachment_role(&r.role),
+        }
+    }
+}
+
+fn attachment_role(value: &str) -> AttachmentRole {
+    match value {
+        "cover" => AttachmentRole::Cover,
+        "icon" => AttachmentRole::Icon,
+        _ => AttachmentRole::Inline,
+    }
+}
+
+impl From<AttachmentDiesel> for Attachment {
+    fn from(a: AttachmentDiesel) -> Self {
+        Attachment {
+            file_id: a.file_id,
+            role: attachment_role(&a.role),
+            url: a.file_path,
+            width: a.width,
+            height: a.height,
+            mime_type: a.mime_type,
+            visibility: visibility(&a.visibility),
         }
     }
 }
@@ -5,3 +5,4 @@ pub mod service_context;
 pub mod snippet;
 pub mod tag;
 pub mod todo;
+pub mod user;
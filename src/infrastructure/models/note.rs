@@ -11,6 +11,7 @@ pub struct NoteDiesel {
     pub published: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub version: i32,
 }
 
 #[derive(Insertable)]
@@ -18,6 +19,9 @@ pub struct NoteDiesel {
 pub struct CreateNoteDiesel {
     pub body: String,
     pub published: bool,
+    // 为空时使用列默认值
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
 impl From<CreateNote> for CreateNoteDiesel {
@@ -25,6 +29,8 @@ impl From<CreateNote> for CreateNoteDiesel {
         CreateNoteDiesel {
             body: note.body,
             published: note.published,
+            created_at: note.created_at,
+            updated_at: note.updated_at,
         }
     }
 }
@@ -38,6 +44,7 @@ impl Into<Note> for NoteDiesel {
             tags: Vec::new(), // 将在repository层填充
             created_at: self.created_at,
             updated_at: self.updated_at,
+            version: self.version,
         }
     }
 }
@@ -14,6 +14,7 @@ pub struct SnippetDiesel {
     pub published: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub version: i32,
 }
 
 #[derive(Insertable)]
@@ -24,6 +25,9 @@ pub struct CreateSnippetDiesel {
     pub description: String,
     pub body: String,
     pub published: bool,
+    // 为空时使用列默认值
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
 impl From<CreateSnippet> for CreateSnippetDiesel {
@@ -34,6 +38,8 @@ impl From<CreateSnippet> for CreateSnippetDiesel {
             description: snippet.description,
             body: snippet.body,
             published: snippet.published,
+            created_at: snippet.created_at,
+            updated_at: snippet.updated_at,
         }
     }
 }
@@ -48,8 +54,10 @@ impl Into<Snippet> for SnippetDiesel {
             body: self.body,
             published: self.published,
             tags: Vec::new(), // 将在repository层填充
+            attachments: Vec::new(),
             created_at: self.created_at,
             updated_at: self.updated_at,
+            version: self.version,
         }
     }
 }
@@ -14,6 +14,7 @@ pub struct TagDiesel {
     pub icon_dark: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub version: i32,
 }
 
 // Factory method for creating a new TagDiesel from a Tag
@@ -31,8 +32,9 @@ impl From<Tag> for TagDiesel {
             },
             icon: t.icon,
             icon_dark: t.icon_dark,
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
+            created_at: t.created_at,
+            updated_at: t.updated_at,
+            version: t.version,
         }
     }
 }
@@ -45,6 +47,9 @@ pub struct CreateTagDiesel {
     pub type_: String,
     pub icon: Option<String>,
     pub icon_dark: Option<String>,
+    // 为空时使用列默认值
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
 // Factory method for creating a new Tag from a TagDiesel
@@ -63,6 +68,9 @@ impl Into<Tag> for TagDiesel {
             },
             icon: self.icon,
             icon_dark: self.icon_dark,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            version: self.version,
         }
     }
 }
@@ -80,6 +88,8 @@ impl From<CreateTag> for CreateTagDiesel {
             },
             icon: t.icon,
             icon_dark: t.icon_dark,
+            created_at: t.created_at,
+            updated_at: t.updated_at,
         }
     }
 }
@@ -99,6 +109,9 @@ impl Into<Tag> for CreateTagDiesel {
             },
             icon: self.icon,
             icon_dark: self.icon_dark,
+            created_at: self.created_at.unwrap_or_else(Utc::now),
+            updated_at: self.updated_at.unwrap_or_else(Utc::now),
+            version: 1,
         }
     }
 }
@@ -0,0 +1,28 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+
+use crate::domain::models::user::{AdminWallet, AdminWalletSource};
+use crate::infrastructure::schema::admin_wallets;
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = admin_wallets)]
+pub struct AdminWalletDiesel {
+    pub address: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = admin_wallets)]
+pub struct CreateAdminWalletDiesel<'a> {
+    pub address: &'a str,
+}
+
+impl From<AdminWalletDiesel> for AdminWallet {
+    fn from(wallet: AdminWalletDiesel) -> Self {
+        AdminWallet {
+            address: wallet.address,
+            source: AdminWalletSource::Database,
+            created_at: Some(wallet.created_at),
+        }
+    }
+}
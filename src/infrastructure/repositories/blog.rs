@@ -1,27 +1,182 @@
-use actix_threadpool::run;
+use crate::infrastructure::threadpool::run;
 use async_trait::async_trait;
+use diesel::pg::{Pg, PgConnection};
 use diesel::prelude::*;
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use crate::{
     domain::{
-        models::blog::{Blog, CreateBlog, UpdateBlog},
+        error::RepositoryError,
+        models::blog::{
+            Blog, BlogArchiveEntry, BlogArchiveMonth, BlogArchiveYear, BlogNeighbors, BlogSummary,
+            CreateBlog, UpdateBlog,
+        },
+        models::file::{AttachmentRole, FileReferenceKind},
         models::tag::Tag,
+        models::version::VersionedUpdate,
         repositories::{
             blog::{BlogQueryParams, BlogRepository},
-            repository::{QueryParams, RepositoryResult, ResultPaging},
+            repository::{
+                fetch_limit, take_next_cursor, Cursor, QueryParams, RepositoryResult, ResultPaging,
+            },
         },
     },
     infrastructure::{
         databases::postgresql::DBConn,
-        error::DieselRepositoryError,
+        error::{slug_conflict_error, DieselRepositoryError},
         models::{
-            blog::{BlogDiesel, CreateBlogDiesel},
+            blog::{
+                BlogArchiveMonthDiesel, BlogDiesel, BlogNeighborDiesel, BlogSummaryDiesel,
+                CreateBlogDiesel,
+            },
             tag::TagDiesel,
         },
+        repositories::content_file::{load_attachments, sync_attachments},
+        schema,
     },
 };
 
+// 列表查询的过滤条件，list 与 list_summaries 共用
+#[derive(Clone)]
+struct BlogListFilter {
+    title: Option<String>,
+    slug: Option<String>,
+    published: Option<bool>,
+}
+
+impl From<&BlogQueryParams> for BlogListFilter {
+    fn from(params: &BlogQueryParams) -> Self {
+        BlogListFilter {
+            title: params.title.clone(),
+            slug: params.slug.clone(),
+            published: params.published,
+        }
+    }
+}
+
+impl BlogListFilter {
+    fn apply(self, cursor: Option<Cursor>) -> schema::blogs::BoxedQuery<'static, Pg> {
+        use crate::infrastructure::schema::blogs::dsl::*;
+        let mut query = blogs.into_boxed();
+
+        if let Some(title_val) = self.title {
+            query = query.filter(title.ilike(format!("%{}%", title_val)));
+        }
+        if let Some(slug_val) = self.slug {
+            query = query.filter(slug.eq(slug_val));
+        }
+        if let Some(published_val) = self.published {
+            query = query.filter(published.eq(published_val));
+        }
+        if let Some(cursor) = cursor {
+            query = query.filter(
+                created_at
+                    .lt(cursor.created_at)
+                    .or(created_at.eq(cursor.created_at).and(id.lt(cursor.id))),
+            );
+        }
+
+        query
+    }
+}
+
+// 记录博客的旧 slug；新 slug 如果曾是历史 slug，则移除该历史记录
+// 行已按年月倒序，这里只需把相邻的月份合并到同一年
+fn group_archive(rows: Vec<BlogArchiveMonthDiesel>) -> RepositoryResult<Vec<BlogArchiveYear>> {
+    let mut years: Vec<BlogArchiveYear> = Vec::new();
+    for row in rows {
+        let entries: Vec<BlogArchiveEntry> = serde_json::from_value(row.entries)
+            .map_err(|e| RepositoryError::SerializationError(e.to_string()))?;
+        let month = BlogArchiveMonth {
+            month: row.month,
+            count: row.count,
+            entries,
+        };
+        match years.last_mut() {
+            Some(year) if year.year == row.year => {
+                year.count += month.count;
+                year.months.push(month);
+            }
+            _ => years.push(BlogArchiveYear {
+                year: row.year,
+                count: month.count,
+                months: vec![month],
+            }),
+        }
+    }
+
+    Ok(years)
+}
+
+fn record_slug_change(
+    conn: &mut PgConnection,
+    blog_id: i32,
+    old_slug: &str,
+    new_slug: &str,
+) -> Result<(), diesel::result::Error> {
+    use crate::infrastructure::schema::blog_slug_histories;
+
+    diesel::insert_into(blog_slug_histories::table)
+        .values((
+            blog_slug_histories::old_slug.eq(old_slug),
+            blog_slug_histories::blog_id.eq(blog_id),
+        ))
+        .on_conflict(blog_slug_histories::old_slug)
+        .do_update()
+        .set((
+            blog_slug_histories::blog_id.eq(blog_id),
+            blog_slug_histories::created_at.eq(diesel::dsl::now),
+        ))
+        .execute(conn)?;
+
+    diesel::delete(blog_slug_histories::table)
+        .filter(blog_slug_histories::old_slug.eq(new_slug))
+        .execute(conn)?;
+
+    Ok(())
+}
+
+// 替换博客的标签关系，在写入博客的同一事务中调用
+fn replace_blog_tags(
+    conn: &mut PgConnection,
+    blog_id: i32,
+    tag_ids: &[i32],
+) -> Result<(), diesel::result::Error> {
+    use crate::infrastructure::schema::blog_tag_relations;
+
+    // 删除现有关系
+    diesel::delete(blog_tag_relations::table)
+        .filter(blog_tag_relations::blog_id.eq(blog_id))
+        .execute(conn)?;
+
+    // 插入新关系
+    for tag_id in tag_ids {
+        diesel::insert_into(blog_tag_relations::table)
+            .values((
+                blog_tag_relations::blog_id.eq(blog_id),
+                blog_tag_relations::tag_id.eq(tag_id),
+            ))
+            .execute(conn)?;
+    }
+    Ok(())
+}
+
+// 按封面和正文重建博客关联的文件
+fn sync_blog_attachments(
+    conn: &mut PgConnection,
+    blog: &BlogDiesel,
+) -> Result<(), diesel::result::Error> {
+    sync_attachments(
+        conn,
+        FileReferenceKind::Blog,
+        blog.id,
+        &[
+            (AttachmentRole::Cover, blog.cover.as_deref()),
+            (AttachmentRole::Inline, Some(&blog.body)),
+        ],
+    )
+}
+
 pub struct BlogDieselRepository {
     pub pool: Arc<DBConn>,
 }
@@ -50,35 +205,102 @@ impl BlogDieselRepository {
         Ok(result.into_iter().map(|tag| tag.into()).collect())
     }
 
-    // 辅助方法：设置博客的标签关系
-    async fn set_blog_tags(&self, blog_id: i32, tag_ids: &[i32]) -> RepositoryResult<()> {
-        use crate::infrastructure::schema::blog_tag_relations;
+    // 辅助方法：一次查询加载多篇博客的标签
+    async fn load_tags_for_blogs(
+        &self,
+        blog_ids: Vec<i32>,
+    ) -> RepositoryResult<HashMap<i32, Vec<Tag>>> {
+        use crate::infrastructure::schema::{blog_tag_relations, tags};
         let pool = self.pool.clone();
-        let tag_ids = tag_ids.to_vec();
 
-        run(move || -> Result<(), diesel::result::Error> {
+        let result = run(move || {
             let mut conn = pool.get().unwrap();
-
-            // 删除现有关系
-            diesel::delete(blog_tag_relations::table)
-                .filter(blog_tag_relations::blog_id.eq(blog_id))
-                .execute(&mut conn)?;
-
-            // 插入新关系
-            for tag_id in tag_ids {
-                diesel::insert_into(blog_tag_relations::table)
-                    .values((
-                        blog_tag_relations::blog_id.eq(blog_id),
-                        blog_tag_relations::tag_id.eq(tag_id),
-                    ))
-                    .execute(&mut conn)?;
-            }
-            Ok(())
+            tags::table
+                .inner_join(blog_tag_relations::table.on(tags::id.eq(blog_tag_relations::tag_id)))
+                .filter(blog_tag_relations::blog_id.eq_any(blog_ids))
+                .select((blog_tag_relations::blog_id, tags::all_columns))
+                .load::<(i32, TagDiesel)>(&mut conn)
         })
         .await
         .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
 
-        Ok(())
+        let mut tags_by_blog: HashMap<i32, Vec<Tag>> = HashMap::new();
+        for (blog_id, tag) in result {
+            tags_by_blog.entry(blog_id).or_default().push(tag.into());
+        }
+        Ok(tags_by_blog)
+    }
+
+    // 辅助方法：把摘要行转换为BlogSummary并填充标签和关联文件
+    async fn with_summary_relations(
+        &self,
+        rows: Vec<BlogSummaryDiesel>,
+    ) -> RepositoryResult<Vec<BlogSummary>> {
+        let ids: Vec<i32> = rows.iter().map(|v| v.id).collect();
+        let mut tags_by_blog = self.load_tags_for_blogs(ids.clone()).await?;
+        let mut attachments_by_blog =
+            load_attachments(self.pool.clone(), FileReferenceKind::Blog, ids).await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let tags = tags_by_blog.remove(&row.id).unwrap_or_default();
+                let attachments = attachments_by_blog.remove(&row.id).unwrap_or_default();
+                let mut summary: BlogSummary = row.into();
+                summary.tags = tags;
+                summary.attachments = attachments;
+                summary
+            })
+            .collect())
+    }
+
+    // 辅助方法：填充单篇博客的标签和关联文件
+    async fn with_relations(&self, blog_diesel: BlogDiesel) -> RepositoryResult<Blog> {
+        let tags = self.load_blog_tags(blog_diesel.id).await?;
+        let attachments = load_attachments(
+            self.pool.clone(),
+            FileReferenceKind::Blog,
+            vec![blog_diesel.id],
+        )
+        .await?
+        .remove(&blog_diesel.id)
+        .unwrap_or_default();
+        let mut blog: Blog = blog_diesel.into();
+        blog.tags = tags;
+        blog.attachments = attachments;
+        Ok(blog)
+    }
+
+    // 辅助方法：批量填充多篇博客的标签和关联文件
+    async fn with_relations_batch(&self, rows: Vec<BlogDiesel>) -> RepositoryResult<Vec<Blog>> {
+        let ids: Vec<i32> = rows.iter().map(|v| v.id).collect();
+        let mut tags_by_blog = self.load_tags_for_blogs(ids.clone()).await?;
+        let mut attachments_by_blog =
+            load_attachments(self.pool.clone(), FileReferenceKind::Blog, ids).await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let tags = tags_by_blog.remove(&row.id).unwrap_or_default();
+                let attachments = attachments_by_blog.remove(&row.id).unwrap_or_default();
+                let mut blog: Blog = row.into();
+                blog.tags = tags;
+                blog.attachments = attachments;
+                blog
+            })
+            .collect())
+    }
+
+    // 辅助方法：按列表过滤条件统计博客数量
+    async fn count_blogs(&self, filter: BlogListFilter) -> RepositoryResult<i64> {
+        let pool = self.pool.clone();
+
+        run(move || {
+            let mut conn = pool.get().unwrap();
+            filter.apply(None).count().get_result::<i64>(&mut conn)
+        })
+        .await
+        .map_err(|v| DieselRepositoryError::from(v).into_inner())
     }
 }
 
@@ -91,96 +313,103 @@ impl BlogRepository for BlogDieselRepository {
         let mut conn = self.pool.get().unwrap();
 
         let result: BlogDiesel = run(move || {
-            diesel::insert_into(blogs)
-                .values(new_blog_diesel)
-                .get_result(&mut conn)
+            conn.transaction(|conn| {
+                let blog = diesel::insert_into(blogs)
+                    .values(new_blog_diesel)
+                    .get_result::<BlogDiesel>(conn)?;
+                sync_blog_attachments(conn, &blog)?;
+                replace_blog_tags(conn, blog.id, &tag_ids)?;
+                Ok::<_, diesel::result::Error>(blog)
+            })
         })
         .await
-        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+        .map_err(slug_conflict_error)?;
 
-        // 设置标签关系
-        if !tag_ids.is_empty() {
-            self.set_blog_tags(result.id, &tag_ids).await?;
-        }
-
-        // 加载标签并转换为Blog
-        let tags = self.load_blog_tags(result.id).await?;
-        let mut blog: Blog = result.into();
-        blog.tags = tags;
-
-        Ok(blog)
+        // 加载标签和关联文件并转换为Blog
+        self.with_relations(result).await
     }
 
     async fn list(&self, params: BlogQueryParams) -> RepositoryResult<ResultPaging<Blog>> {
         use crate::infrastructure::schema::blogs::dsl::*;
         let pool = self.pool.clone();
         let limit_val = params.limit();
-        let offset_val = params.offset();
-        let title_filter = params.title.clone();
-        let slug_filter = params.slug.clone();
-        let published_filter = params.published;
-
-        // 获取总数
-        let total = {
-            let pool_clone = pool.clone();
-            let title_filter_clone = title_filter.clone();
-            let slug_filter_clone = slug_filter.clone();
-            run(move || {
-                let mut conn = pool_clone.get().unwrap();
-                let mut query = blogs.into_boxed();
-
-                if let Some(title_val) = title_filter_clone {
-                    query = query.filter(title.ilike(format!("%{}%", title_val)));
-                }
-                if let Some(slug_val) = slug_filter_clone {
-                    query = query.filter(slug.eq(slug_val));
-                }
-                if let Some(published_val) = published_filter {
-                    query = query.filter(published.eq(published_val));
-                }
-
-                query.count().get_result::<i64>(&mut conn)
-            })
-            .await
-            .map_err(|v| DieselRepositoryError::from(v).into_inner())?
+        let cursor = params.cursor().map(Cursor::decode).transpose()?;
+        // 游标分页时忽略 offset
+        let offset_val = if cursor.is_some() { 0 } else { params.offset() };
+        let filter = BlogListFilter::from(&params);
+
+        // 获取总数（可选）
+        let total = if params.with_total() {
+            Some(self.count_blogs(filter.clone()).await?)
+        } else {
+            None
         };
 
-        // 获取分页数据
-        let result = run(move || {
+        // 获取分页数据，多取一条用于判断是否还有下一页
+        let mut result = run(move || {
             let mut conn = pool.get().unwrap();
-            let mut query = blogs.into_boxed();
+            filter
+                .apply(cursor)
+                .order((created_at.desc(), id.desc()))
+                .limit(fetch_limit(limit_val))
+                .offset(offset_val)
+                .load::<BlogDiesel>(&mut conn)
+        })
+        .await
+        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+        let next_cursor =
+            take_next_cursor(&mut result, limit_val, |v| Cursor::new(v.created_at, v.id));
 
-            if let Some(title_val) = title_filter {
-                query = query.filter(title.ilike(format!("%{}%", title_val)));
-            }
-            if let Some(slug_val) = slug_filter {
-                query = query.filter(slug.eq(slug_val));
-            }
-            if let Some(published_val) = published_filter {
-                query = query.filter(published.eq(published_val));
-            }
+        let items = self.with_relations_batch(result).await?;
 
-            query
-                .order(created_at.desc())
-                .limit(limit_val)
+        Ok(ResultPaging {
+            total,
+            items,
+            next_cursor,
+        })
+    }
+
+    async fn list_summaries(
+        &self,
+        params: BlogQueryParams,
+    ) -> RepositoryResult<ResultPaging<BlogSummary>> {
+        use crate::infrastructure::schema::blogs::dsl::*;
+        let pool = self.pool.clone();
+        let limit_val = params.limit();
+        let cursor = params.cursor().map(Cursor::decode).transpose()?;
+        // 游标分页时忽略 offset
+        let offset_val = if cursor.is_some() { 0 } else { params.offset() };
+        let filter = BlogListFilter::from(&params);
+
+        // 获取总数（可选）
+        let total = if params.with_total() {
+            Some(self.count_blogs(filter.clone()).await?)
+        } else {
+            None
+        };
+
+        // 只选取摘要列，多取一条用于判断是否还有下一页
+        let mut result = run(move || {
+            let mut conn = pool.get().unwrap();
+            filter
+                .apply(cursor)
+                .select(BlogSummaryDiesel::as_select())
+                .order((created_at.desc(), id.desc()))
+                .limit(fetch_limit(limit_val))
                 .offset(offset_val)
-                .load::<BlogDiesel>(&mut conn)
+                .load::<BlogSummaryDiesel>(&mut conn)
         })
         .await
         .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+        let next_cursor =
+            take_next_cursor(&mut result, limit_val, |v| Cursor::new(v.created_at, v.id));
 
-        // 为每个博客加载标签
-        let mut blogs_with_tags = Vec::new();
-        for blog_diesel in result {
-            let tags = self.load_blog_tags(blog_diesel.id).await?;
-            let mut blog: Blog = blog_diesel.into();
-            blog.tags = tags;
-            blogs_with_tags.push(blog);
-        }
+        let items = self.with_summary_relations(result).await?;
 
         Ok(ResultPaging {
             total,
-            items: blogs_with_tags,
+            items,
+            next_cursor,
         })
     }
 
@@ -199,10 +428,7 @@ impl BlogRepository for BlogDieselRepository {
         .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
 
         if let Some(blog_diesel) = result {
-            let tags = self.load_blog_tags(blog_diesel.id).await?;
-            let mut blog: Blog = blog_diesel.into();
-            blog.tags = tags;
-            Ok(Some(blog))
+            Ok(Some(self.with_relations(blog_diesel).await?))
         } else {
             Ok(None)
         }
@@ -224,10 +450,7 @@ impl BlogRepository for BlogDieselRepository {
         .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
 
         if let Some(blog_diesel) = result {
-            let tags = self.load_blog_tags(blog_diesel.id).await?;
-            let mut blog: Blog = blog_diesel.into();
-            blog.tags = tags;
-            Ok(Some(blog))
+            Ok(Some(self.with_relations(blog_diesel).await?))
         } else {
             Ok(None)
         }
@@ -247,15 +470,25 @@ impl BlogRepository for BlogDieselRepository {
         .await
         .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
 
-        let mut blogs_with_tags = Vec::new();
-        for blog_diesel in result {
-            let tags = self.load_blog_tags(blog_diesel.id).await?;
-            let mut blog: Blog = blog_diesel.into();
-            blog.tags = tags;
-            blogs_with_tags.push(blog);
-        }
+        self.with_relations_batch(result).await
+    }
 
-        Ok(blogs_with_tags)
+    async fn get_published_summaries(&self) -> RepositoryResult<Vec<BlogSummary>> {
+        use crate::infrastructure::schema::blogs::dsl::*;
+        let pool = self.pool.clone();
+
+        let result = run(move || {
+            let mut conn = pool.get().unwrap();
+            blogs
+                .filter(published.eq(true))
+                .select(BlogSummaryDiesel::as_select())
+                .order(created_at.desc())
+                .load::<BlogSummaryDiesel>(&mut conn)
+        })
+        .await
+        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+
+        self.with_summary_relations(result).await
     }
 
     async fn get_published_by_slug(&self, slug_val: &str) -> RepositoryResult<Option<Blog>> {
@@ -275,20 +508,142 @@ impl BlogRepository for BlogDieselRepository {
         .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
 
         if let Some(blog_diesel) = result {
-            let tags = self.load_blog_tags(blog_diesel.id).await?;
-            let mut blog: Blog = blog_diesel.into();
-            blog.tags = tags;
-            Ok(Some(blog))
+            Ok(Some(self.with_relations(blog_diesel).await?))
         } else {
             Ok(None)
         }
     }
 
+    async fn find_published_redirect(&self, old_slug: &str) -> RepositoryResult<Option<String>> {
+        use crate::infrastructure::schema::{blog_slug_histories, blogs};
+        let pool = self.pool.clone();
+        let old_slug = old_slug.to_string();
+
+        let result = run(move || {
+            let mut conn = pool.get().unwrap();
+            blog_slug_histories::table
+                .inner_join(blogs::table)
+                .filter(blog_slug_histories::old_slug.eq(old_slug))
+                .filter(blogs::published.eq(true))
+                .select(blogs::slug)
+                .first::<String>(&mut conn)
+                .optional()
+        })
+        .await
+        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+
+        Ok(result)
+    }
+
+    async fn slug_exists(&self, slug_val: &str) -> RepositoryResult<bool> {
+        use crate::infrastructure::schema::{blog_slug_histories, blogs};
+        let pool = self.pool.clone();
+        let slug_val = slug_val.to_string();
+
+        // 旧 slug 仍用于重定向，同样视为已占用
+        let result = run(move || {
+            let mut conn = pool.get().unwrap();
+            diesel::select(
+                diesel::dsl::exists(blogs::table.filter(blogs::slug.eq(slug_val.clone()))).or(
+                    diesel::dsl::exists(
+                        blog_slug_histories::table
+                            .filter(blog_slug_histories::old_slug.eq(slug_val)),
+                    ),
+                ),
+            )
+            .get_result::<bool>(&mut conn)
+        })
+        .await
+        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+
+        Ok(result)
+    }
+
+    async fn get_published_neighbors(&self, blog: &Blog) -> RepositoryResult<BlogNeighbors> {
+        use crate::infrastructure::schema::blogs::dsl::*;
+        let pool = self.pool.clone();
+        let current_id = blog.id;
+        let current_created_at = blog.created_at;
+
+        // 以 (created_at, id) 作为游标，各取一条相邻记录，不加载全部已发布博客
+        let (previous, next) = run(move || -> Result<_, diesel::result::Error> {
+            let mut conn = pool.get().unwrap();
+
+            let previous = blogs
+                .filter(published.eq(true))
+                .filter(
+                    created_at
+                        .lt(current_created_at)
+                        .or(created_at.eq(current_created_at).and(id.lt(current_id))),
+                )
+                .order((created_at.desc(), id.desc()))
+                .select((id, title, slug, created_at))
+                .first::<BlogNeighborDiesel>(&mut conn)
+                .optional()?;
+
+            let next = blogs
+                .filter(published.eq(true))
+                .filter(
+                    created_at
+                        .gt(current_created_at)
+                        .or(created_at.eq(current_created_at).and(id.gt(current_id))),
+                )
+                .order((created_at.asc(), id.asc()))
+                .select((id, title, slug, created_at))
+                .first::<BlogNeighborDiesel>(&mut conn)
+                .optional()?;
+
+            Ok((previous, next))
+        })
+        .await
+        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+
+        Ok(BlogNeighbors {
+            previous: previous.map(|v| v.into()),
+            next: next.map(|v| v.into()),
+        })
+    }
+
+    async fn get_archive(
+        &self,
+        tag_slug: Option<String>,
+    ) -> RepositoryResult<Vec<BlogArchiveYear>> {
+        use diesel::sql_types::{Nullable, Text};
+        let pool = self.pool.clone();
+
+        // 在SQL中按年月分组并聚合条目，只选取标题、slug和日期，不读取正文
+        let rows = run(move || {
+            let mut conn = pool.get().unwrap();
+            diesel::sql_query(
+                "SELECT date_part('year', b.created_at)::int AS year, \
+                        date_part('month', b.created_at)::int AS month, \
+                        COUNT(*) AS count, \
+                        json_agg(json_build_object( \
+                            'id', b.id, 'title', b.title, 'slug', b.slug, 'created_at', b.created_at \
+                        ) ORDER BY b.created_at DESC, b.id DESC) AS entries \
+                 FROM blogs b \
+                 WHERE b.published = true \
+                   AND ($1::text IS NULL OR EXISTS ( \
+                        SELECT 1 FROM blog_tag_relations r \
+                        INNER JOIN tags t ON t.id = r.tag_id \
+                        WHERE r.blog_id = b.id AND t.slug = $1)) \
+                 GROUP BY 1, 2 \
+                 ORDER BY 1 DESC, 2 DESC",
+            )
+            .bind::<Nullable<Text>, _>(tag_slug)
+            .load::<BlogArchiveMonthDiesel>(&mut conn)
+        })
+        .await
+        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+
+        group_archive(rows)
+    }
+
     async fn update(
         &self,
         blog_id: i32,
         update_blog: &UpdateBlog,
-    ) -> RepositoryResult<Option<Blog>> {
+    ) -> RepositoryResult<VersionedUpdate<Blog>> {
         use crate::infrastructure::schema::blogs::dsl::*;
         let pool = self.pool.clone();
         let update_blog = update_blog.clone();
@@ -296,54 +651,67 @@ impl BlogRepository for BlogDieselRepository {
         let result = run(move || {
             let mut conn = pool.get().unwrap();
 
-            // 先获取现有记录
-            let existing = match blogs
-                .filter(id.eq(blog_id))
-                .first::<BlogDiesel>(&mut conn)
-                .optional()?
-            {
-                Some(blog) => blog,
-                None => return Ok(None),
-            };
+            conn.transaction(|conn| {
+                // 先获取并锁定现有记录，版本检查和更新之间不会有其他写入
+                let existing = match blogs
+                    .filter(id.eq(blog_id))
+                    .for_update()
+                    .first::<BlogDiesel>(conn)
+                    .optional()?
+                {
+                    Some(blog) => blog,
+                    None => return Ok(VersionedUpdate::NotFound),
+                };
+                if update_blog.version.is_some_and(|v| v != existing.version) {
+                    return Ok(VersionedUpdate::Conflict(existing));
+                }
 
-            // 准备更新值
-            let new_title = update_blog.title.unwrap_or(existing.title);
-            let new_slug = update_blog.slug.unwrap_or(existing.slug);
-            let new_description = update_blog.description.unwrap_or(existing.description);
-            let new_body = update_blog.body.unwrap_or(existing.body);
-            let new_cover = update_blog.cover.or(existing.cover);
-            let new_author = update_blog.author.or(existing.author);
-            let new_published = update_blog.published.unwrap_or(existing.published);
+                // 准备更新值
+                let new_title = update_blog.title.unwrap_or(existing.title);
+                let new_slug = update_blog.slug.unwrap_or(existing.slug.clone());
+                let new_description = update_blog.description.unwrap_or(existing.description);
+                let new_body = update_blog.body.unwrap_or(existing.body);
+                let new_cover = update_blog.cover.or(existing.cover);
+                let new_author = update_blog.author.or(existing.author);
+                let new_published = update_blog.published.unwrap_or(existing.published);
+
+                // slug 变更时记录旧 slug，供旧链接重定向
+                if new_slug != existing.slug {
+                    record_slug_change(conn, blog_id, &existing.slug, &new_slug)?;
+                }
 
-            // 执行更新
-            diesel::update(blogs.filter(id.eq(blog_id)))
-                .set((
-                    title.eq(new_title),
-                    slug.eq(new_slug),
-                    description.eq(new_description),
-                    body.eq(new_body),
-                    cover.eq(new_cover),
-                    author.eq(new_author),
-                    published.eq(new_published),
-                ))
-                .get_result::<BlogDiesel>(&mut conn)
-                .optional()
+                // 执行更新
+                let updated = diesel::update(blogs.filter(id.eq(blog_id)))
+                    .set((
+                        title.eq(new_title),
+                        slug.eq(new_slug),
+                        description.eq(new_description),
+                        body.eq(new_body),
+                        cover.eq(new_cover),
+                        author.eq(new_author),
+                        published.eq(new_published),
+                        version.eq(version + 1),
+                    ))
+                    .get_result::<BlogDiesel>(conn)?;
+                sync_blog_attachments(conn, &updated)?;
+                // 标签关系与内容在同一事务中更新，锁定期间其他请求看不到只更新了一半的内容
+                if let Some(tag_ids) = &update_blog.tag_ids {
+                    replace_blog_tags(conn, updated.id, tag_ids)?;
+                }
+                Ok::<_, diesel::result::Error>(VersionedUpdate::Updated(updated))
+            })
         })
         .await
-        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
-
-        if let Some(blog_diesel) = result {
-            // 更新标签关系
-            if let Some(tag_ids) = &update_blog.tag_ids {
-                self.set_blog_tags(blog_diesel.id, tag_ids).await?;
-            }
-
-            let tags = self.load_blog_tags(blog_diesel.id).await?;
-            let mut blog: Blog = blog_diesel.into();
-            blog.tags = tags;
-            Ok(Some(blog))
-        } else {
-            Ok(None)
+        .map_err(slug_conflict_error)?;
+
+        match result {
+            VersionedUpdate::Updated(blog_diesel) => Ok(VersionedUpdate::Updated(
+                self.with_relations(blog_diesel).await?,
+            )),
+            VersionedUpdate::Conflict(blog_diesel) => Ok(VersionedUpdate::Conflict(
+                self.with_relations(blog_diesel).await?,
+            )),
+            VersionedUpdate::NotFound => Ok(VersionedUpdate::NotFound),
         }
     }
 
@@ -395,7 +763,10 @@ impl BlogRepository for BlogDieselRepository {
 
             // 切换发布状态
             diesel::update(blogs.filter(id.eq(blog_id)))
-                .set(published.eq(!current_blog.published))
+                .set((
+                    published.eq(!current_blog.published),
+                    version.eq(version + 1),
+                ))
                 .get_result::<BlogDiesel>(&mut conn)
                 .optional()
         })
@@ -403,12 +774,63 @@ impl BlogRepository for BlogDieselRepository {
         .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
 
         if let Some(blog_diesel) = result {
-            let tags = self.load_blog_tags(blog_diesel.id).await?;
-            let mut blog: Blog = blog_diesel.into();
-            blog.tags = tags;
-            Ok(Some(blog))
+            Ok(Some(self.with_relations(blog_diesel).await?))
         } else {
             Ok(None)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(year: i32, month: i32, slugs: &[&str]) -> BlogArchiveMonthDiesel {
+        let entries: Vec<serde_json::Value> = slugs
+            .iter()
+            .enumerate()
+            .map(|(i, slug)| {
+                serde_json::json!({
+                    "id": i as i32 + 1,
+                    "title": slug,
+                    "slug": slug,
+                    "created_at": format!("{}-{:02}-01T08:00:00Z", year, month),
+                })
+            })
+            .collect();
+        BlogArchiveMonthDiesel {
+            year,
+            month,
+            count: slugs.len() as i64,
+            entries: serde_json::Value::Array(entries),
+        }
+    }
+
+    #[test]
+    fn archive_merges_months_into_years() {
+        let years = group_archive(vec![
+            row(2026, 3, &["c", "b"]),
+            row(2026, 1, &["a"]),
+            row(2025, 12, &["z"]),
+        ])
+        .unwrap();
+
+        assert_eq!(years.len(), 2);
+        assert_eq!((years[0].year, years[0].count), (2026, 3));
+        let months: Vec<i32> = years[0].months.iter().map(|m| m.month).collect();
+        assert_eq!(months, vec![3, 1]);
+        assert_eq!(years[0].months[0].entries[1].slug, "b");
+        assert_eq!((years[1].year, years[1].count), (2025, 1));
+        assert_eq!(years[1].months[0].entries[0].slug, "z");
+    }
+
+    #[test]
+    fn archive_rejects_malformed_entries() {
+        let mut bad = row(2026, 1, &["a"]);
+        bad.entries = serde_json::json!([{ "id": "not a number" }]);
+        assert!(matches!(
+            group_archive(vec![bad]),
+            Err(RepositoryError::SerializationError(_))
+        ));
+    }
+}
@@ -0,0 +1,155 @@
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use diesel::sql_types::{Array, Integer, Text};
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Arc,
+};
+
+use crate::{
+    domain::{
+        models::file::{Attachment, AttachmentRole, FileReferenceKind},
+        repositories::repository::RepositoryResult,
+    },
+    infrastructure::{
+        databases::postgresql::DBConn, error::DieselRepositoryError,
+        models::file::AttachmentDiesel, threadpool::run,
+    },
+};
+
+// 内容类型在 content_files 中对应的外键列
+fn owner_column(kind: FileReferenceKind) -> &'static str {
+    match kind {
+        FileReferenceKind::Blog => "blog_id",
+        FileReferenceKind::Snippet => "snippet_id",
+        FileReferenceKind::Note => "note_id",
+        FileReferenceKind::Tag => "tag_id",
+    }
+}
+
+// 按内容字段中引用的文件URL重建内容与文件的关联，需要在保存内容的事务中调用
+pub fn sync_attachments(
+    conn: &mut PgConnection,
+    kind: FileReferenceKind,
+    owner_id: i32,
+    sources: &[(AttachmentRole, Option<&str>)],
+) -> QueryResult<()> {
+    let column = owner_column(kind);
+    diesel::sql_query(format!("DELETE FROM content_files WHERE {} = $1", column))
+        .bind::<Integer, _>(owner_id)
+        .execute(conn)?;
+
+    for (role, text) in sources {
+        let stems = text.map(referenced_stems).unwrap_or_default();
+        if stems.is_empty() {
+            continue;
+        }
+        // 使用 files_storage_stem_idx 表达式索引
+        diesel::sql_query(format!(
+            "INSERT INTO content_files (file_id, role, {}) \
+             SELECT id, $1, $2 FROM files \
+             WHERE original_id IS NULL AND split_part(storage_key, '.', 1) = ANY($3) \
+             ON CONFLICT DO NOTHING",
+            column
+        ))
+        .bind::<Text, _>(role.as_str())
+        .bind::<Integer, _>(owner_id)
+        .bind::<Array<Text>, _>(stems)
+        .execute(conn)?;
+    }
+    Ok(())
+}
+
+// 文本中文件URL的存储文件名（不含扩展名），即 /static/{hash}、/private/{hash}.png?...、
+// 图片变体 /static/{hash}_w640.webp 以及对象存储 URL 最后一段中的hash。
+// 只接受完整的路径段，避免内容中的其他文字碰巧包含某个文件名
+fn referenced_stems(text: &str) -> Vec<String> {
+    let mut stems = BTreeSet::new();
+    for (index, _) in text.match_indices('/') {
+        let segment = &text[index + 1..];
+        let end = segment
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-')))
+            .unwrap_or(segment.len());
+        let stem = segment[..end].split(['.', '_']).next().unwrap_or_default();
+        if is_hash(stem) {
+            stems.insert(stem.to_string());
+        }
+    }
+    stems.into_iter().collect()
+}
+
+// 上传文件以内容hash命名：SHA-256 或迁移前的 MD5，均为小写十六进制
+fn is_hash(stem: &str) -> bool {
+    matches!(stem.len(), 32 | 64)
+        && stem
+            .chars()
+            .all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c))
+}
+
+// 一次查询加载多个内容关联的文件
+pub async fn load_attachments(
+    pool: Arc<DBConn>,
+    kind: FileReferenceKind,
+    owner_ids: Vec<i32>,
+) -> RepositoryResult<HashMap<i32, Vec<Attachment>>> {
+    if owner_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let column = owner_column(kind);
+    let result = run(move || {
+        let mut conn = pool.get().unwrap();
+        diesel::sql_query(format!(
+            "SELECT cf.{c} AS owner_id, cf.role, f.id AS file_id, f.file_path, \
+                    f.width, f.height, f.mime_type, f.visibility \
+             FROM content_files cf JOIN files f ON f.id = cf.file_id \
+             WHERE cf.{c} = ANY($1) \
+             ORDER BY cf.id",
+            c = column
+        ))
+        .bind::<Array<Integer>, _>(owner_ids)
+        .load::<AttachmentDiesel>(&mut conn)
+    })
+    .await
+    .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+
+    let mut attachments: HashMap<i32, Vec<Attachment>> = HashMap::new();
+    for row in result {
+        attachments.entry(row.owner_id).or_default().push(row.into());
+    }
+    Ok(attachments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::referenced_stems;
+
+    const HASH: &str = "510a04e956242e021c3946ebee4e3732b9eb65f3a475d3222eff80b087790c93";
+
+    #[test]
+    fn extracts_file_urls() {
+        let text = format!(
+            "![a](/static/{h}.png) ![b](/static/{h}_w640.webp) [c](/private/{m}.pdf?expires=1&signature=x) \
+             <img src=\"http://127.0.0.1:9000/zwj-blog/{h}\">",
+            h = HASH,
+            m = "0123456789abcdef0123456789abcdef"
+        );
+        assert_eq!(
+            referenced_stems(&text),
+            vec![
+                "0123456789abcdef0123456789abcdef".to_string(),
+                HASH.to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_partial_matches() {
+        let text = format!(
+            "hash {h} without a path, /static/x{h}.png, /static/{short}.png",
+            h = HASH,
+            short = &HASH[..40]
+        );
+        assert!(referenced_stems(&text).is_empty());
+        assert!(referenced_stems("").is_empty());
+    }
+}
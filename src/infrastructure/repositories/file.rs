@@ -1,35 +1,338 @@
-use actix_multipart::form::tempfile::TempFile;
-use actix_threadpool::run;
+use crate::infrastructure::threadpool::run;
 use async_trait::async_trait;
+use chrono::NaiveDateTime;
 use diesel::prelude::*;
-use std::{collections::HashMap, sync::Arc};
+use diesel::sql_types::{Bool, Integer, Text};
+use futures_util::{stream, StreamExt};
+use sha2::{Digest, Sha256};
+use std::{collections::HashMap, ops::Range, path::Path, sync::Arc};
 
 use crate::{
+    config::Images,
     domain::{
-        models::file::{CreateFile, File},
-        repositories::{file::FileRepository, repository::RepositoryResult},
+        error::RepositoryError,
+        file_hash::SHA256,
+        models::file::{
+            CreateFile, File, FileContent, FileReference, FileVisibility, ImageFormat,
+            ImageVariant, UploadFile, UploadMetadata,
+        },
+        repositories::{
+            file::{FileQueryParams, FileRepository},
+            repository::{
+                fetch_limit, take_next_cursor, Cursor, QueryParams, RepositoryResult, ResultPaging,
+            },
+            storage::{ByteStream, StorageBackend},
+        },
     },
     infrastructure::{
         databases::postgresql::DBConn,
         error::DieselRepositoryError,
-        models::file::{CreateFileDiesel, FileDiesel},
+        images::{image_dimensions, render_variant, strip_metadata},
+        models::file::{CreateFileDiesel, FileDiesel, FileIdDiesel, FileReferenceDiesel},
+        tasks::{Shutdown, TaskSupervisor},
     },
 };
 
+#[derive(Clone)]
 pub struct FileDieselRepository {
     pub pool: Arc<DBConn>,
+    pub storage: Arc<dyn StorageBackend>,
+    pub images: Images,
+    // 上传后生成图片变体的后台任务，关闭时在宽限期内等待完成
+    pub tasks: Arc<TaskSupervisor>,
 }
 
 impl FileDieselRepository {
-    pub fn new(db: Arc<DBConn>) -> Self {
-        FileDieselRepository { pool: db }
+    pub fn new(
+        db: Arc<DBConn>,
+        storage: Arc<dyn StorageBackend>,
+        images: Images,
+        tasks: Arc<TaskSupervisor>,
+    ) -> Self {
+        FileDieselRepository {
+            pool: db,
+            storage,
+            images,
+            tasks,
+        }
+    }
+
+    // 按URL中的文件名（不含扩展名）查找原始文件，与hash算法无关；使用 files_storage_stem_idx 表达式索引
+    async fn find_by_name(&self, name: &str) -> RepositoryResult<Option<File>> {
+        use crate::infrastructure::schema::files::dsl::{files, original_id};
+        let pool = self.pool.clone();
+        let name = name.to_string();
+
+        let result = run(move || {
+            let mut conn = pool.get().unwrap();
+            files
+                .filter(original_id.is_null())
+                .filter(
+                    diesel::dsl::sql::<Bool>("split_part(storage_key, '.', 1) = ")
+                        .bind::<Text, _>(name),
+                )
+                .first::<FileDiesel>(&mut conn)
+                .optional()
+        })
+        .await
+        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+
+        Ok(result.map(|v| v.into()))
     }
+
+    // 按存储中的文件名查找，包括图片变体
+    async fn find_by_key(&self, key: &str) -> RepositoryResult<Option<File>> {
+        use crate::infrastructure::schema::files::dsl::{files, storage_key};
+        let pool = self.pool.clone();
+        let key = key.to_string();
+
+        let result = run(move || {
+            let mut conn = pool.get().unwrap();
+            files
+                .filter(storage_key.eq(key))
+                .first::<FileDiesel>(&mut conn)
+                .optional()
+        })
+        .await
+        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+
+        Ok(result.map(|v| v.into()))
+    }
+
+    async fn find_variant(&self, original_id: i32, name: &str) -> RepositoryResult<Option<File>> {
+        use crate::infrastructure::schema::files::dsl::{
+            files, original_id as original_col, variant as variant_col,
+        };
+        let pool = self.pool.clone();
+        let name = name.to_string();
+
+        let result = run(move || {
+            let mut conn = pool.get().unwrap();
+            files
+                .filter(original_col.eq(original_id))
+                .filter(variant_col.eq(name))
+                .first::<FileDiesel>(&mut conn)
+                .optional()
+        })
+        .await
+        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+
+        Ok(result.map(|v| v.into()))
+    }
+
+    // 检查变体参数是否在配置允许的范围内，避免任意尺寸的请求生成大量文件
+    fn check_variant(&self, variant: &ImageVariant) -> RepositoryResult<()> {
+        if let Some(width) = variant.width {
+            if !self.images.widths.contains(&width) {
+                return Err(RepositoryError::ValidationError(format!(
+                    "不支持的图片宽度: {}",
+                    width
+                )));
+            }
+        }
+        if let Some(format) = variant.format {
+            if !self.allowed_formats().contains(&format) {
+                return Err(RepositoryError::ValidationError(format!(
+                    "不支持的图片格式: {}",
+                    format.extension()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn allowed_formats(&self) -> Vec<ImageFormat> {
+        self.images
+            .formats
+            .iter()
+            .filter_map(|f| ImageFormat::from_extension(f))
+            .collect()
+    }
+
+    // 生成变体，写入存储并记录到数据库
+    async fn generate_variant(
+        &self,
+        original: &File,
+        original_format: ImageFormat,
+        source: Arc<Vec<u8>>,
+        variant: ImageVariant,
+    ) -> RepositoryResult<FileContent> {
+        use crate::infrastructure::schema::files::dsl::files;
+
+        let name = variant.name(original_format);
+        let format = variant.format.unwrap_or(original_format);
+        let rendered = run(move || render_variant(&source, variant.width, format))
+            .await
+            .map_err(|e| RepositoryError::ValidationError(format!("图片处理失败: {}", e)))?;
+
+        let key = format!("{}_{}", key_stem(&original.storage_key), name);
+        let file_hash = format!("{:x}", Sha256::digest(&rendered.bytes));
+        let size = rendered.bytes.len() as u64;
+        self.storage.put_bytes(&key, rendered.bytes).await?;
+
+        let new_file = CreateFileDiesel::from(CreateFile {
+            file_hash: file_hash.clone(),
+            file_path: self.storage.url(&key),
+            original_id: Some(original.id),
+            variant: Some(name),
+            width: Some(rendered.width as i32),
+            height: Some(rendered.height as i32),
+            mime_type: Some(content_type(&key)),
+            byte_size: Some(size as i64),
+            original_filename: None,
+            hash_algorithm: SHA256.to_string(),
+            storage_key: key.clone(),
+            visibility: original.visibility,
+        });
+        let pool = self.pool.clone();
+        // 并发生成同一变体时保留先写入的记录
+        run(move || {
+            let mut conn = pool.get().unwrap();
+            diesel::insert_into(files)
+                .values(new_file)
+                .on_conflict_do_nothing()
+                .execute(&mut conn)
+        })
+        .await
+        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+
+        Ok(FileContent {
+            content_type: content_type(&key),
+            file_hash,
+            size,
+            storage_key: key,
+            visibility: original.visibility,
+        })
+    }
+
+    // 上传后生成配置中的所有尺寸和格式组合，服务关闭时停止，剩余的变体在首次访问时生成
+    async fn generate_all_variants(
+        &self,
+        original: File,
+        shutdown: Shutdown,
+    ) -> RepositoryResult<()> {
+        let key = original.storage_key.clone();
+        let Some(original_format) = image_format(&key) else {
+            return Ok(());
+        };
+        let Some(source) = self.storage.get(&key).await? else {
+            return Ok(());
+        };
+        let source = Arc::new(source);
+        let original_width = original.width.unwrap_or(i32::MAX);
+
+        // 不放大，只生成小于原图宽度的尺寸
+        let mut widths = vec![None];
+        widths.extend(
+            self.images
+                .widths
+                .iter()
+                .filter(|w| (**w as i64) < original_width as i64)
+                .map(|w| Some(*w)),
+        );
+        let mut formats = vec![None];
+        formats.extend(self.allowed_formats().into_iter().map(Some));
+
+        for width in &widths {
+            for format in &formats {
+                let variant = ImageVariant {
+                    width: *width,
+                    format: *format,
+                };
+                if variant.width.is_none() && variant.format.is_none() {
+                    continue;
+                }
+                if shutdown.is_requested() {
+                    return Ok(());
+                }
+                self.generate_variant(&original, original_format, source.clone(), variant)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+    // 清除上传图片的元数据并覆盖源文件，返回清除后的内容；无需修改时返回 None
+    async fn strip_metadata(
+        &self,
+        source: &Path,
+        metadata: &UploadMetadata,
+    ) -> RepositoryResult<Option<Vec<u8>>> {
+        let Some(format) = image_format(&format!("x.{}", metadata.extension)) else {
+            return Ok(None);
+        };
+        if !metadata.strip_metadata {
+            return Ok(None);
+        }
+        let storage_error = |e: std::io::Error| RepositoryError::StorageError(e.to_string());
+        let bytes = tokio::fs::read(source).await.map_err(storage_error)?;
+        let stripped = run(move || strip_metadata(&bytes, format))
+            .await
+            .map_err(|e| RepositoryError::ValidationError(format!("图片处理失败: {}", e)))?;
+        if let Some(stripped) = &stripped {
+            tokio::fs::write(source, stripped)
+                .await
+                .map_err(storage_error)?;
+        }
+        Ok(stripped)
+    }
+}
+
+// 通过 content_files 关联引用文件 $1 的内容
+const REFERENCES_QUERY: &str = "\
+    SELECT 'blog' AS kind, b.id, b.title, cf.role \
+      FROM content_files cf JOIN blogs b ON b.id = cf.blog_id WHERE cf.file_id = $1 \
+    UNION ALL SELECT 'snippet', s.id, s.title, cf.role \
+      FROM content_files cf JOIN snippets s ON s.id = cf.snippet_id WHERE cf.file_id = $1 \
+    UNION ALL SELECT 'note', n.id, left(n.body, 50), cf.role \
+      FROM content_files cf JOIN notes n ON n.id = cf.note_id WHERE cf.file_id = $1 \
+    UNION ALL SELECT 'tag', t.id, t.name, cf.role \
+      FROM content_files cf JOIN tags t ON t.id = cf.tag_id WHERE cf.file_id = $1";
+
+// 存储文件名去掉扩展名的部分，即文件URL中的hash
+fn key_stem(key: &str) -> &str {
+    key.split('.').next().unwrap_or(key)
+}
+
+fn image_format(key: &str) -> Option<ImageFormat> {
+    std::path::Path::new(key)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(ImageFormat::from_extension)
+        // AVIF 只作为输出格式，不支持解码
+        .filter(|format| *format != ImageFormat::Avif)
+}
+
+fn content_type(key: &str) -> String {
+    mime_guess::from_path(key)
+        .first_or_octet_stream()
+        .to_string()
+}
+
+// 优先使用上传时检测并记录的类型
+fn file_content(file: &File, size: u64) -> FileContent {
+    FileContent {
+        storage_key: file.storage_key.clone(),
+        size,
+        content_type: file
+            .mime_type
+            .clone()
+            .unwrap_or_else(|| content_type(&file.storage_key)),
+        file_hash: file.file_hash.clone(),
+        visibility: file.visibility,
+    }
+}
+
+// 存储文件名只能是单个路径段
+fn valid_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains(['/', '\\']) && !name.starts_with('.')
 }
 
 #[async_trait]
 impl FileRepository for FileDieselRepository {
     async fn find_by_hash(&self, file_hash: &str) -> RepositoryResult<Option<File>> {
-        use crate::infrastructure::schema::files::dsl::{file_hash as hash_col, files};
+        use crate::infrastructure::schema::files::dsl::{
+            file_hash as hash_col, files, original_id,
+        };
         let pool = self.pool.clone();
         let hash = file_hash.to_string();
 
@@ -37,6 +340,7 @@ impl FileRepository for FileDieselRepository {
             let mut conn = pool.get().unwrap();
             files
                 .filter(hash_col.eq(hash))
+                .filter(original_id.is_null())
                 .first::<FileDiesel>(&mut conn)
                 .optional()
         })
@@ -62,28 +366,22 @@ impl FileRepository for FileDieselRepository {
         Ok(result.into())
     }
 
-    async fn save_files(&self, files: Vec<TempFile>) -> RepositoryResult<HashMap<String, String>> {
+    async fn save_files(
+        &self,
+        files: Vec<UploadFile>,
+    ) -> RepositoryResult<HashMap<String, String>> {
         if files.is_empty() {
             return Ok(HashMap::new());
         }
 
-        // 提前创建存储目录，只创建一次
-        let upload_dir = "uploads";
-        tokio::fs::create_dir_all(upload_dir)
-            .await
-            .map_err(|e| crate::domain::error::RepositoryError::DatabaseError(e.to_string()))?;
-
         let mut file_urls = HashMap::new();
 
-        for file in files {
-            // 获取原始文件名
-            let original_filename = file
-                .file_name
-                .clone()
-                .unwrap_or_else(|| "unknown".to_string());
+        for upload in files {
+            let file = upload.file;
+            let original_filename = upload.metadata.original_filename.clone();
 
             // 流式计算hash，避免一次性读取整个文件到内存
-            let mut hasher = md5::Context::new();
+            let mut hasher = Sha256::new();
             let mut file_reader = tokio::fs::File::open(&file.file.path())
                 .await
                 .map_err(|e| crate::domain::error::RepositoryError::DatabaseError(e.to_string()))?;
@@ -97,45 +395,394 @@ impl FileRepository for FileDieselRepository {
                 if bytes_read == 0 {
                     break;
                 }
-                hasher.consume(&buffer[..bytes_read]);
+                hasher.update(&buffer[..bytes_read]);
             }
 
             let file_hash = format!("{:x}", hasher.finalize());
+            let url = self
+                .save_file(file.file.path(), file_hash, upload.metadata)
+                .await?;
+            file_urls.insert(original_filename, url);
+        }
 
-            // 检查数据库中是否已存在该hash的文件
-            if let Some(existing_file) = self.find_by_hash(&file_hash).await? {
-                // 文件已存在，直接使用数据库中的路径
-                file_urls.insert(original_filename, existing_file.file_path);
-                continue;
+        Ok(file_urls)
+    }
+
+    async fn save_file(
+        &self,
+        source: &Path,
+        file_hash: String,
+        mut metadata: UploadMetadata,
+    ) -> RepositoryResult<String> {
+        // 清除元数据会改变文件内容，需要在去重之前重新计算hash和大小
+        let file_hash = match self.strip_metadata(source, &metadata).await? {
+            Some(stripped) => {
+                metadata.byte_size = stripped.len() as i64;
+                format!("{:x}", Sha256::digest(&stripped))
             }
+            None => file_hash,
+        };
 
-            // 获取文件扩展名
-            let extension = std::path::Path::new(&original_filename)
-                .extension()
-                .and_then(|ext| ext.to_str())
-                .map(|ext| format!(".{}", ext))
-                .unwrap_or_default();
-
-            // 使用hash作为文件名
-            let unique_filename = format!("{}{}", file_hash, extension);
-            let file_path = format!("{}/{}", upload_dir, unique_filename);
-            let db_file_path = format!("/static/{}", unique_filename); // 不带host的路径
-
-            // 保存文件到磁盘
-            file.file
-                .persist(&file_path)
-                .map_err(|e| crate::domain::error::RepositoryError::DatabaseError(e.to_string()))?;
+        // 检查数据库中是否已存在该hash的原始文件
+        if let Some(existing_file) = self.find_by_hash(&file_hash).await? {
+            // 同一内容只保存一份，可见性不同时不能静默复用，需要先修改已有文件的可见性
+            if existing_file.visibility != metadata.visibility {
+                return Err(RepositoryError::Conflict(format!(
+                    "相同内容的文件 {} 已以 {} 可见性存在，请通过 PATCH /api/files/{}/visibility 修改可见性",
+                    existing_file.id,
+                    existing_file.visibility.as_str(),
+                    existing_file.id
+                )));
+            }
+            // 文件已存在，直接使用数据库中的路径
+            return Ok(existing_file.file_path);
+        }
+
+        // 使用hash作为文件名，扩展名取自检测到的文件类型
+        let unique_filename = format!("{}.{}", file_hash, metadata.extension);
+        let db_file_path = self.storage.url(&unique_filename);
+
+        // 图片记录原始尺寸
+        let dimensions = image_format(&unique_filename).and_then(|_| image_dimensions(source));
+
+        // 保存文件到存储后端
+        self.storage.put_file(&unique_filename, source).await?;
 
-            // 保存文件记录到数据库
-            let create_file = CreateFile {
-                file_hash,
-                file_path: db_file_path.clone(),
-            };
-            let _file_record = self.create(&create_file).await?;
+        // 保存文件记录到数据库
+        let create_file = CreateFile {
+            file_hash,
+            file_path: db_file_path.clone(),
+            original_id: None,
+            variant: None,
+            width: dimensions.map(|(w, _)| w as i32),
+            height: dimensions.map(|(_, h)| h as i32),
+            mime_type: Some(metadata.mime_type),
+            byte_size: Some(metadata.byte_size),
+            original_filename: Some(metadata.original_filename),
+            hash_algorithm: SHA256.to_string(),
+            storage_key: unique_filename,
+            visibility: metadata.visibility,
+        };
+        let file_record = self.create(&create_file).await?;
 
-            file_urls.insert(original_filename, db_file_path);
+        // 后台生成图片变体，不阻塞上传请求；失败的变体会在首次访问时重新生成
+        if self.images.eager && dimensions.is_some() {
+            let repository = self.clone();
+            self.tasks
+                .spawn("image-variants", move |shutdown| async move {
+                    if let Err(e) = repository
+                        .generate_all_variants(file_record, shutdown)
+                        .await
+                    {
+                        tracing::warn!("Failed to generate image variants: {:?}", e);
+                    }
+                });
         }
 
-        Ok(file_urls)
+        Ok(db_file_path)
+    }
+
+    async fn load(&self, name: &str) -> RepositoryResult<Option<FileContent>> {
+        if !valid_name(name) {
+            return Ok(None);
+        }
+        // 不带扩展名时按文件名查找原始文件
+        let file = if name.contains('.') {
+            self.find_by_key(name).await?
+        } else {
+            self.find_by_name(name).await?
+        };
+        let Some(file) = file else {
+            return Ok(None);
+        };
+        Ok(self
+            .storage
+            .size(&file.storage_key)
+            .await?
+            .map(|size| file_content(&file, size)))
+    }
+
+    async fn load_variant(
+        &self,
+        name: &str,
+        variant: &ImageVariant,
+        include_private: bool,
+    ) -> RepositoryResult<Option<FileContent>> {
+        if !valid_name(name) {
+            return Ok(None);
+        }
+        self.check_variant(variant)?;
+
+        let Some(original) = self.find_by_name(key_stem(name)).await? else {
+            return Ok(None);
+        };
+        if original.visibility != FileVisibility::Public && !include_private {
+            return Ok(None);
+        }
+        let original_key = original.storage_key.clone();
+        let Some(original_format) = image_format(&original_key) else {
+            return Err(RepositoryError::ValidationError(
+                "该文件不支持图片处理".to_string(),
+            ));
+        };
+
+        let variant_name = variant.name(original_format);
+        if let Some(existing) = self.find_variant(original.id, &variant_name).await? {
+            if let Some(size) = self.storage.size(&existing.storage_key).await? {
+                return Ok(Some(file_content(&existing, size)));
+            }
+        }
+
+        // 变体不存在时按需生成
+        let Some(source) = self.storage.get(&original_key).await? else {
+            return Ok(None);
+        };
+        self.generate_variant(&original, original_format, Arc::new(source), *variant)
+            .await
+            .map(Some)
+    }
+
+    async fn open(
+        &self,
+        content: &FileContent,
+        range: Range<u64>,
+    ) -> RepositoryResult<Option<ByteStream>> {
+        self.storage.get_range(&content.storage_key, range).await
+    }
+
+    async fn list(&self, params: FileQueryParams) -> RepositoryResult<ResultPaging<File>> {
+        use crate::infrastructure::schema::files::dsl::{
+            created_at, files, id, mime_type, original_id,
+        };
+        let pool = self.pool.clone();
+        let limit = params.limit();
+        let with_total = params.with_total();
+        let cursor = params.cursor().map(Cursor::decode).transpose()?;
+        // 游标分页时忽略 offset
+        let offset = if cursor.is_some() { 0 } else { params.offset() };
+        let mime_prefix = params.mime_type.map(|m| format!("{}%", m));
+
+        // 获取总记录数（可选）
+        let total = if with_total {
+            let pool_clone = pool.clone();
+            let mime_prefix = mime_prefix.clone();
+            let count = run(move || {
+                let mut conn = pool_clone.get().unwrap();
+                let mut query = files.filter(original_id.is_null()).into_boxed();
+                if let Some(prefix) = mime_prefix {
+                    query = query.filter(mime_type.like(prefix));
+                }
+                query.count().get_result::<i64>(&mut conn)
+            })
+            .await
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+            Some(count)
+        } else {
+            None
+        };
+
+        // 获取分页数据，多取一条用于判断是否还有下一页
+        let mut result = run(move || {
+            let mut conn = pool.get().unwrap();
+            let mut query = files.filter(original_id.is_null()).into_boxed();
+            if let Some(prefix) = mime_prefix {
+                query = query.filter(mime_type.like(prefix));
+            }
+            if let Some(cursor) = cursor {
+                let cursor_time = cursor.created_at.naive_utc();
+                query = query.filter(
+                    created_at
+                        .lt(cursor_time)
+                        .or(created_at.eq(cursor_time).and(id.lt(cursor.id))),
+                );
+            }
+            query
+                .order((created_at.desc(), id.desc()))
+                .limit(fetch_limit(limit))
+                .offset(offset)
+                .load::<FileDiesel>(&mut conn)
+        })
+        .await
+        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+        let next_cursor = take_next_cursor(&mut result, limit, |v| {
+            Cursor::new(v.created_at.and_utc(), v.id)
+        });
+
+        Ok(ResultPaging {
+            total,
+            items: result.into_iter().map(|v| v.into()).collect(),
+            next_cursor,
+        })
+    }
+
+    async fn get(&self, file_id: i32) -> RepositoryResult<Option<File>> {
+        use crate::infrastructure::schema::files::dsl::{files, id};
+        let pool = self.pool.clone();
+        let result = run(move || {
+            let mut conn = pool.get().unwrap();
+            files
+                .filter(id.eq(file_id))
+                .first::<FileDiesel>(&mut conn)
+                .optional()
+        })
+        .await
+        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+
+        Ok(result.map(|v| v.into()))
+    }
+
+    async fn list_variants(&self, original: i32) -> RepositoryResult<Vec<File>> {
+        use crate::infrastructure::schema::files::dsl::{files, id, original_id};
+        let pool = self.pool.clone();
+        let result = run(move || {
+            let mut conn = pool.get().unwrap();
+            files
+                .filter(original_id.eq(original))
+                .order(id.asc())
+                .load::<FileDiesel>(&mut conn)
+        })
+        .await
+        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+
+        Ok(result.into_iter().map(|v| v.into()).collect())
+    }
+
+    async fn find_references(&self, file: &File) -> RepositoryResult<Vec<FileReference>> {
+        let pool = self.pool.clone();
+        let file_id = file.id;
+        let result = run(move || {
+            let mut conn = pool.get().unwrap();
+            diesel::sql_query(format!("{} ORDER BY kind, id, role", REFERENCES_QUERY))
+                .bind::<Integer, _>(file_id)
+                .load::<FileReferenceDiesel>(&mut conn)
+        })
+        .await
+        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+
+        Ok(result.into_iter().map(|v| v.into()).collect())
+    }
+
+    async fn find_orphans(&self, uploaded_before: NaiveDateTime) -> RepositoryResult<Vec<File>> {
+        use crate::infrastructure::schema::files::dsl::{files, id};
+        let pool = self.pool.clone();
+        let result = run(move || {
+            let mut conn = pool.get().unwrap();
+            let ids = diesel::sql_query(
+                "SELECT f.id FROM files f \
+                 WHERE f.original_id IS NULL AND f.upload_time < $1 \
+                   AND NOT EXISTS (SELECT 1 FROM content_files cf WHERE cf.file_id = f.id)",
+            )
+            .bind::<diesel::sql_types::Timestamp, _>(uploaded_before)
+            .load::<FileIdDiesel>(&mut conn)?
+            .into_iter()
+            .map(|row| row.id)
+            .collect::<Vec<i32>>();
+            files
+                .filter(id.eq_any(ids))
+                .order(id.asc())
+                .load::<FileDiesel>(&mut conn)
+        })
+        .await
+        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+
+        Ok(result.into_iter().map(|v| v.into()).collect())
+    }
+
+    async fn delete(&self, file_id: i32) -> RepositoryResult<bool> {
+        use crate::infrastructure::schema::files::dsl::{files, id};
+        let Some(file) = self.get(file_id).await? else {
+            return Ok(false);
+        };
+        let variants = self.list_variants(file_id).await?;
+
+        // 变体记录通过外键级联删除
+        let pool = self.pool.clone();
+        run(move || {
+            let mut conn = pool.get().unwrap();
+            diesel::delete(files.filter(id.eq(file_id))).execute(&mut conn)
+        })
+        .await
+        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+
+        // 记录已删除，存储内容删除失败只记录日志
+        for stored in std::iter::once(&file).chain(variants.iter()) {
+            let key = stored.storage_key.clone();
+            if let Err(e) = self.storage.delete(&key).await {
+                tracing::warn!("Failed to delete stored file {}: {:?}", key, e);
+            }
+        }
+        Ok(true)
+    }
+
+    async fn list_all(&self) -> RepositoryResult<Vec<File>> {
+        use crate::infrastructure::schema::files::dsl::{files, id};
+        let pool = self.pool.clone();
+        let result = run(move || {
+            let mut conn = pool.get().unwrap();
+            files.order(id.asc()).load::<FileDiesel>(&mut conn)
+        })
+        .await
+        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+
+        Ok(result.into_iter().map(|v| v.into()).collect())
+    }
+
+    async fn read(&self, file: &File) -> RepositoryResult<Option<ByteStream>> {
+        match self.storage.size(&file.storage_key).await? {
+            // 空区间在 S3 上是无效的 Range 请求
+            Some(0) => Ok(Some(stream::empty().boxed())),
+            Some(size) => self.storage.get_range(&file.storage_key, 0..size).await,
+            None => Ok(None),
+        }
+    }
+
+    async fn update_hash(
+        &self,
+        file_id: i32,
+        new_hash: &str,
+        algorithm: &str,
+    ) -> RepositoryResult<()> {
+        use crate::infrastructure::schema::files::dsl::{file_hash, files, hash_algorithm, id};
+        let pool = self.pool.clone();
+        let new_hash = new_hash.to_string();
+        let algorithm = algorithm.to_string();
+        run(move || {
+            let mut conn = pool.get().unwrap();
+            diesel::update(files.filter(id.eq(file_id)))
+                .set((file_hash.eq(new_hash), hash_algorithm.eq(algorithm)))
+                .execute(&mut conn)
+        })
+        .await
+        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+        Ok(())
+    }
+
+    async fn update_visibility(
+        &self,
+        file_id: i32,
+        new_visibility: FileVisibility,
+    ) -> RepositoryResult<Option<File>> {
+        use crate::infrastructure::schema::files::dsl::{files, id, original_id, visibility};
+        let pool = self.pool.clone();
+        let result = run(move || {
+            let mut conn = pool.get().unwrap();
+            conn.transaction(|conn| {
+                let updated = diesel::update(files.filter(id.eq(file_id)))
+                    .set(visibility.eq(new_visibility.as_str()))
+                    .get_result::<FileDiesel>(conn)
+                    .optional()?;
+                // 图片变体与原始文件使用相同的可见性
+                if updated.is_some() {
+                    diesel::update(files.filter(original_id.eq(file_id)))
+                        .set(visibility.eq(new_visibility.as_str()))
+                        .execute(conn)?;
+                }
+                Ok::<_, diesel::result::Error>(updated)
+            })
+        })
+        .await
+        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+
+        Ok(result.map(|v| v.into()))
     }
 }
@@ -1,4 +1,5 @@
 pub mod blog;
+pub mod content_file;
 pub mod file;
 pub mod note;
 pub mod redis;
@@ -1,18 +1,23 @@
 use std::sync::Arc;
 
-use actix_threadpool::run;
+use crate::infrastructure::threadpool::run;
 use async_trait::async_trait;
+use diesel::pg::PgConnection;
 use diesel::prelude::*;
 
 use crate::{
     domain::{
         models::{
+            file::{AttachmentRole, FileReferenceKind},
             note::{CreateNote, Note, UpdateNote},
             tag::Tag,
+            version::VersionedUpdate,
         },
         repositories::{
             note::{NoteQueryParams, NoteRepository},
-            repository::{QueryParams, RepositoryResult, ResultPaging},
+            repository::{
+                fetch_limit, take_next_cursor, Cursor, QueryParams, RepositoryResult, ResultPaging,
+            },
         },
     },
     infrastructure::{
@@ -22,9 +27,48 @@ use crate::{
             note::{CreateNoteDiesel, NoteDiesel},
             tag::TagDiesel,
         },
+        repositories::content_file::sync_attachments,
     },
 };
 
+// 替换笔记的标签关系，在写入笔记的同一事务中调用
+fn replace_note_tags(
+    conn: &mut PgConnection,
+    note_id: i32,
+    tag_ids: &[i32],
+) -> Result<(), diesel::result::Error> {
+    use crate::infrastructure::schema::note_tag_relations;
+
+    // 删除现有关系
+    diesel::delete(note_tag_relations::table)
+        .filter(note_tag_relations::note_id.eq(note_id))
+        .execute(conn)?;
+
+    // 插入新关系
+    for tag_id in tag_ids {
+        diesel::insert_into(note_tag_relations::table)
+            .values((
+                note_tag_relations::note_id.eq(note_id),
+                note_tag_relations::tag_id.eq(tag_id),
+            ))
+            .execute(conn)?;
+    }
+    Ok(())
+}
+
+// 按正文重建笔记关联的文件
+fn sync_note_attachments(
+    conn: &mut PgConnection,
+    note: &NoteDiesel,
+) -> Result<(), diesel::result::Error> {
+    sync_attachments(
+        conn,
+        FileReferenceKind::Note,
+        note.id,
+        &[(AttachmentRole::Inline, Some(&note.body))],
+    )
+}
+
 pub struct NoteDieselRepository {
     pub pool: Arc<DBConn>,
 }
@@ -52,37 +96,6 @@ impl NoteDieselRepository {
 
         Ok(result.into_iter().map(|tag| tag.into()).collect())
     }
-
-    // 辅助方法：设置笔记的标签关系
-    async fn set_note_tags(&self, note_id: i32, tag_ids: &[i32]) -> RepositoryResult<()> {
-        use crate::infrastructure::schema::note_tag_relations;
-        let pool = self.pool.clone();
-        let tag_ids = tag_ids.to_vec();
-
-        run(move || -> Result<(), diesel::result::Error> {
-            let mut conn = pool.get().unwrap();
-
-            // 删除现有关系
-            diesel::delete(note_tag_relations::table)
-                .filter(note_tag_relations::note_id.eq(note_id))
-                .execute(&mut conn)?;
-
-            // 插入新关系
-            for tag_id in tag_ids {
-                diesel::insert_into(note_tag_relations::table)
-                    .values((
-                        note_tag_relations::note_id.eq(note_id),
-                        note_tag_relations::tag_id.eq(tag_id),
-                    ))
-                    .execute(&mut conn)?;
-            }
-            Ok(())
-        })
-        .await
-        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
-
-        Ok(())
-    }
 }
 
 #[async_trait]
@@ -94,18 +107,18 @@ impl NoteRepository for NoteDieselRepository {
         let mut conn = self.pool.get().unwrap();
 
         let result: NoteDiesel = run(move || {
-            diesel::insert_into(notes)
-                .values(new_note_diesel)
-                .get_result(&mut conn)
+            conn.transaction(|conn| {
+                let note = diesel::insert_into(notes)
+                    .values(new_note_diesel)
+                    .get_result::<NoteDiesel>(conn)?;
+                sync_note_attachments(conn, &note)?;
+                replace_note_tags(conn, note.id, &tag_ids)?;
+                Ok::<_, diesel::result::Error>(note)
+            })
         })
         .await
         .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
 
-        // 设置标签关系
-        if !tag_ids.is_empty() {
-            self.set_note_tags(result.id, &tag_ids).await?;
-        }
-
         // 加载标签并转换为Note
         let tags = self.load_note_tags(result.id).await?;
         let mut note: Note = result.into();
@@ -118,15 +131,18 @@ impl NoteRepository for NoteDieselRepository {
         use crate::infrastructure::schema::notes::dsl::*;
         let pool = self.pool.clone();
         let limit_val = params.limit();
-        let offset_val = params.offset();
+        let with_total = params.with_total();
+        let cursor = params.cursor().map(Cursor::decode).transpose()?;
+        // 游标分页时忽略 offset
+        let offset_val = if cursor.is_some() { 0 } else { params.offset() };
         let body_filter = params.body.clone();
         let published_filter = params.published;
 
-        // 获取总数
-        let total = {
+        // 获取总数（可选）
+        let total = if with_total {
             let pool_clone = pool.clone();
             let body_filter_clone = body_filter.clone();
-            run(move || {
+            let count = run(move || {
                 let mut conn = pool_clone.get().unwrap();
                 let mut query = notes.into_boxed();
 
@@ -140,11 +156,14 @@ impl NoteRepository for NoteDieselRepository {
                 query.count().get_result::<i64>(&mut conn)
             })
             .await
-            .map_err(|v| DieselRepositoryError::from(v).into_inner())?
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+            Some(count)
+        } else {
+            None
         };
 
         // 获取分页数据
-        let result = run(move || {
+        let mut result = run(move || {
             let mut conn = pool.get().unwrap();
             let mut query = notes.into_boxed();
 
@@ -154,15 +173,25 @@ impl NoteRepository for NoteDieselRepository {
             if let Some(published_val) = published_filter {
                 query = query.filter(published.eq(published_val));
             }
+            if let Some(cursor) = cursor {
+                query = query.filter(
+                    created_at
+                        .lt(cursor.created_at)
+                        .or(created_at.eq(cursor.created_at).and(id.lt(cursor.id))),
+                );
+            }
 
+            // 多取一条用于判断是否还有下一页
             query
-                .order(created_at.desc())
-                .limit(limit_val)
+                .order((created_at.desc(), id.desc()))
+                .limit(fetch_limit(limit_val))
                 .offset(offset_val)
                 .load::<NoteDiesel>(&mut conn)
         })
         .await
         .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+        let next_cursor =
+            take_next_cursor(&mut result, limit_val, |v| Cursor::new(v.created_at, v.id));
 
         // 为每个笔记加载标签
         let mut notes_with_tags = Vec::new();
@@ -176,6 +205,7 @@ impl NoteRepository for NoteDieselRepository {
         Ok(ResultPaging {
             total,
             items: notes_with_tags,
+            next_cursor,
         })
     }
 
@@ -229,7 +259,7 @@ impl NoteRepository for NoteDieselRepository {
         &self,
         note_id: i32,
         update_note: &UpdateNote,
-    ) -> RepositoryResult<Option<Note>> {
+    ) -> RepositoryResult<VersionedUpdate<Note>> {
         use crate::infrastructure::schema::notes::dsl::*;
         let pool = self.pool.clone();
         let update_note = update_note.clone();
@@ -237,41 +267,58 @@ impl NoteRepository for NoteDieselRepository {
         let result = run(move || {
             let mut conn = pool.get().unwrap();
 
-            // 先获取现有记录
-            let existing = match notes
-                .filter(id.eq(note_id))
-                .first::<NoteDiesel>(&mut conn)
-                .optional()?
-            {
-                Some(note) => note,
-                None => return Ok(None),
-            };
+            conn.transaction(|conn| {
+                // 先获取并锁定现有记录
+                let existing = match notes
+                    .filter(id.eq(note_id))
+                    .for_update()
+                    .first::<NoteDiesel>(conn)
+                    .optional()?
+                {
+                    Some(note) => note,
+                    None => return Ok(VersionedUpdate::NotFound),
+                };
+                if update_note.version.is_some_and(|v| v != existing.version) {
+                    return Ok(VersionedUpdate::Conflict(existing));
+                }
 
-            // 准备更新值
-            let new_body = update_note.body.unwrap_or(existing.body);
-            let new_published = update_note.published.unwrap_or(existing.published);
+                // 准备更新值
+                let new_body = update_note.body.unwrap_or(existing.body);
+                let new_published = update_note.published.unwrap_or(existing.published);
 
-            // 执行更新
-            diesel::update(notes.filter(id.eq(note_id)))
-                .set((body.eq(new_body), published.eq(new_published)))
-                .get_result::<NoteDiesel>(&mut conn)
-                .optional()
+                // 执行更新
+                let updated = diesel::update(notes.filter(id.eq(note_id)))
+                    .set((
+                        body.eq(new_body),
+                        published.eq(new_published),
+                        version.eq(version + 1),
+                    ))
+                    .get_result::<NoteDiesel>(conn)?;
+                sync_note_attachments(conn, &updated)?;
+                // 标签关系与内容在同一事务中更新，锁定期间其他请求看不到只更新了一半的内容
+                if let Some(tag_ids) = &update_note.tag_ids {
+                    replace_note_tags(conn, updated.id, tag_ids)?;
+                }
+                Ok::<_, diesel::result::Error>(VersionedUpdate::Updated(updated))
+            })
         })
         .await
         .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
 
-        if let Some(note_diesel) = result {
-            // 更新标签关系
-            if let Some(tag_ids) = &update_note.tag_ids {
-                self.set_note_tags(note_diesel.id, tag_ids).await?;
+        match result {
+            VersionedUpdate::Updated(note_diesel) => {
+                let tags = self.load_note_tags(note_diesel.id).await?;
+                let mut note: Note = note_diesel.into();
+                note.tags = tags;
+                Ok(VersionedUpdate::Updated(note))
             }
-
-            let tags = self.load_note_tags(note_diesel.id).await?;
-            let mut note: Note = note_diesel.into();
-            note.tags = tags;
-            Ok(Some(note))
-        } else {
-            Ok(None)
+            VersionedUpdate::Conflict(note_diesel) => {
+                let tags = self.load_note_tags(note_diesel.id).await?;
+                let mut note: Note = note_diesel.into();
+                note.tags = tags;
+                Ok(VersionedUpdate::Conflict(note))
+            }
+            VersionedUpdate::NotFound => Ok(VersionedUpdate::NotFound),
         }
     }
 
@@ -323,7 +370,10 @@ impl NoteRepository for NoteDieselRepository {
 
             // 切换发布状态
             diesel::update(notes.filter(id.eq(note_id)))
-                .set(published.eq(!current_note.published))
+                .set((
+                    published.eq(!current_note.published),
+                    version.eq(version + 1),
+                ))
                 .get_result::<NoteDiesel>(&mut conn)
                 .optional()
         })
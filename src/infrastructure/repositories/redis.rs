@@ -1,13 +1,30 @@
 use async_trait::async_trait;
-use redis::{AsyncCommands, Client};
+use redis::{AsyncCommands, Client, RedisResult};
 use serde::{Deserialize, Serialize};
-use std::{sync::Arc, time::Duration};
+use std::{future::Future, sync::Arc, time::Duration};
+use tracing::Instrument;
 
 use crate::{
     domain::error::RepositoryError,
+    metrics::time_redis,
     domain::repositories::{redis::RedisRepository, repository::RepositoryResult},
 };
 
+// 执行 Redis 命令，记录耗时指标和链路 span
+async fn command<F, T>(name: &str, fut: F) -> RedisResult<T>
+where
+    F: Future<Output = RedisResult<T>>,
+{
+    let span = tracing::info_span!(
+        "redis.command",
+        otel.name = format!("redis {}", name),
+        otel.kind = "client",
+        db.system = "redis",
+        db.operation = name,
+    );
+    time_redis(name, fut).instrument(span).await
+}
+
 pub struct RedisClientRepository {
     client: Arc<Client>,
 }
@@ -33,8 +50,7 @@ where
         let serialized_value = serde_json::to_string(&value)
             .map_err(|e| RepositoryError::SerializationError(e.to_string()))?;
 
-        let _: () = conn
-            .set(key, serialized_value)
+        let _: () = command("SET", conn.set(key, serialized_value))
             .await
             .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
@@ -52,8 +68,7 @@ where
         let serialized_value = serde_json::to_string(&value)
             .map_err(|e| RepositoryError::SerializationError(e.to_string()))?;
 
-        let _: () = conn
-            .set_ex(key, serialized_value, ttl.as_secs())
+        let _: () = command("SETEX", conn.set_ex(key, serialized_value, ttl.as_secs()))
             .await
             .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
@@ -67,8 +82,7 @@ where
             .await
             .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
-        let result: Option<String> = conn
-            .get(key)
+        let result: Option<String> = command("GET", conn.get(key))
             .await
             .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
@@ -89,8 +103,7 @@ where
             .await
             .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
-        let _: () = conn
-            .del(key)
+        let _: () = command("DEL", conn.del(key))
             .await
             .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
@@ -104,8 +117,7 @@ where
             .await
             .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
-        let exists: bool = conn
-            .exists(key)
+        let exists: bool = command("EXISTS", conn.exists(key))
             .await
             .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
@@ -122,8 +134,7 @@ where
         let serialized_value = serde_json::to_string(&value)
             .map_err(|e| RepositoryError::SerializationError(e.to_string()))?;
 
-        let _: () = conn
-            .set_ex(key, serialized_value, seconds)
+        let _: () = command("SETEX", conn.set_ex(key, serialized_value, seconds))
             .await
             .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
@@ -1,30 +1,103 @@
 use std::sync::Arc;
 
-use actix_threadpool::run;
+use crate::infrastructure::threadpool::run;
 use async_trait::async_trait;
+use diesel::pg::PgConnection;
 use diesel::prelude::*;
 
 use crate::{
     domain::{
         models::{
+            file::{AttachmentRole, FileReferenceKind},
             snippet::{CreateSnippet, Snippet, UpdateSnippet},
             tag::Tag,
+            version::VersionedUpdate,
         },
         repositories::{
-            repository::{QueryParams, RepositoryResult, ResultPaging},
+            repository::{
+                fetch_limit, take_next_cursor, Cursor, QueryParams, RepositoryResult, ResultPaging,
+            },
             snippet::{SnippetQueryParams, SnippetRepository},
         },
     },
     infrastructure::{
         databases::postgresql::DBConn,
-        error::DieselRepositoryError,
+        error::{slug_conflict_error, DieselRepositoryError},
         models::{
             snippet::{CreateSnippetDiesel, SnippetDiesel},
             tag::TagDiesel,
         },
+        repositories::content_file::{load_attachments, sync_attachments},
     },
 };
 
+// 记录代码片段的旧 slug；新 slug 如果曾是历史 slug，则移除该历史记录
+fn record_slug_change(
+    conn: &mut PgConnection,
+    snippet_id: i32,
+    old_slug: &str,
+    new_slug: &str,
+) -> Result<(), diesel::result::Error> {
+    use crate::infrastructure::schema::snippet_slug_histories;
+
+    diesel::insert_into(snippet_slug_histories::table)
+        .values((
+            snippet_slug_histories::old_slug.eq(old_slug),
+            snippet_slug_histories::snippet_id.eq(snippet_id),
+        ))
+        .on_conflict(snippet_slug_histories::old_slug)
+        .do_update()
+        .set((
+            snippet_slug_histories::snippet_id.eq(snippet_id),
+            snippet_slug_histories::created_at.eq(diesel::dsl::now),
+        ))
+        .execute(conn)?;
+
+    diesel::delete(snippet_slug_histories::table)
+        .filter(snippet_slug_histories::old_slug.eq(new_slug))
+        .execute(conn)?;
+
+    Ok(())
+}
+
+// 替换代码片段的标签关系，在写入代码片段的同一事务中调用
+fn replace_snippet_tags(
+    conn: &mut PgConnection,
+    snippet_id: i32,
+    tag_ids: &[i32],
+) -> Result<(), diesel::result::Error> {
+    use crate::infrastructure::schema::snippet_tag_relations;
+
+    // 删除现有关系
+    diesel::delete(snippet_tag_relations::table)
+        .filter(snippet_tag_relations::snippet_id.eq(snippet_id))
+        .execute(conn)?;
+
+    // 插入新关系
+    for tag_id in tag_ids {
+        diesel::insert_into(snippet_tag_relations::table)
+            .values((
+                snippet_tag_relations::snippet_id.eq(snippet_id),
+                snippet_tag_relations::tag_id.eq(tag_id),
+            ))
+            .execute(conn)?;
+    }
+    Ok(())
+}
+
+// 按正文重建代码片段关联的文件
+fn sync_snippet_attachments(
+    conn: &mut PgConnection,
+    snippet: &SnippetDiesel,
+) -> Result<(), diesel::result::Error> {
+    sync_attachments(
+        conn,
+        FileReferenceKind::Snippet,
+        snippet.id,
+        &[(AttachmentRole::Inline, Some(&snippet.body))],
+    )
+}
+
 pub struct SnippetDieselRepository {
     pub pool: Arc<DBConn>,
 }
@@ -55,35 +128,21 @@ impl SnippetDieselRepository {
         Ok(result.into_iter().map(|tag| tag.into()).collect())
     }
 
-    // 辅助方法：设置代码片段的标签关系
-    async fn set_snippet_tags(&self, snippet_id: i32, tag_ids: &[i32]) -> RepositoryResult<()> {
-        use crate::infrastructure::schema::snippet_tag_relations;
-        let pool = self.pool.clone();
-        let tag_ids = tag_ids.to_vec();
-
-        run(move || -> Result<(), diesel::result::Error> {
-            let mut conn = pool.get().unwrap();
-
-            // 删除现有关系
-            diesel::delete(snippet_tag_relations::table)
-                .filter(snippet_tag_relations::snippet_id.eq(snippet_id))
-                .execute(&mut conn)?;
-
-            // 插入新关系
-            for tag_id in tag_ids {
-                diesel::insert_into(snippet_tag_relations::table)
-                    .values((
-                        snippet_tag_relations::snippet_id.eq(snippet_id),
-                        snippet_tag_relations::tag_id.eq(tag_id),
-                    ))
-                    .execute(&mut conn)?;
-            }
-            Ok(())
-        })
-        .await
-        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
-
-        Ok(())
+    // 辅助方法：填充代码片段的标签和关联文件
+    async fn with_relations(&self, snippet_diesel: SnippetDiesel) -> RepositoryResult<Snippet> {
+        let tags = self.load_snippet_tags(snippet_diesel.id).await?;
+        let attachments = load_attachments(
+            self.pool.clone(),
+            FileReferenceKind::Snippet,
+            vec![snippet_diesel.id],
+        )
+        .await?
+        .remove(&snippet_diesel.id)
+        .unwrap_or_default();
+        let mut snippet: Snippet = snippet_diesel.into();
+        snippet.tags = tags;
+        snippet.attachments = attachments;
+        Ok(snippet)
     }
 }
 
@@ -97,41 +156,40 @@ impl SnippetRepository for SnippetDieselRepository {
         let mut conn = self.pool.get().unwrap();
 
         let result: SnippetDiesel = run(move || {
-            diesel::insert_into(snippets)
-                .values(new_snippet_diesel)
-                .get_result(&mut conn)
+            conn.transaction(|conn| {
+                let snippet = diesel::insert_into(snippets)
+                    .values(new_snippet_diesel)
+                    .get_result::<SnippetDiesel>(conn)?;
+                sync_snippet_attachments(conn, &snippet)?;
+                replace_snippet_tags(conn, snippet.id, &tag_ids)?;
+                Ok::<_, diesel::result::Error>(snippet)
+            })
         })
         .await
-        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
-
-        // 设置标签关系
-        if !tag_ids.is_empty() {
-            self.set_snippet_tags(result.id, &tag_ids).await?;
-        }
-
-        // 加载标签并转换为Snippet
-        let tags = self.load_snippet_tags(result.id).await?;
-        let mut snippet: Snippet = result.into();
-        snippet.tags = tags;
+        .map_err(slug_conflict_error)?;
 
-        Ok(snippet)
+        // 加载标签和关联文件并转换为Snippet
+        self.with_relations(result).await
     }
 
     async fn list(&self, params: SnippetQueryParams) -> RepositoryResult<ResultPaging<Snippet>> {
         use crate::infrastructure::schema::snippets::dsl::*;
         let pool = self.pool.clone();
         let limit_val = params.limit();
-        let offset_val = params.offset();
+        let with_total = params.with_total();
+        let cursor = params.cursor().map(Cursor::decode).transpose()?;
+        // 游标分页时忽略 offset
+        let offset_val = if cursor.is_some() { 0 } else { params.offset() };
         let title_filter = params.title.clone();
         let slug_filter = params.slug.clone();
         let published_filter = params.published;
 
-        // 获取总数
-        let total = {
+        // 获取总数（可选）
+        let total = if with_total {
             let pool_clone = pool.clone();
             let title_filter_clone = title_filter.clone();
             let slug_filter_clone = slug_filter.clone();
-            run(move || {
+            let count = run(move || {
                 let mut conn = pool_clone.get().unwrap();
                 let mut query = snippets.into_boxed();
 
@@ -148,11 +206,14 @@ impl SnippetRepository for SnippetDieselRepository {
                 query.count().get_result::<i64>(&mut conn)
             })
             .await
-            .map_err(|v| DieselRepositoryError::from(v).into_inner())?
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+            Some(count)
+        } else {
+            None
         };
 
         // 获取分页数据
-        let result = run(move || {
+        let mut result = run(move || {
             let mut conn = pool.get().unwrap();
             let mut query = snippets.into_boxed();
 
@@ -165,28 +226,36 @@ impl SnippetRepository for SnippetDieselRepository {
             if let Some(published_val) = published_filter {
                 query = query.filter(published.eq(published_val));
             }
+            if let Some(cursor) = cursor {
+                query = query.filter(
+                    created_at
+                        .lt(cursor.created_at)
+                        .or(created_at.eq(cursor.created_at).and(id.lt(cursor.id))),
+                );
+            }
 
+            // 多取一条用于判断是否还有下一页
             query
-                .order(created_at.desc())
-                .limit(limit_val)
+                .order((created_at.desc(), id.desc()))
+                .limit(fetch_limit(limit_val))
                 .offset(offset_val)
                 .load::<SnippetDiesel>(&mut conn)
         })
         .await
         .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+        let next_cursor =
+            take_next_cursor(&mut result, limit_val, |v| Cursor::new(v.created_at, v.id));
 
-        // 为每个代码片段加载标签
+        // 为每个代码片段加载标签和关联文件
         let mut snippets_with_tags = Vec::new();
         for snippet_diesel in result {
-            let tags = self.load_snippet_tags(snippet_diesel.id).await?;
-            let mut snippet: Snippet = snippet_diesel.into();
-            snippet.tags = tags;
-            snippets_with_tags.push(snippet);
+            snippets_with_tags.push(self.with_relations(snippet_diesel).await?);
         }
 
         Ok(ResultPaging {
             total,
             items: snippets_with_tags,
+            next_cursor,
         })
     }
 
@@ -205,10 +274,7 @@ impl SnippetRepository for SnippetDieselRepository {
         .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
 
         if let Some(snippet_diesel) = result {
-            let tags = self.load_snippet_tags(snippet_diesel.id).await?;
-            let mut snippet: Snippet = snippet_diesel.into();
-            snippet.tags = tags;
-            Ok(Some(snippet))
+            Ok(Some(self.with_relations(snippet_diesel).await?))
         } else {
             Ok(None)
         }
@@ -230,15 +296,55 @@ impl SnippetRepository for SnippetDieselRepository {
         .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
 
         if let Some(snippet_diesel) = result {
-            let tags = self.load_snippet_tags(snippet_diesel.id).await?;
-            let mut snippet: Snippet = snippet_diesel.into();
-            snippet.tags = tags;
-            Ok(Some(snippet))
+            Ok(Some(self.with_relations(snippet_diesel).await?))
         } else {
             Ok(None)
         }
     }
 
+    async fn find_redirect(&self, old_slug: &str) -> RepositoryResult<Option<String>> {
+        use crate::infrastructure::schema::{snippet_slug_histories, snippets};
+        let pool = self.pool.clone();
+        let old_slug = old_slug.to_string();
+
+        let result = run(move || {
+            let mut conn = pool.get().unwrap();
+            snippet_slug_histories::table
+                .inner_join(snippets::table)
+                .filter(snippet_slug_histories::old_slug.eq(old_slug))
+                .select(snippets::slug)
+                .first::<String>(&mut conn)
+                .optional()
+        })
+        .await
+        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+
+        Ok(result)
+    }
+
+    async fn slug_exists(&self, slug_val: &str) -> RepositoryResult<bool> {
+        use crate::infrastructure::schema::{snippet_slug_histories, snippets};
+        let pool = self.pool.clone();
+        let slug_val = slug_val.to_string();
+
+        // 旧 slug 仍用于重定向，同样视为已占用
+        let result = run(move || {
+            let mut conn = pool.get().unwrap();
+            diesel::select(
+                diesel::dsl::exists(snippets::table.filter(snippets::slug.eq(slug_val.clone())))
+                    .or(diesel::dsl::exists(
+                        snippet_slug_histories::table
+                            .filter(snippet_slug_histories::old_slug.eq(slug_val)),
+                    )),
+            )
+            .get_result::<bool>(&mut conn)
+        })
+        .await
+        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+
+        Ok(result)
+    }
+
     async fn get_published(&self) -> RepositoryResult<Vec<Snippet>> {
         use crate::infrastructure::schema::snippets::dsl::*;
         let pool = self.pool.clone();
@@ -255,10 +361,7 @@ impl SnippetRepository for SnippetDieselRepository {
 
         let mut snippets_with_tags = Vec::new();
         for snippet_diesel in result {
-            let tags = self.load_snippet_tags(snippet_diesel.id).await?;
-            let mut snippet: Snippet = snippet_diesel.into();
-            snippet.tags = tags;
-            snippets_with_tags.push(snippet);
+            snippets_with_tags.push(self.with_relations(snippet_diesel).await?);
         }
 
         Ok(snippets_with_tags)
@@ -268,7 +371,7 @@ impl SnippetRepository for SnippetDieselRepository {
         &self,
         snippet_id: i32,
         update_snippet: &UpdateSnippet,
-    ) -> RepositoryResult<Option<Snippet>> {
+    ) -> RepositoryResult<VersionedUpdate<Snippet>> {
         use crate::infrastructure::schema::snippets::dsl::*;
         let pool = self.pool.clone();
         let update_snippet = update_snippet.clone();
@@ -276,50 +379,66 @@ impl SnippetRepository for SnippetDieselRepository {
         let result = run(move || {
             let mut conn = pool.get().unwrap();
 
-            // 先获取现有记录
-            let existing = match snippets
-                .filter(id.eq(snippet_id))
-                .first::<SnippetDiesel>(&mut conn)
-                .optional()?
-            {
-                Some(snippet) => snippet,
-                None => return Ok(None),
-            };
+            conn.transaction(|conn| {
+                // 先获取并锁定现有记录
+                let existing = match snippets
+                    .filter(id.eq(snippet_id))
+                    .for_update()
+                    .first::<SnippetDiesel>(conn)
+                    .optional()?
+                {
+                    Some(snippet) => snippet,
+                    None => return Ok(VersionedUpdate::NotFound),
+                };
+                if update_snippet
+                    .version
+                    .is_some_and(|v| v != existing.version)
+                {
+                    return Ok(VersionedUpdate::Conflict(existing));
+                }
 
-            // 准备更新值
-            let new_title = update_snippet.title.unwrap_or(existing.title);
-            let new_slug = update_snippet.slug.unwrap_or(existing.slug);
-            let new_description = update_snippet.description.unwrap_or(existing.description);
-            let new_body = update_snippet.body.unwrap_or(existing.body);
-            let new_published = update_snippet.published.unwrap_or(existing.published);
+                // 准备更新值
+                let new_title = update_snippet.title.unwrap_or(existing.title);
+                let new_slug = update_snippet.slug.unwrap_or(existing.slug.clone());
+                let new_description = update_snippet.description.unwrap_or(existing.description);
+                let new_body = update_snippet.body.unwrap_or(existing.body);
+                let new_published = update_snippet.published.unwrap_or(existing.published);
 
-            // 执行更新
-            diesel::update(snippets.filter(id.eq(snippet_id)))
-                .set((
-                    title.eq(new_title),
-                    slug.eq(new_slug),
-                    description.eq(new_description),
-                    body.eq(new_body),
-                    published.eq(new_published),
-                ))
-                .get_result::<SnippetDiesel>(&mut conn)
-                .optional()
+                // slug 变更时记录旧 slug，供旧链接重定向
+                if new_slug != existing.slug {
+                    record_slug_change(conn, snippet_id, &existing.slug, &new_slug)?;
+                }
+
+                // 执行更新
+                let updated = diesel::update(snippets.filter(id.eq(snippet_id)))
+                    .set((
+                        title.eq(new_title),
+                        slug.eq(new_slug),
+                        description.eq(new_description),
+                        body.eq(new_body),
+                        published.eq(new_published),
+                        version.eq(version + 1),
+                    ))
+                    .get_result::<SnippetDiesel>(conn)?;
+                sync_snippet_attachments(conn, &updated)?;
+                // 标签关系与内容在同一事务中更新，锁定期间其他请求看不到只更新了一半的内容
+                if let Some(tag_ids) = &update_snippet.tag_ids {
+                    replace_snippet_tags(conn, updated.id, tag_ids)?;
+                }
+                Ok::<_, diesel::result::Error>(VersionedUpdate::Updated(updated))
+            })
         })
         .await
-        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
-
-        if let Some(snippet_diesel) = result {
-            // 更新标签关系
-            if let Some(tag_ids) = &update_snippet.tag_ids {
-                self.set_snippet_tags(snippet_diesel.id, tag_ids).await?;
-            }
-
-            let tags = self.load_snippet_tags(snippet_diesel.id).await?;
-            let mut snippet: Snippet = snippet_diesel.into();
-            snippet.tags = tags;
-            Ok(Some(snippet))
-        } else {
-            Ok(None)
+        .map_err(slug_conflict_error)?;
+
+        match result {
+            VersionedUpdate::Updated(snippet_diesel) => Ok(VersionedUpdate::Updated(
+                self.with_relations(snippet_diesel).await?,
+            )),
+            VersionedUpdate::Conflict(snippet_diesel) => Ok(VersionedUpdate::Conflict(
+                self.with_relations(snippet_diesel).await?,
+            )),
+            VersionedUpdate::NotFound => Ok(VersionedUpdate::NotFound),
         }
     }
 
@@ -371,7 +490,10 @@ impl SnippetRepository for SnippetDieselRepository {
 
             // 切换发布状态
             diesel::update(snippets.filter(id.eq(snippet_id)))
-                .set(published.eq(!current_snippet.published))
+                .set((
+                    published.eq(!current_snippet.published),
+                    version.eq(version + 1),
+                ))
                 .get_result::<SnippetDiesel>(&mut conn)
                 .optional()
         })
@@ -379,10 +501,7 @@ impl SnippetRepository for SnippetDieselRepository {
         .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
 
         if let Some(snippet_diesel) = result {
-            let tags = self.load_snippet_tags(snippet_diesel.id).await?;
-            let mut snippet: Snippet = snippet_diesel.into();
-            snippet.tags = tags;
-            Ok(Some(snippet))
+            Ok(Some(self.with_relations(snippet_diesel).await?))
         } else {
             Ok(None)
         }
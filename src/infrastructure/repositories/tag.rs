@@ -1,24 +1,48 @@
 use std::sync::Arc;
 
-use actix_threadpool::run;
+use crate::infrastructure::threadpool::run;
 use async_trait::async_trait;
+use diesel::pg::PgConnection;
 use diesel::prelude::*;
 
 use crate::{
     domain::{
-        models::tag::{CreateTag, Tag, TagType, UpdateTag},
+        models::{
+            file::{AttachmentRole, FileReferenceKind},
+            tag::{CreateTag, Tag, TagType, UpdateTag},
+            version::VersionedUpdate,
+        },
         repositories::{
-            repository::{QueryParams, RepositoryResult, ResultPaging},
+            repository::{
+                fetch_limit, take_next_cursor, Cursor, QueryParams, RepositoryResult, ResultPaging,
+            },
             tag::{TagQueryParams, TagRepository},
         },
     },
     infrastructure::{
         databases::postgresql::DBConn,
-        error::DieselRepositoryError,
+        error::{slug_conflict_error, DieselRepositoryError},
         models::tag::{CreateTagDiesel, TagDiesel},
+        repositories::content_file::sync_attachments,
     },
 };
 
+// 按图标重建标签关联的文件
+fn sync_tag_attachments(
+    conn: &mut PgConnection,
+    tag: &TagDiesel,
+) -> Result<(), diesel::result::Error> {
+    sync_attachments(
+        conn,
+        FileReferenceKind::Tag,
+        tag.id,
+        &[
+            (AttachmentRole::Icon, tag.icon.as_deref()),
+            (AttachmentRole::Icon, tag.icon_dark.as_deref()),
+        ],
+    )
+}
+
 pub struct TagDieselRepository {
     pub pool: Arc<DBConn>,
 }
@@ -36,45 +60,67 @@ impl TagRepository for TagDieselRepository {
         let new_tag_diesel: CreateTagDiesel = CreateTagDiesel::from(new_tag.clone());
         let mut conn = self.pool.get().unwrap();
         let result: TagDiesel = run(move || {
-            diesel::insert_into(tags)
-                .values(new_tag_diesel)
-                .get_result(&mut conn)
+            conn.transaction(|conn| {
+                let tag = diesel::insert_into(tags)
+                    .values(new_tag_diesel)
+                    .get_result::<TagDiesel>(conn)?;
+                sync_tag_attachments(conn, &tag)?;
+                Ok::<_, diesel::result::Error>(tag)
+            })
         })
         .await
-        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+        .map_err(slug_conflict_error)?;
         Ok(result.into())
     }
 
     async fn list(&self, params: TagQueryParams) -> RepositoryResult<ResultPaging<Tag>> {
-        use crate::infrastructure::schema::tags::dsl::tags;
+        use crate::infrastructure::schema::tags::dsl::{created_at, id, tags};
         let pool = self.pool.clone();
         let limit = params.limit();
-        let offset = params.offset();
+        let with_total = params.with_total();
+        let cursor = params.cursor().map(Cursor::decode).transpose()?;
+        // 游标分页时忽略 offset
+        let offset = if cursor.is_some() { 0 } else { params.offset() };
 
-        // 获取总记录数
-        let total = {
+        // 获取总记录数（可选）
+        let total = if with_total {
             let pool_clone = pool.clone();
-            run(move || {
+            let count = run(move || {
                 let mut conn = pool_clone.get().unwrap();
                 tags.count().get_result::<i64>(&mut conn)
             })
             .await
-            .map_err(|v| DieselRepositoryError::from(v).into_inner())?
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+            Some(count)
+        } else {
+            None
         };
 
-        // 获取分页数据
-        let result = run(move || {
+        // 获取分页数据，多取一条用于判断是否还有下一页
+        let mut result = run(move || {
             let mut conn = pool.get().unwrap();
-            tags.limit(limit)
+            let mut query = tags.into_boxed();
+            if let Some(cursor) = cursor {
+                query = query.filter(
+                    created_at
+                        .lt(cursor.created_at)
+                        .or(created_at.eq(cursor.created_at).and(id.lt(cursor.id))),
+                );
+            }
+            query
+                .order((created_at.desc(), id.desc()))
+                .limit(fetch_limit(limit))
                 .offset(offset)
                 .load::<TagDiesel>(&mut conn)
         })
         .await
         .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+        let next_cursor = take_next_cursor(&mut result, limit, |v| Cursor::new(v.created_at, v.id));
 
         Ok(ResultPaging {
             total,
             items: result.into_iter().map(|v| v.into()).collect(),
+            next_cursor,
         })
     }
 
@@ -93,9 +139,13 @@ impl TagRepository for TagDieselRepository {
         Ok(result.map(|tag| tag.into()))
     }
 
-    async fn update(&self, tag_id: i32, update_tag: &UpdateTag) -> RepositoryResult<Option<Tag>> {
+    async fn update(
+        &self,
+        tag_id: i32,
+        update_tag: &UpdateTag,
+    ) -> RepositoryResult<VersionedUpdate<Tag>> {
         use crate::infrastructure::schema::tags::dsl::{
-            icon, icon_dark, id, name, slug, tags, type_,
+            icon, icon_dark, id, name, slug, tags, type_, version,
         };
         let pool = self.pool.clone();
 
@@ -105,50 +155,59 @@ impl TagRepository for TagDieselRepository {
         let type_val = update_tag.tag_type.clone();
         let icon_val = update_tag.icon.clone();
         let icon_dark_val = update_tag.icon_dark.clone();
+        let expected_version = update_tag.version;
 
         let result = run(move || {
             let mut conn = pool.get().unwrap();
 
-            // 先获取现有记录
-            let existing = match tags
-                .filter(id.eq(tag_id))
-                .first::<TagDiesel>(&mut conn)
-                .optional()?
-            {
-                Some(tag) => tag,
-                None => return Ok(None),
-            };
-
-            // 准备更新值（如果字段为 None 则保持原值）
-            let new_name = name_val.unwrap_or(existing.name);
-            let new_slug = slug_val.unwrap_or(existing.slug);
-            let new_type = type_val
-                .map(|t| match t {
-                    TagType::All => "ALL".to_string(),
-                    TagType::Blog => "BLOG".to_string(),
-                    TagType::Note => "NOTE".to_string(),
-                    TagType::Snippet => "SNIPPET".to_string(),
-                })
-                .unwrap_or(existing.type_);
-            let new_icon = icon_val.or(existing.icon);
-            let new_icon_dark = icon_dark_val.or(existing.icon_dark);
-
-            // 执行更新
-            diesel::update(tags.filter(id.eq(tag_id)))
-                .set((
-                    name.eq(new_name),
-                    slug.eq(new_slug),
-                    type_.eq(new_type),
-                    icon.eq(new_icon),
-                    icon_dark.eq(new_icon_dark),
-                ))
-                .get_result::<TagDiesel>(&mut conn)
-                .optional()
+            conn.transaction(|conn| {
+                // 先获取并锁定现有记录
+                let existing = match tags
+                    .filter(id.eq(tag_id))
+                    .for_update()
+                    .first::<TagDiesel>(conn)
+                    .optional()?
+                {
+                    Some(tag) => tag,
+                    None => return Ok(VersionedUpdate::NotFound),
+                };
+                if expected_version.is_some_and(|v| v != existing.version) {
+                    return Ok(VersionedUpdate::Conflict(existing.into()));
+                }
+
+                // 准备更新值（如果字段为 None 则保持原值）
+                let new_name = name_val.unwrap_or(existing.name);
+                let new_slug = slug_val.unwrap_or(existing.slug);
+                let new_type = type_val
+                    .map(|t| match t {
+                        TagType::All => "ALL".to_string(),
+                        TagType::Blog => "BLOG".to_string(),
+                        TagType::Note => "NOTE".to_string(),
+                        TagType::Snippet => "SNIPPET".to_string(),
+                    })
+                    .unwrap_or(existing.type_);
+                let new_icon = icon_val.or(existing.icon);
+                let new_icon_dark = icon_dark_val.or(existing.icon_dark);
+
+                // 执行更新
+                let updated = diesel::update(tags.filter(id.eq(tag_id)))
+                    .set((
+                        name.eq(new_name),
+                        slug.eq(new_slug),
+                        type_.eq(new_type),
+                        icon.eq(new_icon),
+                        icon_dark.eq(new_icon_dark),
+                        version.eq(version + 1),
+                    ))
+                    .get_result::<TagDiesel>(conn)?;
+                sync_tag_attachments(conn, &updated)?;
+                Ok::<_, diesel::result::Error>(VersionedUpdate::Updated(updated.into()))
+            })
         })
         .await
-        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+        .map_err(slug_conflict_error)?;
 
-        Ok(result.map(|tag| tag.into()))
+        Ok(result)
     }
 
     async fn delete(&self, tag_id: i32) -> RepositoryResult<bool> {
@@ -178,6 +237,21 @@ impl TagRepository for TagDieselRepository {
         Ok(result)
     }
 
+    async fn slug_exists(&self, slug_val: &str) -> RepositoryResult<bool> {
+        use crate::infrastructure::schema::tags::dsl::{slug, tags};
+        let pool = self.pool.clone();
+        let slug_val = slug_val.to_string();
+        let result = run(move || {
+            let mut conn = pool.get().unwrap();
+            diesel::select(diesel::dsl::exists(tags.filter(slug.eq(slug_val))))
+                .get_result::<bool>(&mut conn)
+        })
+        .await
+        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+
+        Ok(result)
+    }
+
     async fn get_all(&self, tag_type: Option<TagType>) -> RepositoryResult<Vec<Tag>> {
         use crate::infrastructure::schema::tags::dsl::{tags, type_};
         let pool = self.pool.clone();
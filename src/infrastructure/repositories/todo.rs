@@ -1,14 +1,16 @@
 use std::sync::Arc;
-use actix_threadpool::run;
 use async_trait::async_trait;
 use diesel::prelude::*;
 
 use crate::domain::models::todo::{CreateTodo, Todo};
-use crate::domain::repositories::repository::{QueryParams, RepositoryResult, ResultPaging};
+use crate::domain::repositories::repository::{
+    page_size, QueryParams, RepositoryResult, ResultPaging,
+};
 use crate::domain::repositories::todo::{TodoQueryParams, TodoRepository};
 use crate::infrastructure::error::DieselRepositoryError;
 use crate::infrastructure::databases::postgresql::DBConn;
 use crate::infrastructure::models::todo::{CreateTodoDiesel, TodoDiesel};
+use crate::infrastructure::threadpool::run;
 
 pub struct TodoDieselRepository {
     pub pool: Arc<DBConn>
@@ -37,7 +39,7 @@ impl TodoRepository for TodoDieselRepository {
     async fn list(&self, params: TodoQueryParams) -> RepositoryResult<ResultPaging<Todo>> {
         use crate::infrastructure::schema::todos::dsl::todos;
         let pool = self.pool.clone();
-        let builder = todos.limit(params.limit()).offset(params.offset());
+        let builder = todos.limit(page_size(params.limit())).offset(params.offset());
         let result = run(move || {
             let mut conn = pool.get().unwrap();
             builder.load::<TodoDiesel>(&mut conn)
@@ -45,8 +47,9 @@ impl TodoRepository for TodoDieselRepository {
             .await
             .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
         Ok(ResultPaging {
-            total: 0,
-            items: result.into_iter().map(|v| v.into()).collect()
+            total: Some(0),
+            items: result.into_iter().map(|v| v.into()).collect(),
+            next_cursor: None,
         })
     }
 
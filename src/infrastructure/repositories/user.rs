@@ -1,10 +1,19 @@
 use async_trait::async_trait;
+use diesel::prelude::*;
 use rand::{distributions::Alphanumeric, prelude::Distribution};
 use std::sync::Arc;
 
 use crate::{
-    domain::repositories::{repository::RepositoryResult, user::UserRepository},
-    infrastructure::databases::postgresql::DBConn,
+    domain::{
+        models::user::AdminWallet,
+        repositories::{repository::RepositoryResult, user::UserRepository},
+    },
+    infrastructure::{
+        databases::postgresql::DBConn,
+        error::DieselRepositoryError,
+        models::user::{AdminWalletDiesel, CreateAdminWalletDiesel},
+        threadpool::run,
+    },
 };
 
 pub struct UserDieselRepository {
@@ -28,4 +37,63 @@ impl UserRepository for UserDieselRepository {
             .map(char::from)
             .collect::<String>())
     }
+
+    async fn list_admin_wallets(&self) -> RepositoryResult<Vec<AdminWallet>> {
+        use crate::infrastructure::schema::admin_wallets::dsl::{address, admin_wallets};
+        let pool = self.pool.clone();
+        let result = run(move || {
+            let mut conn = pool.get().unwrap();
+            admin_wallets
+                .order(address.asc())
+                .select(AdminWalletDiesel::as_select())
+                .load::<AdminWalletDiesel>(&mut conn)
+        })
+        .await
+        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+        Ok(result.into_iter().map(Into::into).collect())
+    }
+
+    async fn is_admin_wallet(&self, wallet: &str) -> RepositoryResult<bool> {
+        use crate::infrastructure::schema::admin_wallets::dsl::{address, admin_wallets};
+        let pool = self.pool.clone();
+        let wallet = wallet.to_string();
+        run(move || {
+            let mut conn = pool.get().unwrap();
+            diesel::select(diesel::dsl::exists(
+                admin_wallets.filter(address.eq(wallet)),
+            ))
+            .get_result::<bool>(&mut conn)
+        })
+        .await
+        .map_err(|v| DieselRepositoryError::from(v).into_inner())
+    }
+
+    async fn add_admin_wallet(&self, wallet: &str) -> RepositoryResult<bool> {
+        use crate::infrastructure::schema::admin_wallets::dsl::admin_wallets;
+        let pool = self.pool.clone();
+        let wallet = wallet.to_string();
+        let inserted = run(move || {
+            let mut conn = pool.get().unwrap();
+            diesel::insert_into(admin_wallets)
+                .values(CreateAdminWalletDiesel { address: &wallet })
+                .on_conflict_do_nothing()
+                .execute(&mut conn)
+        })
+        .await
+        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+        Ok(inserted > 0)
+    }
+
+    async fn remove_admin_wallet(&self, wallet: &str) -> RepositoryResult<bool> {
+        use crate::infrastructure::schema::admin_wallets::dsl::{address, admin_wallets};
+        let pool = self.pool.clone();
+        let wallet = wallet.to_string();
+        let deleted = run(move || {
+            let mut conn = pool.get().unwrap();
+            diesel::delete(admin_wallets.filter(address.eq(wallet))).execute(&mut conn)
+        })
+        .await
+        .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+        Ok(deleted > 0)
+    }
 }
@@ -1,5 +1,13 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    admin_wallets (address) {
+        #[max_length = 64]
+        address -> Varchar,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     blog_tag_relations (blog_id, tag_id) {
         blog_id -> Int4,
@@ -7,6 +15,15 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    blog_slug_histories (old_slug) {
+        #[max_length = 191]
+        old_slug -> Varchar,
+        blog_id -> Int4,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     blogs (id) {
         id -> Int4,
@@ -24,18 +41,49 @@ diesel::table! {
         published -> Bool,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        version -> Int4,
+    }
+}
+
+diesel::table! {
+    content_files (id) {
+        id -> Int4,
+        file_id -> Int4,
+        #[max_length = 16]
+        role -> Varchar,
+        blog_id -> Nullable<Int4>,
+        snippet_id -> Nullable<Int4>,
+        note_id -> Nullable<Int4>,
+        tag_id -> Nullable<Int4>,
+        created_at -> Timestamp,
     }
 }
 
 diesel::table! {
     files (id) {
         id -> Int4,
-        #[max_length = 32]
+        #[max_length = 64]
         file_hash -> Varchar,
         file_path -> Varchar,
         upload_time -> Timestamp,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        original_id -> Nullable<Int4>,
+        #[max_length = 32]
+        variant -> Nullable<Varchar>,
+        width -> Nullable<Int4>,
+        height -> Nullable<Int4>,
+        #[max_length = 127]
+        mime_type -> Nullable<Varchar>,
+        byte_size -> Nullable<Int8>,
+        #[max_length = 255]
+        original_filename -> Nullable<Varchar>,
+        #[max_length = 16]
+        hash_algorithm -> Varchar,
+        #[max_length = 255]
+        storage_key -> Varchar,
+        #[max_length = 16]
+        visibility -> Varchar,
     }
 }
 
@@ -53,6 +101,7 @@ diesel::table! {
         published -> Bool,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        version -> Int4,
     }
 }
 
@@ -63,6 +112,15 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    snippet_slug_histories (old_slug) {
+        #[max_length = 191]
+        old_slug -> Varchar,
+        snippet_id -> Int4,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     snippet_tag_relations (snippet_id, tag_id) {
         snippet_id -> Int4,
@@ -83,6 +141,7 @@ diesel::table! {
         published -> Bool,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        version -> Int4,
     }
 }
 
@@ -100,6 +159,7 @@ diesel::table! {
         icon_dark -> Nullable<Text>,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        version -> Int4,
     }
 }
 
@@ -130,20 +190,31 @@ diesel::table! {
     }
 }
 
+diesel::joinable!(blog_slug_histories -> blogs (blog_id));
 diesel::joinable!(blog_tag_relations -> blogs (blog_id));
 diesel::joinable!(blog_tag_relations -> tags (tag_id));
+diesel::joinable!(content_files -> blogs (blog_id));
+diesel::joinable!(content_files -> files (file_id));
+diesel::joinable!(content_files -> notes (note_id));
+diesel::joinable!(content_files -> snippets (snippet_id));
+diesel::joinable!(content_files -> tags (tag_id));
 diesel::joinable!(note_tag_relations -> notes (note_id));
 diesel::joinable!(note_tag_relations -> tags (tag_id));
+diesel::joinable!(snippet_slug_histories -> snippets (snippet_id));
 diesel::joinable!(snippet_tag_relations -> snippets (snippet_id));
 diesel::joinable!(snippet_tag_relations -> tags (tag_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    admin_wallets,
+    blog_slug_histories,
     blog_tag_relations,
     blogs,
+    content_files,
     files,
     note_tag_relations,
     notes,
     service_contexts,
+    snippet_slug_histories,
     snippet_tag_relations,
     snippets,
     tags,
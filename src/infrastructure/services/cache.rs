@@ -0,0 +1,223 @@
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+
+use crate::config::Cache;
+use crate::domain::models::cache::{CacheGroup, CacheKey, CacheLookup};
+use crate::domain::repositories::redis::RedisRepository;
+use crate::domain::repositories::repository::RepositoryResult;
+use crate::domain::services::cache::CacheService;
+use crate::metrics::CACHE_REQUESTS_TOTAL;
+
+// 每个分组保存一个版本号，缓存 key 包含所属分组的版本号；
+// 失效时更换版本号，旧条目不再被读取并随 TTL 过期
+pub struct RedisCacheService {
+    redis: Arc<dyn RedisRepository<String>>,
+    config: Cache,
+}
+
+impl RedisCacheService {
+    pub fn new(redis: Arc<dyn RedisRepository<String>>, config: &Cache) -> Self {
+        RedisCacheService {
+            redis,
+            config: config.clone(),
+        }
+    }
+
+    fn ttl(&self, key: &CacheKey) -> Duration {
+        let secs = match key {
+            CacheKey::PublishedBlog { .. } => self.config.detail_ttl_secs,
+            CacheKey::Tags { .. } => self.config.tags_ttl_secs,
+            CacheKey::PublishedBlogs { .. } | CacheKey::PublishedSnippets | CacheKey::Notes => {
+                self.config.list_ttl_secs
+            }
+        };
+        Duration::from_secs(secs)
+    }
+
+    async fn entry_key(&self, key: &CacheKey) -> RepositoryResult<String> {
+        let mut versions = Vec::new();
+        for group in key.groups() {
+            let version = self.redis.get(&version_key(*group)).await?;
+            versions.push(version.unwrap_or_else(|| "0".to_string()));
+        }
+        Ok(format!(
+            "cache:{}:{}:{}",
+            key.name(),
+            versions.join("."),
+            key.variant()
+        ))
+    }
+}
+
+fn version_key(group: CacheGroup) -> String {
+    format!("cache:version:{}", group.as_str())
+}
+
+#[async_trait]
+impl CacheService for RedisCacheService {
+    async fn lookup(&self, key: &CacheKey) -> CacheLookup {
+        let mut lookup = CacheLookup {
+            entry: None,
+            ttl: self.ttl(key),
+            body: None,
+        };
+        if !self.config.enabled {
+            return lookup;
+        }
+
+        let result = match self.entry_key(key).await {
+            Ok(entry) => self.redis.get(&entry).await.map(|body| (entry, body)),
+            Err(e) => Err(e),
+        };
+        let status = match result {
+            Ok((entry, body)) => {
+                let status = if body.is_some() { "hit" } else { "miss" };
+                lookup.entry = Some(entry);
+                lookup.body = body;
+                status
+            }
+            Err(e) => {
+                tracing::warn!("Failed to read {} cache: {:?}", key.name(), e);
+                "error"
+            }
+        };
+        CACHE_REQUESTS_TOTAL
+            .with_label_values(&[key.name(), status])
+            .inc();
+        lookup
+    }
+
+    async fn store(&self, lookup: &CacheLookup, body: String) {
+        let Some(entry) = &lookup.entry else {
+            return;
+        };
+        if let Err(e) = self.redis.set_with_ttl(entry, body, lookup.ttl).await {
+            tracing::warn!("Failed to write cache entry {}: {:?}", entry, e);
+        }
+    }
+
+    async fn evict(&self, groups: &[CacheGroup]) {
+        if !self.config.enabled {
+            return;
+        }
+        let version = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos()
+            .to_string();
+        for group in groups {
+            if let Err(e) = self.redis.set(&version_key(*group), version.clone()).await {
+                tracing::warn!(
+                    "Failed to evict {} cache, entries expire after their TTL: {:?}",
+                    group.as_str(),
+                    e
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+
+    use super::RedisCacheService;
+    use crate::config::Cache;
+    use crate::domain::models::cache::{CacheGroup, CacheKey};
+    use crate::domain::repositories::{redis::RedisRepository, repository::RepositoryResult};
+    use crate::domain::services::cache::CacheService;
+
+    #[derive(Default)]
+    struct MemoryRedis(Mutex<HashMap<String, String>>);
+
+    #[async_trait]
+    impl RedisRepository<String> for MemoryRedis {
+        async fn get(&self, key: &str) -> RepositoryResult<Option<String>> {
+            Ok(self.0.lock().unwrap().get(key).cloned())
+        }
+        async fn set(&self, key: &str, value: String) -> RepositoryResult<()> {
+            self.0.lock().unwrap().insert(key.to_string(), value);
+            Ok(())
+        }
+        async fn set_with_ttl(
+            &self,
+            key: &str,
+            value: String,
+            _ttl: Duration,
+        ) -> RepositoryResult<()> {
+            self.set(key, value).await
+        }
+        async fn delete(&self, key: &str) -> RepositoryResult<()> {
+            self.0.lock().unwrap().remove(key);
+            Ok(())
+        }
+        async fn exists(&self, key: &str) -> RepositoryResult<bool> {
+            Ok(self.0.lock().unwrap().contains_key(key))
+        }
+        async fn set_ex(&self, key: &str, value: String, _seconds: u64) -> RepositoryResult<()> {
+            self.set(key, value).await
+        }
+    }
+
+    fn cache(enabled: bool) -> RedisCacheService {
+        RedisCacheService::new(
+            Arc::new(MemoryRedis::default()),
+            &Cache {
+                enabled,
+                ..Cache::default()
+            },
+        )
+    }
+
+    const BLOGS: CacheKey = CacheKey::PublishedBlogs { full: false };
+
+    #[actix_web::test]
+    async fn miss_then_hit() {
+        let cache = cache(true);
+        let lookup = cache.lookup(&BLOGS).await;
+        assert!(lookup.entry.is_some());
+        assert_eq!(lookup.body, None);
+        cache.store(&lookup, "blogs".to_string()).await;
+
+        assert_eq!(cache.lookup(&BLOGS).await.body.as_deref(), Some("blogs"));
+        // 同一接口的其他参数使用不同的条目
+        let full = CacheKey::PublishedBlogs { full: true };
+        assert_eq!(cache.lookup(&full).await.body, None);
+    }
+
+    #[actix_web::test]
+    async fn evict_changes_group_version() {
+        let cache = cache(true);
+        let lookup = cache.lookup(&BLOGS).await;
+        cache.store(&lookup, "blogs".to_string()).await;
+
+        // 不相关的分组不影响
+        cache.evict(&[CacheGroup::Notes]).await;
+        assert_eq!(cache.lookup(&BLOGS).await.body.as_deref(), Some("blogs"));
+
+        // 博客列表包含标签，标签变更后旧条目不再返回
+        cache.evict(&[CacheGroup::Tags]).await;
+        let lookup = cache.lookup(&BLOGS).await;
+        assert_eq!(lookup.body, None);
+
+        // 失效前查询到的旧数据写入旧版本的 key，不会被之后的请求读到
+        cache.evict(&[CacheGroup::Blogs]).await;
+        cache.store(&lookup, "stale".to_string()).await;
+        assert_eq!(cache.lookup(&BLOGS).await.body, None);
+    }
+
+    #[actix_web::test]
+    async fn disabled_cache_never_stores() {
+        let cache = cache(false);
+        let lookup = cache.lookup(&BLOGS).await;
+        assert_eq!(lookup.entry, None);
+        cache.store(&lookup, "blogs".to_string()).await;
+        assert_eq!(cache.lookup(&BLOGS).await.body, None);
+    }
+}
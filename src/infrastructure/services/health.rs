@@ -0,0 +1,170 @@
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::infrastructure::threadpool::run;
+use async_trait::async_trait;
+use diesel::prelude::*;
+use diesel_migrations::MigrationHarness;
+use redis::Client;
+
+use crate::config::{AppConfig, StorageBackendKind};
+use crate::domain::models::health::{DependencyHealth, HealthReport, HealthStatus};
+use crate::domain::services::health::HealthService;
+use crate::infrastructure::databases::postgresql::{DBConn, MIGRATIONS};
+use crate::metrics::time_redis;
+
+// 单项检查的超时时间，避免依赖无响应时探针一直挂起
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+pub struct HealthServiceImpl {
+    pool: Arc<DBConn>,
+    redis_client: Arc<Client>,
+    // 需要可写的上传目录：本地存储根目录和分片接收目录
+    upload_dirs: Vec<(String, PathBuf)>,
+}
+
+impl HealthServiceImpl {
+    pub fn new(pool: Arc<DBConn>, redis_client: Arc<Client>, config: &AppConfig) -> Self {
+        let mut upload_dirs = Vec::new();
+        if config.storage.backend == StorageBackendKind::Local {
+            upload_dirs.push((
+                "uploads".to_string(),
+                PathBuf::from(&config.storage.local.root),
+            ));
+        }
+        upload_dirs.push((
+            "upload_chunks".to_string(),
+            PathBuf::from(&config.uploads.chunk_dir),
+        ));
+        HealthServiceImpl {
+            pool,
+            redis_client,
+            upload_dirs,
+        }
+    }
+
+    async fn check_database(&self) -> DependencyHealth {
+        let pool = self.pool.clone();
+        check("database", async move {
+            run(move || {
+                let mut conn = pool.get_timeout(CHECK_TIMEOUT).map_err(|e| e.to_string())?;
+                diesel::sql_query("SELECT 1")
+                    .execute(&mut conn)
+                    .map_err(|e| e.to_string())?;
+                let state = pool.state();
+                Ok::<_, String>(Some(format!(
+                    "connections: {}, idle: {}",
+                    state.connections, state.idle_connections
+                )))
+            })
+            .await
+            .map_err(|e| e.to_string())
+        })
+        .await
+    }
+
+    async fn check_migrations(&self) -> DependencyHealth {
+        let pool = self.pool.clone();
+        check("migrations", async move {
+            let pending = run(move || {
+                let mut conn = pool.get_timeout(CHECK_TIMEOUT).map_err(|e| e.to_string())?;
+                conn.pending_migrations(MIGRATIONS)
+                    .map(|migrations| migrations.len())
+                    .map_err(|e| e.to_string())
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+            if pending > 0 {
+                return Err(format!("{} pending migrations", pending));
+            }
+            Ok(None)
+        })
+        .await
+    }
+
+    async fn check_redis(&self) -> DependencyHealth {
+        let client = self.redis_client.clone();
+        check("redis", async move {
+            let mut conn = client
+                .get_multiplexed_async_connection()
+                .await
+                .map_err(|e| e.to_string())?;
+            time_redis("PING", redis::cmd("PING").query_async::<String>(&mut conn))
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(None)
+        })
+        .await
+    }
+
+    async fn check_upload_dir(&self, name: &str, dir: PathBuf) -> DependencyHealth {
+        check(name, async move {
+            tokio::fs::create_dir_all(&dir)
+                .await
+                .map_err(|e| e.to_string())?;
+            // 写入并删除探测文件确认目录可写
+            let probe = dir.join(format!(".readyz-{}", rand::random::<u64>()));
+            tokio::fs::write(&probe, b"ok")
+                .await
+                .map_err(|e| e.to_string())?;
+            tokio::fs::remove_file(&probe)
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(None)
+        })
+        .await
+    }
+}
+
+// 执行单项检查并记录耗时，超时视为失败
+async fn check<F>(name: &str, fut: F) -> DependencyHealth
+where
+    F: Future<Output = Result<Option<String>, String>>,
+{
+    let start = Instant::now();
+    let result = match tokio::time::timeout(CHECK_TIMEOUT, fut).await {
+        Ok(result) => result,
+        Err(_) => Err(format!("timed out after {}ms", CHECK_TIMEOUT.as_millis())),
+    };
+    let latency_ms = start.elapsed().as_millis() as u64;
+    match result {
+        Ok(detail) => DependencyHealth {
+            name: name.to_string(),
+            status: HealthStatus::Up,
+            latency_ms,
+            detail,
+        },
+        Err(e) => {
+            tracing::warn!("Readiness check {} failed: {}", name, e);
+            DependencyHealth {
+                name: name.to_string(),
+                status: HealthStatus::Down,
+                latency_ms,
+                detail: Some(e),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl HealthService for HealthServiceImpl {
+    async fn readiness(&self) -> HealthReport {
+        let (database, migrations, redis) = futures::join!(
+            self.check_database(),
+            self.check_migrations(),
+            self.check_redis()
+        );
+        let upload_dirs = futures::future::join_all(
+            self.upload_dirs
+                .iter()
+                .map(|(name, dir)| self.check_upload_dir(name, dir.clone())),
+        )
+        .await;
+
+        let mut checks = vec![database, migrations, redis];
+        checks.extend(upload_dirs);
+        HealthReport::new(checks)
+    }
+}
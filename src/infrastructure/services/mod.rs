@@ -1 +1,3 @@
+pub mod cache;
+pub mod health;
 pub mod service_context;
\ No newline at end of file
@@ -2,20 +2,48 @@ use crate::domain::models::service_context::ServiceContext;
 use crate::domain::services::service_context::ServiceContextService;
 use crate::infrastructure::databases::postgresql::DBConn;
 use crate::infrastructure::models::service_context::ServiceContextDiesel;
+use crate::infrastructure::threadpool::run;
+use async_trait::async_trait;
 use diesel::prelude::*;
 use diesel::result::Error;
 use diesel::{insert_into, update};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tracing::info;
+use std::time::Duration;
+use tracing::{info, warn};
+
+// 后台任务刷新维护模式状态的间隔，管理命令修改后最多延迟这么久生效
+pub const MAINTENANCE_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
 
 #[derive(Clone)]
 pub struct ServiceContextServiceImpl {
     pub pool: Arc<DBConn>,
+    // 每个请求都会检查维护模式，只读取后台任务刷新的结果，不在请求中查询数据库
+    maintenance: Arc<AtomicBool>,
 }
 
 impl ServiceContextServiceImpl {
     pub fn new(db: Arc<DBConn>) -> Self {
-        ServiceContextServiceImpl { pool: db }
+        ServiceContextServiceImpl {
+            pool: db,
+            maintenance: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    fn load_maintenance(pool: &DBConn) -> Result<bool, String> {
+        use crate::infrastructure::schema::service_contexts::dsl::{
+            id, maintenance, service_contexts,
+        };
+        let mut conn = pool
+            .get_timeout(Duration::from_secs(1))
+            .map_err(|e| e.to_string())?;
+        let active = service_contexts
+            .filter(id.eq(1))
+            .select(maintenance)
+            .first::<bool>(&mut conn)
+            .optional()
+            .map_err(|e| e.to_string())?;
+        Ok(active.unwrap_or(false))
     }
 
     fn get_service_context(&self) -> ServiceContext {
@@ -50,6 +78,7 @@ impl ServiceContextServiceImpl {
     }
 }
 
+#[async_trait]
 impl ServiceContextService for ServiceContextServiceImpl {
     fn get_service_context(&self) -> ServiceContext {
         self.get_service_context()
@@ -68,10 +97,22 @@ impl ServiceContextService for ServiceContextServiceImpl {
         if result.is_err() {
             panic!("Could not update service context");
         }
-        result.unwrap().into()
+        let service_context: ServiceContext = result.unwrap().into();
+        self.maintenance
+            .store(service_context.maintenance, Ordering::Relaxed);
+        service_context
     }
 
     fn is_maintenance_active(&self) -> bool {
-        self.get_service_context().maintenance
+        self.maintenance.load(Ordering::Relaxed)
+    }
+
+    async fn refresh_maintenance(&self) {
+        let pool = self.pool.clone();
+        // 查询失败时沿用上一次的状态，不因数据库故障拒绝所有请求
+        match run(move || Self::load_maintenance(&pool)).await {
+            Ok(active) => self.maintenance.store(active, Ordering::Relaxed),
+            Err(e) => warn!("Failed to load maintenance state: {}", e),
+        }
     }
 }
@@ -0,0 +1,130 @@
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use actix_web::web::Bytes;
+use async_trait::async_trait;
+use futures_util::stream::{self, StreamExt};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+use crate::config::LocalStorage;
+use crate::domain::error::RepositoryError;
+use crate::domain::repositories::{
+    repository::RepositoryResult,
+    storage::{ByteStream, StorageBackend},
+};
+
+// 流式读取每次读取的最大字节数
+const CHUNK_SIZE: u64 = 64 * 1024;
+
+pub struct LocalStorageBackend {
+    root: PathBuf,
+    base_url: String,
+}
+
+impl LocalStorageBackend {
+    pub fn new(config: &LocalStorage) -> Self {
+        LocalStorageBackend {
+            root: PathBuf::from(&config.root),
+            base_url: config.base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.root.join(key)
+    }
+}
+
+fn storage_error(e: std::io::Error) -> RepositoryError {
+    RepositoryError::StorageError(e.to_string())
+}
+
+#[async_trait]
+impl StorageBackend for LocalStorageBackend {
+    async fn put_file(&self, key: &str, source: &Path) -> RepositoryResult<()> {
+        tokio::fs::create_dir_all(&self.root)
+            .await
+            .map_err(storage_error)?;
+        let target = self.path(key);
+        // 临时目录与上传目录可能不在同一文件系统，rename 失败时改为复制
+        if tokio::fs::rename(source, &target).await.is_err() {
+            tokio::fs::copy(source, &target)
+                .await
+                .map_err(storage_error)?;
+        }
+        Ok(())
+    }
+
+    async fn put_bytes(&self, key: &str, bytes: Vec<u8>) -> RepositoryResult<()> {
+        tokio::fs::create_dir_all(&self.root)
+            .await
+            .map_err(storage_error)?;
+        tokio::fs::write(self.path(key), bytes)
+            .await
+            .map_err(storage_error)
+    }
+
+    async fn get(&self, key: &str) -> RepositoryResult<Option<Vec<u8>>> {
+        match tokio::fs::read(self.path(key)).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(storage_error(e)),
+        }
+    }
+
+    async fn get_range(
+        &self,
+        key: &str,
+        range: Range<u64>,
+    ) -> RepositoryResult<Option<ByteStream>> {
+        let mut file = match tokio::fs::File::open(self.path(key)).await {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(storage_error(e)),
+        };
+        file.seek(std::io::SeekFrom::Start(range.start))
+            .await
+            .map_err(storage_error)?;
+        let remaining = range.end.saturating_sub(range.start);
+        let chunks = stream::try_unfold((file, remaining), |(mut file, remaining)| async move {
+            if remaining == 0 {
+                return Ok(None);
+            }
+            let mut buffer = vec![0; remaining.min(CHUNK_SIZE) as usize];
+            let read = file.read(&mut buffer).await.map_err(storage_error)?;
+            if read == 0 {
+                return Err(RepositoryError::StorageError(
+                    "文件在读取过程中被截断".to_string(),
+                ));
+            }
+            buffer.truncate(read);
+            Ok(Some((Bytes::from(buffer), (file, remaining - read as u64))))
+        });
+        Ok(Some(chunks.boxed()))
+    }
+
+    async fn size(&self, key: &str) -> RepositoryResult<Option<u64>> {
+        match tokio::fs::metadata(self.path(key)).await {
+            Ok(metadata) => Ok(Some(metadata.len())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(storage_error(e)),
+        }
+    }
+
+    async fn exists(&self, key: &str) -> RepositoryResult<bool> {
+        tokio::fs::try_exists(self.path(key))
+            .await
+            .map_err(storage_error)
+    }
+
+    async fn delete(&self, key: &str) -> RepositoryResult<bool> {
+        match tokio::fs::remove_file(self.path(key)).await {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(storage_error(e)),
+        }
+    }
+
+    fn url(&self, key: &str) -> String {
+        format!("{}/{}", self.base_url, key)
+    }
+}
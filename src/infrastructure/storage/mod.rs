@@ -0,0 +1,27 @@
+pub mod local;
+pub mod s3;
+
+use std::sync::Arc;
+
+use crate::config::{AppConfig, StorageBackendKind};
+use crate::domain::repositories::storage::StorageBackend;
+use crate::infrastructure::storage::{local::LocalStorageBackend, s3::S3StorageBackend};
+
+pub fn storage_backend(config: &AppConfig) -> Arc<dyn StorageBackend> {
+    match config.storage.backend {
+        StorageBackendKind::S3 => {
+            // 加载配置时已校验 storage.s3 存在
+            let s3 = config
+                .storage
+                .s3
+                .as_ref()
+                .expect("storage.s3 is required when storage.backend is s3");
+            tracing::info!("Using S3 storage: bucket {}", s3.bucket);
+            Arc::new(S3StorageBackend::new(s3).expect("Failed to create S3 storage"))
+        }
+        StorageBackendKind::Local => {
+            tracing::info!("Using local storage: {}", config.storage.local.root);
+            Arc::new(LocalStorageBackend::new(&config.storage.local))
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures_util::{StreamExt, TryStreamExt};
+use object_store::{
+    aws::AmazonS3Builder, buffered::BufWriter, path::Path as ObjectPath, GetOptions, GetRange,
+    ObjectStore,
+};
+use tokio::io::AsyncWriteExt;
+
+use crate::config::S3Storage;
+use crate::domain::error::RepositoryError;
+use crate::domain::repositories::{
+    repository::RepositoryResult,
+    storage::{ByteStream, StorageBackend},
+};
+
+// S3 兼容的对象存储（AWS S3、MinIO 等）
+pub struct S3StorageBackend {
+    store: Arc<dyn ObjectStore>,
+    public_url: String,
+}
+
+impl S3StorageBackend {
+    pub fn new(config: &S3Storage) -> RepositoryResult<Self> {
+        let mut builder = AmazonS3Builder::new()
+            .with_bucket_name(&config.bucket)
+            .with_region(&config.region)
+            .with_access_key_id(&config.access_key_id)
+            .with_secret_access_key(&config.secret_access_key)
+            .with_allow_http(config.allow_http);
+        if let Some(endpoint) = &config.endpoint {
+            // 自定义端点使用 path-style 访问，兼容 MinIO
+            builder = builder
+                .with_endpoint(endpoint)
+                .with_virtual_hosted_style_request(false);
+        }
+        let store = builder.build().map_err(storage_error)?;
+        Ok(S3StorageBackend {
+            store: Arc::new(store),
+            public_url: config.public_url.trim_end_matches('/').to_string(),
+        })
+    }
+}
+
+fn storage_error(e: impl std::fmt::Display) -> RepositoryError {
+    RepositoryError::StorageError(e.to_string())
+}
+
+#[async_trait]
+impl StorageBackend for S3StorageBackend {
+    async fn put_file(&self, key: &str, source: &Path) -> RepositoryResult<()> {
+        let mut reader = tokio::fs::File::open(source).await.map_err(storage_error)?;
+        // 小文件单次上传，大文件自动切换为分片上传
+        let mut writer = BufWriter::new(self.store.clone(), ObjectPath::from(key));
+        tokio::io::copy(&mut reader, &mut writer)
+            .await
+            .map_err(storage_error)?;
+        writer.shutdown().await.map_err(storage_error)
+    }
+
+    async fn put_bytes(&self, key: &str, bytes: Vec<u8>) -> RepositoryResult<()> {
+        self.store
+            .put(&ObjectPath::from(key), bytes.into())
+            .await
+            .map(|_| ())
+            .map_err(storage_error)
+    }
+
+    async fn get(&self, key: &str) -> RepositoryResult<Option<Vec<u8>>> {
+        match self.store.get(&ObjectPath::from(key)).await {
+            Ok(result) => {
+                let bytes = result.bytes().await.map_err(storage_error)?;
+                Ok(Some(bytes.to_vec()))
+            }
+            Err(object_store::Error::NotFound { .. }) => Ok(None),
+            Err(e) => Err(storage_error(e)),
+        }
+    }
+
+    async fn get_range(
+        &self,
+        key: &str,
+        range: Range<u64>,
+    ) -> RepositoryResult<Option<ByteStream>> {
+        let options = GetOptions {
+            range: Some(GetRange::Bounded(range)),
+            ..Default::default()
+        };
+        match self.store.get_opts(&ObjectPath::from(key), options).await {
+            Ok(result) => Ok(Some(result.into_stream().map_err(storage_error).boxed())),
+            Err(object_store::Error::NotFound { .. }) => Ok(None),
+            Err(e) => Err(storage_error(e)),
+        }
+    }
+
+    async fn size(&self, key: &str) -> RepositoryResult<Option<u64>> {
+        match self.store.head(&ObjectPath::from(key)).await {
+            Ok(meta) => Ok(Some(meta.size)),
+            Err(object_store::Error::NotFound { .. }) => Ok(None),
+            Err(e) => Err(storage_error(e)),
+        }
+    }
+
+    async fn exists(&self, key: &str) -> RepositoryResult<bool> {
+        match self.store.head(&ObjectPath::from(key)).await {
+            Ok(_) => Ok(true),
+            Err(object_store::Error::NotFound { .. }) => Ok(false),
+            Err(e) => Err(storage_error(e)),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> RepositoryResult<bool> {
+        // S3 删除不存在的对象不会报错，需要先确认是否存在
+        if !self.exists(key).await? {
+            return Ok(false);
+        }
+        self.store
+            .delete(&ObjectPath::from(key))
+            .await
+            .map(|_| true)
+            .map_err(storage_error)
+    }
+
+    fn url(&self, key: &str) -> String {
+        format!("{}/{}", self.public_url, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use futures_util::TryStreamExt;
+    use object_store::memory::InMemory;
+
+    use super::S3StorageBackend;
+    use crate::domain::repositories::storage::StorageBackend;
+
+    // 与 MinIO 的集成测试见 src/tests/infrastructure/test_s3_storage.rs，这里只使用内存对象存储
+    #[actix_web::test]
+    async fn reads_writes_and_deletes_objects() {
+        let storage = S3StorageBackend {
+            store: Arc::new(InMemory::new()),
+            public_url: "https://cdn.example.com".to_string(),
+        };
+        let source = std::env::temp_dir().join(format!("zwj_blog_s3_{}", std::process::id()));
+        std::fs::write(&source, b"hello object store").unwrap();
+        storage.put_file("a.txt", &source).await.unwrap();
+        std::fs::remove_file(&source).unwrap();
+
+        assert_eq!(storage.size("a.txt").await.unwrap(), Some(18));
+        let chunks: Vec<_> = storage
+            .get_range("a.txt", 6..12)
+            .await
+            .unwrap()
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(chunks.concat(), b"object");
+        assert!(storage
+            .get_range("missing.txt", 0..1)
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(storage.get("missing.txt").await.unwrap(), None);
+
+        assert!(storage.delete("a.txt").await.unwrap());
+        assert!(!storage.delete("a.txt").await.unwrap());
+        assert_eq!(storage.url("a.txt"), "https://cdn.example.com/a.txt");
+    }
+}
@@ -0,0 +1,145 @@
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::runtime::Handle;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+// 管理后台任务的生命周期：按名称启动，关闭时通知任务退出并等待宽限期
+pub struct TaskSupervisor {
+    // 创建时所在的主运行时；请求中启动的任务也在这里运行，不随 HTTP worker 停止而取消
+    runtime: Handle,
+    grace_period: Duration,
+    shutdown: watch::Sender<bool>,
+    tasks: Mutex<Vec<(String, JoinHandle<()>)>>,
+}
+
+// 任务通过它得知服务正在关闭，应尽快结束当前工作并返回
+#[derive(Clone)]
+pub struct Shutdown(watch::Receiver<bool>);
+
+impl Shutdown {
+    pub fn is_requested(&self) -> bool {
+        *self.0.borrow()
+    }
+
+    // 收到关闭通知时返回
+    pub async fn requested(&mut self) {
+        let _ = self.0.wait_for(|requested| *requested).await;
+    }
+}
+
+impl TaskSupervisor {
+    // 需要在主线程的运行时中创建
+    pub fn new(grace_period: Duration) -> Self {
+        TaskSupervisor {
+            runtime: Handle::current(),
+            grace_period,
+            shutdown: watch::Sender::new(false),
+            tasks: Mutex::new(Vec::new()),
+        }
+    }
+
+    // 在主运行时上启动任务，关闭开始后不再接受新任务
+    pub fn spawn<F, Fut>(&self, name: &str, task: F)
+    where
+        F: FnOnce(Shutdown) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        if *self.shutdown.borrow() {
+            tracing::warn!("Not starting background task {} during shutdown", name);
+            return;
+        }
+        let handle = self
+            .runtime
+            .spawn(task(Shutdown(self.shutdown.subscribe())));
+        let mut tasks = self.tasks.lock().unwrap();
+        tasks.retain(|(_, handle)| !handle.is_finished());
+        tasks.push((name.to_string(), handle));
+        tracing::debug!("Started background task {}", name);
+    }
+
+    pub fn grace_period(&self) -> Duration {
+        self.grace_period
+    }
+
+    // 通知所有任务退出，宽限期内未结束的任务记录日志后中止
+    pub async fn shutdown(&self) {
+        self.shutdown.send_replace(true);
+        let tasks = std::mem::take(&mut *self.tasks.lock().unwrap());
+        if tasks.is_empty() {
+            return;
+        }
+        tracing::info!(
+            "Waiting up to {:?} for {} background tasks",
+            self.grace_period,
+            tasks.len()
+        );
+
+        let deadline = tokio::time::Instant::now() + self.grace_period;
+        let mut unfinished = Vec::new();
+        for (name, mut handle) in tasks {
+            match tokio::time::timeout_at(deadline, &mut handle).await {
+                Ok(Ok(())) => tracing::debug!("Background task {} finished", name),
+                Ok(Err(e)) => tracing::error!("Background task {} failed: {}", name, e),
+                Err(_) => {
+                    handle.abort();
+                    unfinished.push(name);
+                }
+            }
+        }
+        if !unfinished.is_empty() {
+            tracing::warn!(
+                "Background tasks did not finish within {:?} and were aborted: {}",
+                self.grace_period,
+                unfinished.join(", ")
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    use tokio::sync::oneshot;
+
+    use super::*;
+
+    #[actix_web::test]
+    async fn shutdown_aborts_tasks_that_outlive_grace_period() {
+        let supervisor = TaskSupervisor::new(Duration::from_millis(50));
+
+        let finished = Arc::new(AtomicBool::new(false));
+        let cooperative = finished.clone();
+        supervisor.spawn("cooperative", |mut shutdown| async move {
+            shutdown.requested().await;
+            cooperative.store(true, Ordering::SeqCst);
+        });
+
+        // 忽略关闭通知的任务，被中止时 sender 随任务一起释放
+        let (sender, dropped) = oneshot::channel::<()>();
+        supervisor.spawn("stubborn", |_shutdown| async move {
+            let _sender = sender;
+            tokio::time::sleep(Duration::from_secs(3600)).await;
+        });
+
+        let started = tokio::time::Instant::now();
+        supervisor.shutdown().await;
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(finished.load(Ordering::SeqCst));
+        let aborted = tokio::time::timeout(Duration::from_secs(1), dropped).await;
+        assert!(matches!(aborted, Ok(Err(_))));
+
+        // 关闭开始后不再启动新任务
+        let started = Arc::new(AtomicBool::new(false));
+        let late = started.clone();
+        supervisor.spawn("late", |_shutdown| async move {
+            late.store(true, Ordering::SeqCst);
+        });
+        tokio::task::yield_now().await;
+        assert!(!started.load(Ordering::SeqCst));
+    }
+}
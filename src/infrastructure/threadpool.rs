@@ -0,0 +1,51 @@
+use std::fmt;
+use std::panic::Location;
+use std::time::Instant;
+
+use actix_threadpool::CpuFuture;
+
+use crate::metrics::{THREADPOOL_ACTIVE, THREADPOOL_QUEUED};
+
+// 在 actix_threadpool 中执行阻塞任务，同时记录排队和执行中的任务数；
+// 任务在调用位置的 span 下执行，闭包中的数据库查询会成为它的子 span
+#[track_caller]
+pub fn run<F, I, E>(f: F) -> CpuFuture<I, E>
+where
+    F: FnOnce() -> Result<I, E> + Send + 'static,
+    I: Send + 'static,
+    E: Send + fmt::Debug + 'static,
+{
+    let caller = Location::caller();
+    let span = tracing::info_span!(
+        "threadpool.run",
+        code.filepath = caller.file(),
+        code.lineno = caller.line(),
+        queue_wait_ms = tracing::field::Empty,
+    );
+    let queued_at = Instant::now();
+    let queued = TaskGauge::new(&THREADPOOL_QUEUED);
+    actix_threadpool::run(move || {
+        // 调用方提前取消时任务不会执行，闭包被丢弃时同样会释放排队计数
+        drop(queued);
+        span.record("queue_wait_ms", queued_at.elapsed().as_millis() as u64);
+        let _entered = span.enter();
+        let _active = TaskGauge::new(&THREADPOOL_ACTIVE);
+        f()
+    })
+}
+
+// 创建时加一，丢弃时减一
+struct TaskGauge(&'static prometheus::IntGauge);
+
+impl TaskGauge {
+    fn new(gauge: &'static prometheus::IntGauge) -> Self {
+        gauge.inc();
+        TaskGauge(gauge)
+    }
+}
+
+impl Drop for TaskGauge {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
+}
@@ -7,5 +7,6 @@ pub mod create_app;
 pub mod domain;
 pub mod infrastructure;
 pub mod logging;
+pub mod metrics;
 pub mod services;
 pub mod constants;
@@ -1,21 +1,181 @@
-use crate::config::AppConfig;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::sync::Mutex;
 
-pub fn init_tracing(config: &AppConfig) {
+use chrono::{DateTime, Utc};
+use opentelemetry::trace::{Status, TracerProvider as _};
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::error::{OTelSdkError, OTelSdkResult};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{Sampler, SdkTracerProvider, SpanData, SpanExporter};
+use opentelemetry_sdk::Resource;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
+
+use crate::config::{AppConfig, Otel};
+
+// 持有链路导出器，退出前丢弃时导出剩余的 span
+pub struct TracingGuard {
+    provider: Option<SdkTracerProvider>,
+}
+
+impl Drop for TracingGuard {
+    fn drop(&mut self) {
+        if let Some(provider) = self.provider.take() {
+            if let Err(e) = provider.shutdown() {
+                eprintln!("Failed to shut down trace exporter: {}", e);
+            }
+        }
+    }
+}
+
+pub fn init_tracing(config: &AppConfig) -> TracingGuard {
     use std::str::FromStr;
     let level = tracing::Level::from_str(&config.logging.level).unwrap_or(tracing::Level::INFO);
 
-    match config.logging.format.as_str() {
-        "json" => {
-            tracing_subscriber::fmt()
-                .json()
-                .with_max_level(level)
-                .init();
+    let fmt_layer = match config.logging.format.as_str() {
+        "json" => tracing_subscriber::fmt::layer().json().boxed(),
+        _ => tracing_subscriber::fmt::layer().pretty().boxed(),
+    };
+
+    // 链路导出不受日志级别影响，始终包含 INFO 及以上的 span
+    let provider = config
+        .logging
+        .otel
+        .as_ref()
+        .map(|otel| tracer_provider(otel, &config.app.name));
+    let otel_layer = provider.as_ref().map(|provider| {
+        tracing_opentelemetry::layer()
+            .with_tracer(provider.tracer("zwj_blog"))
+            .with_filter(LevelFilter::INFO)
+    });
+
+    tracing_subscriber::registry()
+        .with(fmt_layer.with_filter(LevelFilter::from_level(level)))
+        .with(otel_layer)
+        .init();
+
+    if let Some(otel) = &config.logging.otel {
+        tracing::info!(
+            "Exporting traces via {} to {}",
+            otel.exporter,
+            if otel.exporter == "file" {
+                &otel.file
+            } else {
+                &otel.endpoint
+            }
+        );
+    }
+    TracingGuard { provider }
+}
+
+fn tracer_provider(otel: &Otel, app_name: &str) -> SdkTracerProvider {
+    // 从请求头 traceparent 继续上游链路，TracingLogger 据此设置根 span 的父节点
+    opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let resource = Resource::builder()
+        .with_service_name(
+            otel.service_name
+                .clone()
+                .unwrap_or_else(|| app_name.to_string()),
+        )
+        .build();
+    let builder = SdkTracerProvider::builder()
+        .with_resource(resource)
+        .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+            otel.sample_ratio,
+        ))));
+
+    let provider = match otel.exporter.as_str() {
+        "file" => {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&otel.file)
+                .expect("Failed to open trace export file");
+            builder.with_batch_exporter(FileSpanExporter {
+                file: Mutex::new(file),
+            })
         }
-        _ => {
-            tracing_subscriber::fmt()
-                .pretty()
-                .with_max_level(level)
-                .init();
+        "otlp" => {
+            let exporter = opentelemetry_otlp::SpanExporter::builder()
+                .with_http()
+                .with_endpoint(&otel.endpoint)
+                .build()
+                .expect("Failed to create OTLP exporter");
+            builder.with_batch_exporter(exporter)
         }
+        other => panic!("Unknown trace exporter: {}", other),
+    };
+    let provider = provider.build();
+    opentelemetry::global::set_tracer_provider(provider.clone());
+    provider
+}
+
+// 每行一个 JSON span 的文件导出器，用于本地调试
+#[derive(Debug)]
+struct FileSpanExporter {
+    file: Mutex<File>,
+}
+
+impl SpanExporter for FileSpanExporter {
+    async fn export(&self, batch: Vec<SpanData>) -> OTelSdkResult {
+        let mut lines = String::new();
+        for span in batch {
+            lines.push_str(&span_json(span).to_string());
+            lines.push('\n');
+        }
+        let mut file = self
+            .file
+            .lock()
+            .map_err(|e| OTelSdkError::InternalFailure(e.to_string()))?;
+        file.write_all(lines.as_bytes())
+            .and_then(|_| file.flush())
+            .map_err(|e| OTelSdkError::InternalFailure(e.to_string()))
     }
 }
+
+fn span_json(span: SpanData) -> serde_json::Value {
+    let attributes = |attributes: &[KeyValue]| -> serde_json::Map<String, serde_json::Value> {
+        attributes
+            .iter()
+            .map(|kv| (kv.key.to_string(), kv.value.to_string().into()))
+            .collect()
+    };
+    let duration_ms = span
+        .end_time
+        .duration_since(span.start_time)
+        .map(|d| d.as_secs_f64() * 1000.0)
+        .unwrap_or_default();
+    let status = match &span.status {
+        Status::Unset => serde_json::json!("unset"),
+        Status::Ok => serde_json::json!("ok"),
+        Status::Error { description } => serde_json::json!({ "error": description }),
+    };
+    let events: Vec<serde_json::Value> = span
+        .events
+        .iter()
+        .map(|event| {
+            serde_json::json!({
+                "name": event.name,
+                "time": DateTime::<Utc>::from(event.timestamp).to_rfc3339(),
+                "attributes": attributes(&event.attributes),
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "trace_id": span.span_context.trace_id().to_string(),
+        "span_id": span.span_context.span_id().to_string(),
+        "parent_span_id": span.parent_span_id.to_string(),
+        "name": span.name,
+        "kind": format!("{:?}", span.span_kind),
+        "start_time": DateTime::<Utc>::from(span.start_time).to_rfc3339(),
+        "duration_ms": duration_ms,
+        "status": status,
+        "attributes": attributes(&span.attributes),
+        "events": events,
+    })
+}
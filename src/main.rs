@@ -1,17 +1,68 @@
 use zwj_blog::{
-    config::AppConfig, container::Container, create_app::create_app, logging,
+    config::AppConfig,
+    container::Container,
+    create_app::create_app,
+    infrastructure::{
+        databases::postgresql::run_migrations,
+        services::service_context::MAINTENANCE_REFRESH_INTERVAL,
+    },
+    logging,
 };
 use actix_web::HttpServer;
+use clap::Parser;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[cfg(test)]
 mod tests;
 
+#[derive(Parser)]
+#[command(name = "zwj_blog")]
+struct Args {
+    /// Configuration file, defaults to config.yaml
+    #[arg(long)]
+    config: Option<PathBuf>,
+    /// Apply pending migrations and exit
+    #[arg(long)]
+    migrate_only: bool,
+    /// Print the effective configuration with secrets redacted and exit
+    #[arg(long)]
+    print_config: bool,
+}
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    let config = AppConfig::new().expect("Failed to load configuration");
+    let args = Args::parse();
+    let config = match AppConfig::load(args.config.as_deref()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Failed to load configuration: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if args.print_config {
+        let yaml = serde_yaml::to_string(&config.redacted()).map_err(std::io::Error::other)?;
+        print!("{}", yaml);
+        return Ok(());
+    }
+
+    let _tracing = logging::init_tracing(&config);
 
-    logging::init_tracing(&config);
+    // --migrate-only: 执行迁移后退出，用于部署流程中单独的迁移步骤
+    let migrate_only = args.migrate_only;
+    if migrate_only || config.database.run_migrations {
+        let applied = run_migrations(&config).map_err(std::io::Error::other)?;
+        if applied.is_empty() {
+            tracing::info!("Database is up to date");
+        } else {
+            tracing::info!("Applied migrations: {}", applied.join(", "));
+        }
+        if migrate_only {
+            return Ok(());
+        }
+    }
 
     tracing::info!("Starting {} server", config.app.name);
     tracing::info!(
@@ -22,10 +73,86 @@ async fn main() -> std::io::Result<()> {
     );
 
     let container = Arc::new(Container::new(&config));
+    let tasks = container.task_supervisor.clone();
+
+    // 定时刷新维护模式状态，请求中只读取内存中的结果
+    let service_context = container.service_context_service.clone();
+    tasks.spawn("maintenance-refresh", move |mut shutdown| async move {
+        let mut interval = tokio::time::interval(MAINTENANCE_REFRESH_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = shutdown.requested() => break,
+            }
+            service_context.refresh_maintenance().await;
+        }
+    });
+
+    // 回填迁移前文件的 SHA-256 hash，关闭时未处理的文件在下次启动时继续
+    let file_service = container.file_service.clone();
+    tasks.spawn("file-hash-backfill", move |shutdown| async move {
+        if let Err(e) = file_service
+            .backfill_hashes(&|| shutdown.is_requested())
+            .await
+        {
+            tracing::error!("Failed to backfill file hashes: {}", e);
+        }
+    });
+
+    // 定时清理孤立文件
+    if let Some(hours) = config.uploads.orphan_cleanup_interval_hours {
+        let file_service = container.file_service.clone();
+        tasks.spawn("orphan-cleanup", move |mut shutdown| async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(hours.max(1) * 3600));
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = shutdown.requested() => break,
+                }
+                if let Err(e) = file_service.cleanup_orphans().await {
+                    tracing::error!("Failed to clean up orphaned files: {}", e);
+                }
+            }
+        });
+    }
+
+    // 自行处理信号，关闭时同时停止 HTTP 服务和后台任务，两者共用同一个宽限期
     let server = HttpServer::new(move || create_app(container.clone()))
         .workers(config.app.workers)
-        .bind((config.app.host.clone(), config.app.port))?;
-
+        .disable_signals()
+        .shutdown_timeout(config.app.shutdown_grace_secs)
+        .bind((config.app.host.clone(), config.app.port))?
+        .run();
+    let handle = server.handle();
     tracing::info!("Server started successfully");
-    server.run().await
+
+    let mut server = std::pin::pin!(server);
+    tokio::select! {
+        result = &mut server => return result,
+        _ = shutdown_signal() => {}
+    }
+    tracing::info!(
+        "Shutdown signal received, no longer accepting requests, waiting up to {:?}",
+        tasks.grace_period()
+    );
+    let (result, ()) = futures::join!(server, async {
+        futures::join!(handle.stop(true), tasks.shutdown());
+    });
+    tracing::info!("Shutdown complete");
+    result
+}
+
+// SIGTERM 或 Ctrl-C
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        let mut terminate = signal(SignalKind::terminate()).expect("Failed to listen for SIGTERM");
+        tokio::select! {
+            _ = terminate.recv() => {}
+            _ = tokio::signal::ctrl_c() => {}
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = tokio::signal::ctrl_c().await;
 }
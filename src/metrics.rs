@@ -0,0 +1,147 @@
+use std::future::Future;
+use std::sync::LazyLock;
+use std::time::Instant;
+
+use prometheus::{
+    register_histogram, register_histogram_vec, register_int_counter, register_int_counter_vec,
+    register_int_gauge, Encoder, Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge,
+    TextEncoder,
+};
+
+// 指标注册到 prometheus 默认 registry，由 /metrics 输出
+
+pub static HTTP_REQUESTS_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "http_requests_total",
+        "HTTP requests by method, route template and status",
+        &["method", "route", "status"]
+    )
+    .unwrap()
+});
+
+pub static HTTP_REQUEST_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "http_request_duration_seconds",
+        "HTTP request latency by method, route template and status",
+        &["method", "route", "status"]
+    )
+    .unwrap()
+});
+
+pub static DB_POOL_CONNECTIONS: LazyLock<IntGauge> = LazyLock::new(|| {
+    register_int_gauge!(
+        "db_pool_connections",
+        "Connections currently managed by the database pool"
+    )
+    .unwrap()
+});
+
+pub static DB_POOL_IDLE_CONNECTIONS: LazyLock<IntGauge> = LazyLock::new(|| {
+    register_int_gauge!(
+        "db_pool_idle_connections",
+        "Idle connections in the database pool"
+    )
+    .unwrap()
+});
+
+pub static DB_POOL_MAX_SIZE: LazyLock<IntGauge> = LazyLock::new(|| {
+    register_int_gauge!(
+        "db_pool_max_size",
+        "Maximum number of connections in the database pool"
+    )
+    .unwrap()
+});
+
+pub static DB_POOL_CHECKOUT_DURATION: LazyLock<Histogram> = LazyLock::new(|| {
+    register_histogram!(
+        "db_pool_checkout_duration_seconds",
+        "Time spent waiting for a database connection"
+    )
+    .unwrap()
+});
+
+pub static DB_POOL_CHECKOUT_TIMEOUTS: LazyLock<IntCounter> = LazyLock::new(|| {
+    register_int_counter!(
+        "db_pool_checkout_timeouts_total",
+        "Database connection checkouts that timed out"
+    )
+    .unwrap()
+});
+
+pub static THREADPOOL_QUEUED: LazyLock<IntGauge> = LazyLock::new(|| {
+    register_int_gauge!(
+        "threadpool_queued_tasks",
+        "Blocking tasks waiting for an actix_threadpool worker"
+    )
+    .unwrap()
+});
+
+pub static THREADPOOL_ACTIVE: LazyLock<IntGauge> = LazyLock::new(|| {
+    register_int_gauge!(
+        "threadpool_active_tasks",
+        "Blocking tasks running on actix_threadpool workers"
+    )
+    .unwrap()
+});
+
+pub static REDIS_COMMAND_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "redis_command_duration_seconds",
+        "Redis command latency by command and result",
+        &["command", "result"],
+        vec![0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0]
+    )
+    .unwrap()
+});
+
+pub static UPLOAD_BYTES_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "upload_bytes_total",
+        "Uploaded bytes by upload method",
+        &["method"]
+    )
+    .unwrap()
+});
+
+pub static AUTH_ATTEMPTS_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "auth_attempts_total",
+        "Signature login attempts by result",
+        &["result"]
+    )
+    .unwrap()
+});
+
+pub static CACHE_REQUESTS_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "cache_requests_total",
+        "Response cache lookups by cache and result (hit, miss, bypass, error)",
+        &["cache", "result"]
+    )
+    .unwrap()
+});
+
+// 记录一次 Redis 命令的耗时，result 为 ok 或 error
+pub async fn time_redis<F, T, E>(command: &str, fut: F) -> Result<T, E>
+where
+    F: Future<Output = Result<T, E>>,
+{
+    let start = Instant::now();
+    let result = fut.await;
+    let status = if result.is_ok() { "ok" } else { "error" };
+    REDIS_COMMAND_DURATION
+        .with_label_values(&[command, status])
+        .observe(start.elapsed().as_secs_f64());
+    result
+}
+
+// 以 Prometheus 文本格式输出所有指标
+pub fn render() -> Result<(String, String), prometheus::Error> {
+    let encoder = TextEncoder::new();
+    let mut buffer = Vec::new();
+    encoder.encode(&prometheus::gather(), &mut buffer)?;
+    Ok((
+        encoder.format_type().to_string(),
+        String::from_utf8_lossy(&buffer).into_owned(),
+    ))
+}
@@ -3,32 +3,59 @@ use std::sync::Arc;
 
 use crate::domain::{
     error::CommonError,
-    models::blog::{Blog, CreateBlog, UpdateBlog},
+    models::{
+        blog::{Blog, BlogArchiveYear, BlogNeighbors, BlogSummary, CreateBlog, UpdateBlog},
+        cache::CacheGroup,
+        version::VersionedUpdate,
+    },
     repositories::{
         blog::{BlogQueryParams, BlogRepository},
         repository::ResultPaging,
     },
-    services::blog::BlogService,
+    services::{blog::BlogService, cache::CacheService},
+    slug::create_with_unique_slug,
 };
 
 #[derive(Clone)]
 pub struct BlogServiceImpl {
     pub repository: Arc<dyn BlogRepository>,
+    pub cache: Arc<dyn CacheService>,
 }
 
 impl BlogServiceImpl {
-    pub fn new(repository: Arc<dyn BlogRepository>) -> Self {
-        BlogServiceImpl { repository }
+    pub fn new(repository: Arc<dyn BlogRepository>, cache: Arc<dyn CacheService>) -> Self {
+        BlogServiceImpl { repository, cache }
     }
 }
 
 #[async_trait]
 impl BlogService for BlogServiceImpl {
     async fn create(&self, blog: CreateBlog) -> Result<Blog, CommonError> {
-        self.repository
-            .create(&blog)
+        // 未提供 slug 时根据标题生成
+        let result = if blog.slug.trim().is_empty() {
+            let repository = self.repository.clone();
+            create_with_unique_slug(
+                &blog.title,
+                |candidate| {
+                    let repository = repository.clone();
+                    async move { repository.slug_exists(&candidate).await }
+                },
+                |slug| {
+                    let repository = repository.clone();
+                    let blog = CreateBlog {
+                        slug,
+                        ..blog.clone()
+                    };
+                    async move { repository.create(&blog).await }
+                },
+            )
             .await
-            .map_err(|e| -> CommonError { e.into() })
+        } else {
+            self.repository.create(&blog).await
+        }
+        .map_err(|e| -> CommonError { e.into() })?;
+        self.cache.evict(&[CacheGroup::Blogs]).await;
+        Ok(result)
     }
 
     async fn list(&self, params: BlogQueryParams) -> Result<ResultPaging<Blog>, CommonError> {
@@ -38,6 +65,16 @@ impl BlogService for BlogServiceImpl {
             .map_err(|e| -> CommonError { e.into() })
     }
 
+    async fn list_summaries(
+        &self,
+        params: BlogQueryParams,
+    ) -> Result<ResultPaging<BlogSummary>, CommonError> {
+        self.repository
+            .list_summaries(params)
+            .await
+            .map_err(|e| -> CommonError { e.into() })
+    }
+
     async fn get(&self, blog_id: i32) -> Result<Option<Blog>, CommonError> {
         self.repository
             .get(blog_id)
@@ -59,6 +96,13 @@ impl BlogService for BlogServiceImpl {
             .map_err(|e| -> CommonError { e.into() })
     }
 
+    async fn get_published_summaries(&self) -> Result<Vec<BlogSummary>, CommonError> {
+        self.repository
+            .get_published_summaries()
+            .await
+            .map_err(|e| -> CommonError { e.into() })
+    }
+
     async fn get_published_by_slug(&self, slug: &str) -> Result<Option<Blog>, CommonError> {
         self.repository
             .get_published_by_slug(slug)
@@ -66,22 +110,54 @@ impl BlogService for BlogServiceImpl {
             .map_err(|e| -> CommonError { e.into() })
     }
 
+    async fn find_published_redirect(&self, slug: &str) -> Result<Option<String>, CommonError> {
+        self.repository
+            .find_published_redirect(slug)
+            .await
+            .map_err(|e| -> CommonError { e.into() })
+    }
+
+    async fn get_published_neighbors(&self, blog: &Blog) -> Result<BlogNeighbors, CommonError> {
+        self.repository
+            .get_published_neighbors(blog)
+            .await
+            .map_err(|e| -> CommonError { e.into() })
+    }
+
+    async fn get_archive(
+        &self,
+        tag_slug: Option<String>,
+    ) -> Result<Vec<BlogArchiveYear>, CommonError> {
+        self.repository
+            .get_archive(tag_slug)
+            .await
+            .map_err(|e| -> CommonError { e.into() })
+    }
+
     async fn update(
         &self,
         blog_id: i32,
         update_blog: UpdateBlog,
-    ) -> Result<Option<Blog>, CommonError> {
-        self.repository
+    ) -> Result<VersionedUpdate<Blog>, CommonError> {
+        let result = self
+            .repository
             .update(blog_id, &update_blog)
             .await
-            .map_err(|e| -> CommonError { e.into() })
+            .map_err(|e| -> CommonError { e.into() })?;
+        if let VersionedUpdate::Updated(_) = &result {
+            self.cache.evict(&[CacheGroup::Blogs]).await;
+        }
+        Ok(result)
     }
 
     async fn delete(&self, blog_id: i32) -> Result<bool, CommonError> {
-        self.repository
+        let result = self
+            .repository
             .delete(blog_id)
             .await
-            .map_err(|e| -> CommonError { e.into() })
+            .map_err(|e| -> CommonError { e.into() })?;
+        self.cache.evict(&[CacheGroup::Blogs]).await;
+        Ok(result)
     }
 
     async fn exists(&self, blog_id: i32) -> Result<bool, CommonError> {
@@ -92,9 +168,12 @@ impl BlogService for BlogServiceImpl {
     }
 
     async fn toggle_published(&self, blog_id: i32) -> Result<Option<Blog>, CommonError> {
-        self.repository
+        let result = self
+            .repository
             .toggle_published(blog_id)
             .await
-            .map_err(|e| -> CommonError { e.into() })
+            .map_err(|e| -> CommonError { e.into() })?;
+        self.cache.evict(&[CacheGroup::Blogs]).await;
+        Ok(result)
     }
 }
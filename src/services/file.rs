@@ -1,38 +1,1296 @@
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use actix_multipart::form::tempfile::TempFile;
 use async_trait::async_trait;
+use futures_util::StreamExt;
+use rand::{distributions::Alphanumeric, prelude::Distribution};
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
+use crate::config::Uploads;
 use crate::domain::{
-    error::CommonError,
-    models::file::{FileInfo, Files},
-    repositories::file::FileRepository,
-    services::file::FileService,
+    error::{CommonError, RepositoryError},
+    file_hash::{ContentHasher, MD5, SHA256},
+    models::cache::CacheGroup,
+    models::file::{
+        File, FileContent, FileDeletion, FileDetail, FileInfo, FileIntegrityReport, FileVisibility,
+        Files, ImageVariant, SignedUrl, UploadFile, UploadMetadata, UploadOptions, UploadSession,
+    },
+    repositories::{
+        file::{FileQueryParams, FileRepository},
+        redis::RedisRepository,
+        repository::ResultPaging,
+        storage::ByteStream,
+    },
+    services::{cache::CacheService, file::FileService},
+    signed_url::{self, SignatureError},
 };
+use crate::metrics::UPLOAD_BYTES_TOTAL;
 
 #[derive(Clone)]
 pub struct FileServiceImpl {
     pub repository: Arc<dyn FileRepository>,
+    pub upload_sessions: Arc<dyn RedisRepository<UploadSession>>,
+    pub uploads: Uploads,
+    pub cache: Arc<dyn CacheService>,
+    // 分片上传中的hash状态，每个上传一把锁，保证分片按顺序写入
+    partials: Arc<Mutex<HashMap<String, PartialLock>>>,
+    // 私有文件签名URL的密钥
+    signing_secret: Arc<Vec<u8>>,
 }
 
+// 已写入分片的增量hash，进程重启后从分片文件重新计算
+struct PartialUpload {
+    hasher: Sha256,
+    hashed: i64,
+}
+
+type PartialLock = Arc<tokio::sync::Mutex<Option<PartialUpload>>>;
+
+// 检测文件类型读取的文件头长度
+const SNIFF_LENGTH: u64 = 8192;
+const REDIS_UPLOAD_SESSION_KEY: &str = "upload_session:";
+// 私有文件签名URL的路径前缀
+const PRIVATE_URL_PREFIX: &str = "/private";
+// 内容响应中包含关联文件的URL和可见性，文件变更时这些缓存都需要失效
+const ATTACHMENT_CACHE_GROUPS: &[CacheGroup] = &[
+    CacheGroup::Blogs,
+    CacheGroup::Notes,
+    CacheGroup::Snippets,
+    CacheGroup::Tags,
+];
+
 impl FileServiceImpl {
-    pub fn new(repository: Arc<dyn FileRepository>) -> Self {
-        FileServiceImpl { repository }
+    pub fn new(
+        repository: Arc<dyn FileRepository>,
+        upload_sessions: Arc<dyn RedisRepository<UploadSession>>,
+        uploads: Uploads,
+        cache: Arc<dyn CacheService>,
+    ) -> Self {
+        let signing_secret = match &uploads.signing_secret {
+            Some(secret) => secret.clone().into_bytes(),
+            None => {
+                tracing::warn!(
+                    "uploads.signing_secret is not configured, signed URLs become invalid after restart"
+                );
+                rand::random::<[u8; 32]>().to_vec()
+            }
+        };
+        FileServiceImpl {
+            repository,
+            upload_sessions,
+            uploads,
+            cache,
+            partials: Arc::new(Mutex::new(HashMap::new())),
+            signing_secret: Arc::new(signing_secret),
+        }
+    }
+
+    fn session_key(upload_id: &str) -> String {
+        format!("{}{}", REDIS_UPLOAD_SESSION_KEY, upload_id)
+    }
+
+    fn part_path(&self, upload_id: &str) -> PathBuf {
+        Path::new(&self.uploads.chunk_dir).join(format!("{}.part", upload_id))
+    }
+
+    fn partial_lock(&self, upload_id: &str) -> PartialLock {
+        self.partials
+            .lock()
+            .unwrap()
+            .entry(upload_id.to_string())
+            .or_default()
+            .clone()
+    }
+
+    // 上传结束或出错后移除hash状态；仍有其他请求等待该锁时保留，避免同一上传出现两把锁
+    fn release_partial(&self, upload_id: &str, lock: PartialLock) {
+        let mut partials = self.partials.lock().unwrap();
+        if Arc::strong_count(&lock) <= 2 {
+            partials.remove(upload_id);
+        }
+    }
+
+    async fn load_session(&self, upload_id: &str) -> Result<Option<UploadSession>, CommonError> {
+        // upload_id 用于拼接文件路径，只接受生成时的字母数字格式
+        if upload_id.is_empty() || !upload_id.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Ok(None);
+        }
+        self.upload_sessions
+            .get(&Self::session_key(upload_id))
+            .await
+            .map_err(|e| -> CommonError { e.into() })
+    }
+
+    async fn save_session(&self, session: &UploadSession) -> Result<(), CommonError> {
+        self.upload_sessions
+            .set_with_ttl(
+                &Self::session_key(&session.upload_id),
+                session.clone(),
+                Duration::from_secs(self.uploads.session_ttl_hours * 3600),
+            )
+            .await
+            .map_err(|e| -> CommonError { e.into() })
+    }
+
+    // 从分片文件重新计算已接收部分的hash，并截掉超出已确认长度的残留数据
+    async fn rebuild_partial(&self, session: &UploadSession) -> Result<PartialUpload, CommonError> {
+        let storage_error = |e: std::io::Error| -> CommonError {
+            RepositoryError::StorageError(e.to_string()).into()
+        };
+        let path = self.part_path(&session.upload_id);
+        let file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .read(true)
+            .open(&path)
+            .await
+            .map_err(storage_error)?;
+        file.set_len(session.received as u64)
+            .await
+            .map_err(storage_error)?;
+
+        let mut hasher = Sha256::new();
+        let mut reader = file;
+        let mut buffer = [0; 8192];
+        loop {
+            let bytes_read = reader.read(&mut buffer).await.map_err(storage_error)?;
+            if bytes_read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..bytes_read]);
+        }
+        Ok(PartialUpload {
+            hasher,
+            hashed: session.received,
+        })
+    }
+
+    // 删除过期会话遗留的分片文件和对应的hash状态
+    async fn sweep_stale_parts(&self) {
+        let Ok(mut entries) = tokio::fs::read_dir(&self.uploads.chunk_dir).await else {
+            return;
+        };
+        let ttl = Duration::from_secs(self.uploads.session_ttl_hours * 3600);
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let stale = entry
+                .metadata()
+                .await
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|elapsed| elapsed > ttl);
+            if stale {
+                let _ = tokio::fs::remove_file(entry.path()).await;
+                // 会话已过期，同时移除内存中的hash状态
+                if let Some(upload_id) = entry.path().file_stem().and_then(|s| s.to_str()) {
+                    self.partials.lock().unwrap().remove(upload_id);
+                }
+            }
+        }
+    }
+
+    // 在持有上传锁时写入分片，会话需要在加锁后重新读取以获得最新的 received
+    async fn write_chunk(
+        &self,
+        partial: &mut Option<PartialUpload>,
+        upload_id: &str,
+        offset: i64,
+        chunk: &[u8],
+    ) -> Result<Option<UploadSession>, CommonError> {
+        let Some(mut session) = self.load_session(upload_id).await? else {
+            return Ok(None);
+        };
+
+        // 偏移量必须等于已接收的字节数，客户端可以查询会话后从 received 处续传
+        if offset != session.received {
+            return Err(CommonError {
+                message: format!(
+                    "分片偏移量不匹配: 期望 {}，实际 {}",
+                    session.received, offset
+                ),
+                code: 409,
+            });
+        }
+        if session.received + chunk.len() as i64 > session.total_size {
+            return Err(RepositoryError::ValidationError("分片超出文件大小".to_string()).into());
+        }
+
+        if partial.as_ref().map(|p| p.hashed) != Some(session.received) {
+            *partial = Some(self.rebuild_partial(&session).await?);
+        }
+
+        let storage_error = |e: std::io::Error| -> CommonError {
+            RepositoryError::StorageError(e.to_string()).into()
+        };
+        let mut file = tokio::fs::OpenOptions::new()
+            .append(true)
+            .open(self.part_path(upload_id))
+            .await
+            .map_err(storage_error)?;
+        file.write_all(chunk).await.map_err(storage_error)?;
+        file.flush().await.map_err(storage_error)?;
+
+        if let Some(partial) = partial.as_mut() {
+            partial.hasher.update(chunk);
+            partial.hashed += chunk.len() as i64;
+        }
+        session.received += chunk.len() as i64;
+        self.save_session(&session).await?;
+        UPLOAD_BYTES_TOTAL
+            .with_label_values(&["chunked"])
+            .inc_by(chunk.len() as u64);
+        Ok(Some(session))
+    }
+
+    async fn finish_upload(
+        &self,
+        partial: &mut Option<PartialUpload>,
+        upload_id: &str,
+    ) -> Result<Option<FileInfo>, CommonError> {
+        let Some(session) = self.load_session(upload_id).await? else {
+            return Ok(None);
+        };
+        if session.received != session.total_size {
+            return Err(RepositoryError::ValidationError(format!(
+                "文件未上传完成: 已接收 {} / {} 字节",
+                session.received, session.total_size
+            ))
+            .into());
+        }
+
+        if partial.as_ref().map(|p| p.hashed) != Some(session.received) {
+            *partial = Some(self.rebuild_partial(&session).await?);
+        }
+        let file_hash = partial
+            .take()
+            .map(|p| format!("{:x}", p.hasher.finalize()))
+            .unwrap_or_default();
+
+        let path = self.part_path(upload_id);
+        let result = match self
+            .detect(
+                &path,
+                session.file_name.clone(),
+                session.total_size,
+                UploadOptions {
+                    keep_metadata: session.keep_metadata,
+                    visibility: session.visibility,
+                },
+            )
+            .await
+        {
+            Ok(metadata) => self
+                .repository
+                .save_file(&path, file_hash, metadata)
+                .await
+                .map_err(|e| -> CommonError { e.into() }),
+            Err(e) => Err(e),
+        };
+
+        // 无论成功还是校验失败，会话都已结束
+        self.upload_sessions
+            .delete(&Self::session_key(upload_id))
+            .await
+            .map_err(|e| -> CommonError { e.into() })?;
+        let _ = tokio::fs::remove_file(&path).await;
+
+        result.map(|url| {
+            Some(FileInfo {
+                file_name: session.file_name,
+                url,
+            })
+        })
+    }
+
+    async fn load(
+        &self,
+        name: &str,
+        variant: Option<ImageVariant>,
+        include_private: bool,
+    ) -> Result<Option<FileContent>, CommonError> {
+        match variant {
+            Some(variant) => {
+                self.repository
+                    .load_variant(name, &variant, include_private)
+                    .await
+            }
+            None => self.repository.load(name).await,
+        }
+        .map_err(|e| -> CommonError { e.into() })
+    }
+
+    // 流式读取存储中的文件，同时计算多个hash；文件不存在时返回 None
+    async fn hash_stored<const N: usize>(
+        &self,
+        file: &File,
+        mut hashers: [ContentHasher; N],
+    ) -> Result<Option<[String; N]>, CommonError> {
+        let Some(mut chunks) = self
+            .repository
+            .read(file)
+            .await
+            .map_err(|e| -> CommonError { e.into() })?
+        else {
+            return Ok(None);
+        };
+        while let Some(chunk) = chunks.next().await {
+            let chunk = chunk.map_err(|e| -> CommonError { e.into() })?;
+            for hasher in hashers.iter_mut() {
+                hasher.update(&chunk);
+            }
+        }
+        Ok(Some(hashers.map(ContentHasher::finalize)))
+    }
+
+    fn orphan_cutoff(&self) -> chrono::NaiveDateTime {
+        chrono::Utc::now().naive_utc()
+            - chrono::Duration::hours(self.uploads.orphan_grace_hours as i64)
+    }
+
+    // 根据文件头检测类型，校验类型白名单、大小和扩展名
+    async fn detect(
+        &self,
+        path: &Path,
+        original_filename: String,
+        byte_size: i64,
+        options: UploadOptions,
+    ) -> Result<UploadMetadata, CommonError> {
+        let invalid =
+            |message: String| -> CommonError { RepositoryError::ValidationError(message).into() };
+
+        let mut header = Vec::new();
+        tokio::fs::File::open(path)
+            .await
+            .map_err(|e| invalid(e.to_string()))?
+            .take(SNIFF_LENGTH)
+            .read_to_end(&mut header)
+            .await
+            .map_err(|e| invalid(e.to_string()))?;
+
+        let upload_type = infer::get(&header)
+            .and_then(|kind| {
+                self.uploads
+                    .types
+                    .iter()
+                    .find(|t| t.mime == kind.mime_type())
+            })
+            .ok_or_else(|| invalid(format!("不支持的文件类型: {}", original_filename)))?;
+
+        if byte_size as u64 > upload_type.max_size {
+            return Err(invalid(format!(
+                "文件 {} 超过大小限制 {} 字节",
+                original_filename, upload_type.max_size
+            )));
+        }
+
+        if let Some(extension) = client_extension(&original_filename) {
+            if !upload_type.extensions.contains(&extension) {
+                return Err(invalid(format!(
+                    "文件 {} 的扩展名与内容类型 {} 不符",
+                    original_filename, upload_type.mime
+                )));
+            }
+        }
+
+        Ok(UploadMetadata {
+            mime_type: upload_type.mime.clone(),
+            extension: upload_type.extensions.first().cloned().unwrap_or_default(),
+            byte_size,
+            original_filename,
+            strip_metadata: self.uploads.strip_metadata && !options.keep_metadata,
+            visibility: options.visibility,
+        })
+    }
+
+    async fn validate(
+        &self,
+        file: TempFile,
+        options: UploadOptions,
+    ) -> Result<UploadFile, CommonError> {
+        let original_filename = file
+            .file_name
+            .clone()
+            .unwrap_or_else(|| "unknown".to_string());
+        let metadata = self
+            .detect(
+                file.file.path(),
+                original_filename,
+                file.size as i64,
+                options,
+            )
+            .await?;
+        Ok(UploadFile { file, metadata })
     }
 }
 
+fn client_extension(file_name: &str) -> Option<String> {
+    Path::new(file_name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase())
+}
+
 #[async_trait]
 impl FileService for FileServiceImpl {
-    async fn upload_files(&self, files: Vec<TempFile>) -> Result<Files, CommonError> {
+    async fn upload_files(
+        &self,
+        files: Vec<TempFile>,
+        options: UploadOptions,
+    ) -> Result<Files, CommonError> {
+        // 全部校验通过后再保存，避免部分文件已写入
+        let mut uploads = Vec::with_capacity(files.len());
+        for file in files {
+            uploads.push(self.validate(file, options).await?);
+        }
+        let bytes: usize = uploads.iter().map(|upload| upload.file.size).sum();
         let files_info = self
             .repository
-            .save_files(files)
+            .save_files(uploads)
             .await
             .map_err(|e| -> CommonError { e.into() })?;
+        UPLOAD_BYTES_TOTAL
+            .with_label_values(&["multipart"])
+            .inc_by(bytes as u64);
         let mut files: Vec<FileInfo> = Vec::new();
         for (file_name, url) in files_info {
             files.push(FileInfo { file_name, url });
         }
         Ok(Files { files })
     }
+
+    async fn read_file(
+        &self,
+        name: &str,
+        variant: Option<ImageVariant>,
+    ) -> Result<Option<FileContent>, CommonError> {
+        // 私有文件按不存在处理，不暴露文件是否存在
+        Ok(self
+            .load(name, variant, false)
+            .await?
+            .filter(|content| content.visibility == FileVisibility::Public))
+    }
+
+    async fn read_signed_file(
+        &self,
+        name: &str,
+        expires: i64,
+        signature: &str,
+        variant: Option<ImageVariant>,
+    ) -> Result<Option<FileContent>, CommonError> {
+        let now = chrono::Utc::now().timestamp();
+        if let Err(e) = signed_url::check(&self.signing_secret, name, expires, signature, now) {
+            let message = match e {
+                SignatureError::Invalid => "签名无效",
+                SignatureError::Expired => "签名已过期",
+            };
+            return Err(CommonError {
+                message: message.to_string(),
+                code: 403,
+            });
+        }
+        self.load(name, variant, true).await
+    }
+
+    async fn open_file(
+        &self,
+        content: &FileContent,
+        range: Range<u64>,
+    ) -> Result<Option<ByteStream>, CommonError> {
+        self.repository
+            .open(content, range)
+            .await
+            .map_err(|e| -> CommonError { e.into() })
+    }
+
+    async fn sign_url(
+        &self,
+        file_id: i32,
+        expires_in: Option<u64>,
+    ) -> Result<Option<SignedUrl>, CommonError> {
+        let expires_in = expires_in.unwrap_or(self.uploads.signed_url_ttl_secs);
+        if expires_in == 0 || expires_in > self.uploads.signed_url_max_ttl_secs {
+            return Err(RepositoryError::ValidationError(format!(
+                "有效期必须在 1 到 {} 秒之间",
+                self.uploads.signed_url_max_ttl_secs
+            ))
+            .into());
+        }
+        let Some(file) = self
+            .repository
+            .get(file_id)
+            .await
+            .map_err(|e| -> CommonError { e.into() })?
+        else {
+            return Ok(None);
+        };
+        let expires_at = chrono::Utc::now() + chrono::Duration::seconds(expires_in as i64);
+        let expires = expires_at.timestamp();
+        let signature = signed_url::sign(&self.signing_secret, &file.storage_key, expires);
+        Ok(Some(SignedUrl {
+            url: format!(
+                "{}/{}?expires={}&signature={}",
+                PRIVATE_URL_PREFIX, file.storage_key, expires, signature
+            ),
+            expires_at,
+        }))
+    }
+
+    async fn set_visibility(
+        &self,
+        file_id: i32,
+        visibility: FileVisibility,
+    ) -> Result<Option<File>, CommonError> {
+        let Some(file) = self
+            .repository
+            .get(file_id)
+            .await
+            .map_err(|e| -> CommonError { e.into() })?
+        else {
+            return Ok(None);
+        };
+        if file.original_id.is_some() {
+            return Err(RepositoryError::ValidationError(
+                "图片变体的可见性跟随原始文件".to_string(),
+            )
+            .into());
+        }
+        let updated = self
+            .repository
+            .update_visibility(file_id, visibility)
+            .await
+            .map_err(|e| -> CommonError { e.into() })?;
+        if updated.is_some() {
+            self.cache.evict(ATTACHMENT_CACHE_GROUPS).await;
+        }
+        Ok(updated)
+    }
+
+    async fn list(&self, params: FileQueryParams) -> Result<ResultPaging<File>, CommonError> {
+        self.repository
+            .list(params)
+            .await
+            .map_err(|e| -> CommonError { e.into() })
+    }
+
+    async fn get_detail(&self, file_id: i32) -> Result<Option<FileDetail>, CommonError> {
+        let Some(file) = self
+            .repository
+            .get(file_id)
+            .await
+            .map_err(|e| -> CommonError { e.into() })?
+        else {
+            return Ok(None);
+        };
+        // 变体的引用归属于原始文件
+        let original = match file.original_id {
+            Some(original_id) => self
+                .repository
+                .get(original_id)
+                .await
+                .map_err(|e| -> CommonError { e.into() })?
+                .unwrap_or_else(|| file.clone()),
+            None => file.clone(),
+        };
+        let variants = match file.original_id {
+            Some(_) => Vec::new(),
+            None => self
+                .repository
+                .list_variants(file.id)
+                .await
+                .map_err(|e| -> CommonError { e.into() })?,
+        };
+        let references = self
+            .repository
+            .find_references(&original)
+            .await
+            .map_err(|e| -> CommonError { e.into() })?;
+        Ok(Some(FileDetail {
+            file,
+            variants,
+            references,
+        }))
+    }
+
+    async fn delete(&self, file_id: i32) -> Result<FileDeletion, CommonError> {
+        let Some(file) = self
+            .repository
+            .get(file_id)
+            .await
+            .map_err(|e| -> CommonError { e.into() })?
+        else {
+            return Ok(FileDeletion::NotFound);
+        };
+        let references = self
+            .repository
+            .find_references(&file)
+            .await
+            .map_err(|e| -> CommonError { e.into() })?;
+        if !references.is_empty() {
+            return Ok(FileDeletion::Referenced(references));
+        }
+        match self
+            .repository
+            .delete(file_id)
+            .await
+            .map_err(|e| -> CommonError { e.into() })?
+        {
+            true => {
+                self.cache.evict(ATTACHMENT_CACHE_GROUPS).await;
+                Ok(FileDeletion::Deleted)
+            }
+            false => Ok(FileDeletion::NotFound),
+        }
+    }
+
+    async fn find_orphans(&self) -> Result<Vec<File>, CommonError> {
+        self.repository
+            .find_orphans(self.orphan_cutoff())
+            .await
+            .map_err(|e| -> CommonError { e.into() })
+    }
+
+    async fn cleanup_orphans(&self) -> Result<Vec<File>, CommonError> {
+        let orphans = self.find_orphans().await?;
+        let mut deleted = Vec::with_capacity(orphans.len());
+        for file in orphans {
+            // 删除前重新检查引用，避免删除期间被新内容引用
+            if let FileDeletion::Deleted = self.delete(file.id).await? {
+                deleted.push(file);
+            }
+        }
+        tracing::info!("Cleaned up {} orphaned files", deleted.len());
+        Ok(deleted)
+    }
+
+    async fn backfill_hashes(
+        &self,
+        stop: &(dyn Fn() -> bool + Sync),
+    ) -> Result<usize, CommonError> {
+        let files = self
+            .repository
+            .list_all()
+            .await
+            .map_err(|e| -> CommonError { e.into() })?;
+        let mut count = 0;
+        for file in files.into_iter().filter(|f| f.hash_algorithm == MD5) {
+            if stop() {
+                // 未处理的文件在下次启动时继续回填
+                tracing::info!("Hash backfill stopped by shutdown");
+                break;
+            }
+            let hashers = [MD5, SHA256].map(|algorithm| ContentHasher::new(algorithm).unwrap());
+            let [md5, sha256] = match self.hash_stored(&file, hashers).await {
+                Ok(Some(hashes)) => hashes,
+                Ok(None) => {
+                    tracing::warn!("Skip hash backfill, file missing: {}", file.storage_key);
+                    continue;
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Skip hash backfill, failed to read {}: {}",
+                        file.storage_key,
+                        e
+                    );
+                    continue;
+                }
+            };
+            // 内容已损坏的文件保留原hash，由完整性检查报告
+            if md5 != file.file_hash {
+                tracing::warn!("Skip hash backfill, file corrupted: {}", file.storage_key);
+                continue;
+            }
+            // 相同内容在迁移后已重新上传时，两条原始文件记录不能使用同一个hash，保留 MD5 记录
+            if file.original_id.is_none() {
+                match self.repository.find_by_hash(&sha256).await {
+                    Ok(None) => {}
+                    Ok(Some(existing)) => {
+                        tracing::warn!(
+                            "Skip hash backfill, file {} has the same content as file {}",
+                            file.id,
+                            existing.id
+                        );
+                        continue;
+                    }
+                    Err(e) => {
+                        tracing::warn!("Skip hash backfill of file {}: {:?}", file.id, e);
+                        continue;
+                    }
+                }
+            }
+            if let Err(e) = self.repository.update_hash(file.id, &sha256, SHA256).await {
+                tracing::warn!("Skip hash backfill of file {}: {:?}", file.id, e);
+                continue;
+            }
+            count += 1;
+        }
+        if count > 0 {
+            tracing::info!("Backfilled SHA-256 hashes for {} files", count);
+        }
+        Ok(count)
+    }
+
+    async fn check_integrity(&self) -> Result<FileIntegrityReport, CommonError> {
+        let files = self
+            .repository
+            .list_all()
+            .await
+            .map_err(|e| -> CommonError { e.into() })?;
+        let mut report = FileIntegrityReport::default();
+        for file in files {
+            report.checked += 1;
+            // 不支持的算法无法校验，按损坏报告
+            let Some(hasher) = ContentHasher::new(&file.hash_algorithm) else {
+                report.corrupted.push(file);
+                continue;
+            };
+            match self.hash_stored(&file, [hasher]).await? {
+                None => report.missing.push(file),
+                Some([actual]) => {
+                    if actual != file.file_hash {
+                        report.corrupted.push(file);
+                    }
+                }
+            }
+        }
+        tracing::info!(
+            "Checked {} files: {} missing, {} corrupted",
+            report.checked,
+            report.missing.len(),
+            report.corrupted.len()
+        );
+        Ok(report)
+    }
+
+    async fn init_upload(
+        &self,
+        file_name: String,
+        total_size: i64,
+        options: UploadOptions,
+    ) -> Result<UploadSession, CommonError> {
+        let invalid =
+            |message: String| -> CommonError { RepositoryError::ValidationError(message).into() };
+        if total_size <= 0 {
+            return Err(invalid("文件大小必须大于0".to_string()));
+        }
+        // 内容类型在合并后检测，这里先按扩展名允许的最大限制提前拒绝
+        let max_size = self
+            .uploads
+            .types
+            .iter()
+            .filter(|t| match client_extension(&file_name) {
+                Some(extension) => t.extensions.contains(&extension),
+                None => true,
+            })
+            .map(|t| t.max_size)
+            .max()
+            .ok_or_else(|| invalid(format!("不支持的文件类型: {}", file_name)))?;
+        if total_size as u64 > max_size {
+            return Err(invalid(format!(
+                "文件 {} 超过大小限制 {} 字节",
+                file_name, max_size
+            )));
+        }
+
+        self.sweep_stale_parts().await;
+        tokio::fs::create_dir_all(&self.uploads.chunk_dir)
+            .await
+            .map_err(|e| -> CommonError { RepositoryError::StorageError(e.to_string()).into() })?;
+
+        let upload_id = Alphanumeric
+            .sample_iter(&mut rand::thread_rng())
+            .take(32)
+            .map(char::from)
+            .collect::<String>();
+        let session = UploadSession {
+            upload_id,
+            file_name,
+            total_size,
+            received: 0,
+            chunk_size: self.uploads.chunk_size as i64,
+            created_at: chrono::Utc::now(),
+            keep_metadata: options.keep_metadata,
+            visibility: options.visibility,
+        };
+        self.save_session(&session).await?;
+        Ok(session)
+    }
+
+    async fn get_upload(&self, upload_id: &str) -> Result<Option<UploadSession>, CommonError> {
+        self.load_session(upload_id).await
+    }
+
+    async fn append_chunk(
+        &self,
+        upload_id: &str,
+        offset: i64,
+        chunk: &[u8],
+    ) -> Result<Option<UploadSession>, CommonError> {
+        // 先确认会话存在，无效或过期的 upload_id 不会在 partials 中留下记录
+        if self.load_session(upload_id).await?.is_none() {
+            return Ok(None);
+        }
+        let lock = self.partial_lock(upload_id);
+        let mut partial = lock.lock().await;
+        let result = self
+            .write_chunk(&mut partial, upload_id, offset, chunk)
+            .await;
+        drop(partial);
+        // 出错时已写入的hash状态不再可信，下次写入时从分片文件重新计算
+        if !matches!(result, Ok(Some(_))) {
+            self.release_partial(upload_id, lock);
+        }
+        result
+    }
+
+    async fn complete_upload(&self, upload_id: &str) -> Result<Option<FileInfo>, CommonError> {
+        if self.load_session(upload_id).await?.is_none() {
+            return Ok(None);
+        }
+        let lock = self.partial_lock(upload_id);
+        let mut partial = lock.lock().await;
+        let result = self.finish_upload(&mut partial, upload_id).await;
+        drop(partial);
+        self.release_partial(upload_id, lock);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::ops::Range;
+    use std::path::Path;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use actix_web::web::Bytes;
+    use async_trait::async_trait;
+    use chrono::NaiveDateTime;
+    use futures_util::stream;
+    use sha2::{Digest, Sha256};
+
+    use super::FileServiceImpl;
+    use crate::config::Uploads;
+    use crate::domain::{
+        error::RepositoryError,
+        file_hash::{MD5, SHA256},
+        models::cache::{CacheGroup, CacheKey, CacheLookup},
+        models::file::{
+            CreateFile, File, FileContent, FileReference, FileVisibility, ImageVariant, UploadFile,
+            UploadMetadata, UploadOptions, UploadSession,
+        },
+        repositories::{
+            file::{FileQueryParams, FileRepository},
+            redis::RedisRepository,
+            repository::{RepositoryResult, ResultPaging},
+            storage::ByteStream,
+        },
+        services::{cache::CacheService, file::FileService},
+    };
+
+    // 内存中的文件记录和存储内容，只实现测试用到的方法
+    #[derive(Default)]
+    struct MemoryFiles {
+        files: Mutex<Vec<File>>,
+        contents: HashMap<String, Vec<u8>>,
+    }
+
+    #[async_trait]
+    impl FileRepository for MemoryFiles {
+        async fn save_files(
+            &self,
+            _files: Vec<UploadFile>,
+        ) -> RepositoryResult<HashMap<String, String>> {
+            unimplemented!()
+        }
+
+        async fn save_file(
+            &self,
+            _source: &Path,
+            _file_hash: String,
+            _metadata: UploadMetadata,
+        ) -> RepositoryResult<String> {
+            unimplemented!()
+        }
+
+        async fn find_by_hash(&self, file_hash: &str) -> RepositoryResult<Option<File>> {
+            let files = self.files.lock().unwrap();
+            Ok(files
+                .iter()
+                .find(|f| f.file_hash == file_hash && f.original_id.is_none())
+                .cloned())
+        }
+
+        async fn create(&self, _new_file: &CreateFile) -> RepositoryResult<File> {
+            unimplemented!()
+        }
+
+        async fn load(&self, _name: &str) -> RepositoryResult<Option<FileContent>> {
+            unimplemented!()
+        }
+
+        async fn load_variant(
+            &self,
+            _name: &str,
+            _variant: &ImageVariant,
+            _include_private: bool,
+        ) -> RepositoryResult<Option<FileContent>> {
+            unimplemented!()
+        }
+
+        async fn open(
+            &self,
+            _content: &FileContent,
+            _range: Range<u64>,
+        ) -> RepositoryResult<Option<ByteStream>> {
+            unimplemented!()
+        }
+
+        async fn list(&self, _params: FileQueryParams) -> RepositoryResult<ResultPaging<File>> {
+            unimplemented!()
+        }
+
+        async fn get(&self, file_id: i32) -> RepositoryResult<Option<File>> {
+            let files = self.files.lock().unwrap();
+            Ok(files.iter().find(|f| f.id == file_id).cloned())
+        }
+
+        async fn list_variants(&self, _original_id: i32) -> RepositoryResult<Vec<File>> {
+            unimplemented!()
+        }
+
+        async fn find_references(&self, _file: &File) -> RepositoryResult<Vec<FileReference>> {
+            unimplemented!()
+        }
+
+        async fn find_orphans(
+            &self,
+            _uploaded_before: NaiveDateTime,
+        ) -> RepositoryResult<Vec<File>> {
+            unimplemented!()
+        }
+
+        async fn delete(&self, _file_id: i32) -> RepositoryResult<bool> {
+            unimplemented!()
+        }
+
+        async fn list_all(&self) -> RepositoryResult<Vec<File>> {
+            Ok(self.files.lock().unwrap().clone())
+        }
+
+        async fn read(&self, file: &File) -> RepositoryResult<Option<ByteStream>> {
+            Ok(self.contents.get(&file.storage_key).map(|content| {
+                let chunk: RepositoryResult<Bytes> = Ok(Bytes::from(content.clone()));
+                Box::pin(stream::iter([chunk])) as ByteStream
+            }))
+        }
+
+        // 与数据库的唯一索引一致：原始文件的hash不能重复
+        async fn update_hash(
+            &self,
+            file_id: i32,
+            file_hash: &str,
+            hash_algorithm: &str,
+        ) -> RepositoryResult<()> {
+            let mut files = self.files.lock().unwrap();
+            if files
+                .iter()
+                .any(|f| f.id != file_id && f.file_hash == file_hash && f.original_id.is_none())
+            {
+                return Err(RepositoryError::DatabaseError(
+                    "duplicate key value violates unique constraint \"idx_files_hash\"".to_string(),
+                ));
+            }
+            let file = files.iter_mut().find(|f| f.id == file_id).unwrap();
+            file.file_hash = file_hash.to_string();
+            file.hash_algorithm = hash_algorithm.to_string();
+            Ok(())
+        }
+
+        async fn update_visibility(
+            &self,
+            _file_id: i32,
+            _visibility: FileVisibility,
+        ) -> RepositoryResult<Option<File>> {
+            unimplemented!()
+        }
+    }
+
+    #[derive(Default)]
+    struct MemorySessions(Mutex<HashMap<String, UploadSession>>);
+
+    #[async_trait]
+    impl RedisRepository<UploadSession> for MemorySessions {
+        async fn get(&self, key: &str) -> RepositoryResult<Option<UploadSession>> {
+            Ok(self.0.lock().unwrap().get(key).cloned())
+        }
+        async fn set(&self, key: &str, value: UploadSession) -> RepositoryResult<()> {
+            self.0.lock().unwrap().insert(key.to_string(), value);
+            Ok(())
+        }
+        async fn set_with_ttl(
+            &self,
+            key: &str,
+            value: UploadSession,
+            _ttl: Duration,
+        ) -> RepositoryResult<()> {
+            self.set(key, value).await
+        }
+        async fn delete(&self, key: &str) -> RepositoryResult<()> {
+            self.0.lock().unwrap().remove(key);
+            Ok(())
+        }
+        async fn exists(&self, key: &str) -> RepositoryResult<bool> {
+            Ok(self.0.lock().unwrap().contains_key(key))
+        }
+        async fn set_ex(
+            &self,
+            key: &str,
+            value: UploadSession,
+            _seconds: u64,
+        ) -> RepositoryResult<()> {
+            self.set(key, value).await
+        }
+    }
+
+    struct NoCache;
+
+    #[async_trait]
+    impl CacheService for NoCache {
+        async fn lookup(&self, _key: &CacheKey) -> CacheLookup {
+            CacheLookup {
+                entry: None,
+                ttl: Duration::ZERO,
+                body: None,
+            }
+        }
+        async fn store(&self, _lookup: &CacheLookup, _body: String) {}
+        async fn evict(&self, _groups: &[CacheGroup]) {}
+    }
+
+    fn service(repository: Arc<MemoryFiles>) -> FileServiceImpl {
+        service_with(repository, Uploads::default())
+    }
+
+    fn service_with(repository: Arc<MemoryFiles>, uploads: Uploads) -> FileServiceImpl {
+        FileServiceImpl::new(
+            repository,
+            Arc::new(MemorySessions::default()),
+            Uploads {
+                signing_secret: Some("test".to_string()),
+                ..uploads
+            },
+            Arc::new(NoCache),
+        )
+    }
+
+    fn file(id: i32, content: &[u8], hash_algorithm: &str) -> File {
+        let file_hash = match hash_algorithm {
+            MD5 => format!("{:x}", md5::compute(content)),
+            _ => format!("{:x}", Sha256::digest(content)),
+        };
+        File {
+            id,
+            file_path: format!("/static/{}.png", id),
+            storage_key: format!("{}.png", id),
+            file_hash,
+            upload_time: NaiveDateTime::default(),
+            created_at: NaiveDateTime::default(),
+            updated_at: NaiveDateTime::default(),
+            original_id: None,
+            variant: None,
+            width: None,
+            height: None,
+            mime_type: Some("image/png".to_string()),
+            byte_size: Some(content.len() as i64),
+            original_filename: None,
+            hash_algorithm: hash_algorithm.to_string(),
+            visibility: FileVisibility::Public,
+        }
+    }
+
+    fn algorithm(repository: &MemoryFiles, file_id: i32) -> String {
+        let files = repository.files.lock().unwrap();
+        let file = files.iter().find(|f| f.id == file_id).unwrap();
+        file.hash_algorithm.clone()
+    }
+
+    #[actix_web::test]
+    async fn backfill_skips_missing_and_duplicate_files() {
+        // 1 在迁移后以 SHA-256 重新上传为 2；3 的文件已丢失；4 可以正常回填
+        let files = vec![
+            file(1, b"same", MD5),
+            file(2, b"same", SHA256),
+            file(3, b"missing", MD5),
+            file(4, b"converted", MD5),
+        ];
+        let contents = [(1, &b"same"[..]), (2, b"same"), (4, b"converted")]
+            .into_iter()
+            .map(|(id, content)| (format!("{}.png", id), content.to_vec()))
+            .collect();
+        let repository = Arc::new(MemoryFiles {
+            files: Mutex::new(files),
+            contents,
+        });
+
+        let count = service(repository.clone())
+            .backfill_hashes(&|| false)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(algorithm(&repository, 1), MD5);
+        assert_eq!(algorithm(&repository, 3), MD5);
+        assert_eq!(algorithm(&repository, 4), SHA256);
+        let converted = repository.get(4).await.unwrap().unwrap();
+        assert_eq!(converted.file_hash, file(4, b"converted", SHA256).file_hash);
+    }
+
+    #[actix_web::test]
+    async fn backfill_stops_on_shutdown() {
+        let repository = Arc::new(MemoryFiles {
+            files: Mutex::new(vec![file(1, b"content", MD5)]),
+            contents: HashMap::from([("1.png".to_string(), b"content".to_vec())]),
+        });
+        let count = service(repository.clone())
+            .backfill_hashes(&|| true)
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+        assert_eq!(algorithm(&repository, 1), MD5);
+    }
+
+    // PNG 文件头，足够让 infer 识别类型
+    const PNG_HEADER: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+    async fn detect(content: &[u8], file_name: &str, byte_size: i64) -> Result<String, String> {
+        let path = std::env::temp_dir().join(format!(
+            "zwj_blog_detect_{}_{}",
+            std::process::id(),
+            file_name
+        ));
+        std::fs::write(&path, content).unwrap();
+        let result = service(Arc::new(MemoryFiles::default()))
+            .detect(
+                &path,
+                file_name.to_string(),
+                byte_size,
+                UploadOptions::default(),
+            )
+            .await;
+        std::fs::remove_file(&path).unwrap();
+        result.map(|metadata| metadata.mime_type).map_err(|e| {
+            assert_eq!(e.code, 400);
+            e.message
+        })
+    }
+
+    #[actix_web::test]
+    async fn detect_uses_sniffed_type() {
+        assert_eq!(
+            detect(PNG_HEADER, "image.png", 16).await.unwrap(),
+            "image/png"
+        );
+        // 没有扩展名时以内容类型为准
+        assert_eq!(detect(PNG_HEADER, "image", 16).await.unwrap(), "image/png");
+    }
+
+    #[actix_web::test]
+    async fn detect_rejects_mismatch_and_oversized_files() {
+        let mismatch = detect(PNG_HEADER, "image.jpg", 16).await.unwrap_err();
+        assert!(
+            mismatch.contains("扩展名与内容类型 image/png 不符"),
+            "{}",
+            mismatch
+        );
+
+        let unsupported = detect(b"#!/bin/sh\necho hi\n", "image.png", 16)
+            .await
+            .unwrap_err();
+        assert!(unsupported.contains("不支持的文件类型"), "{}", unsupported);
+
+        let oversized = detect(PNG_HEADER, "image.png", 10 * 1024 * 1024 + 1)
+            .await
+            .unwrap_err();
+        assert!(oversized.contains("超过大小限制"), "{}", oversized);
+    }
+
+    #[actix_web::test]
+    async fn chunks_must_follow_received_offset() {
+        let chunk_dir =
+            std::env::temp_dir().join(format!("zwj_blog_chunks_{}", std::process::id()));
+        let service = service_with(
+            Arc::new(MemoryFiles::default()),
+            Uploads {
+                chunk_dir: chunk_dir.to_string_lossy().to_string(),
+                ..Uploads::default()
+            },
+        );
+        let session = service
+            .init_upload("image.png".to_string(), 8, UploadOptions::default())
+            .await
+            .unwrap();
+        let upload_id = session.upload_id.as_str();
+
+        let skipped = service
+            .append_chunk(upload_id, 4, b"abcd")
+            .await
+            .unwrap_err();
+        assert_eq!(skipped.code, 409);
+        let session = service
+            .append_chunk(upload_id, 0, b"abcd")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(session.received, 4);
+        // 重复发送已接收的分片同样视为偏移量不匹配
+        let repeated = service
+            .append_chunk(upload_id, 0, b"abcd")
+            .await
+            .unwrap_err();
+        assert_eq!(repeated.code, 409);
+        let overflow = service
+            .append_chunk(upload_id, 4, b"efghi")
+            .await
+            .unwrap_err();
+        assert_eq!(overflow.code, 400);
+
+        let incomplete = service.complete_upload(upload_id).await.unwrap_err();
+        assert_eq!(incomplete.code, 400);
+        assert_eq!(
+            service
+                .get_upload(upload_id)
+                .await
+                .unwrap()
+                .unwrap()
+                .received,
+            4
+        );
+        assert_eq!(
+            std::fs::read(chunk_dir.join(format!("{}.part", upload_id))).unwrap(),
+            b"abcd"
+        );
+        std::fs::remove_dir_all(&chunk_dir).unwrap();
+    }
+
+    #[actix_web::test]
+    async fn unknown_or_malformed_upload_ids_are_not_found() {
+        let service = service(Arc::new(MemoryFiles::default()));
+        for upload_id in ["", "missing", "../config", "a/b"] {
+            assert!(service.get_upload(upload_id).await.unwrap().is_none());
+            assert!(service
+                .append_chunk(upload_id, 0, b"abcd")
+                .await
+                .unwrap()
+                .is_none());
+            assert!(service.complete_upload(upload_id).await.unwrap().is_none());
+        }
+        // 无效的 upload_id 不会留下hash状态
+        assert!(service.partials.lock().unwrap().is_empty());
+    }
 }
@@ -3,32 +3,40 @@ use std::sync::Arc;
 
 use crate::domain::{
     error::CommonError,
-    models::note::{CreateNote, Note, UpdateNote},
+    models::{
+        cache::CacheGroup,
+        note::{CreateNote, Note, UpdateNote},
+        version::VersionedUpdate,
+    },
     repositories::{
         note::{NoteQueryParams, NoteRepository},
         repository::ResultPaging,
     },
-    services::note::NoteService,
+    services::{cache::CacheService, note::NoteService},
 };
 
 #[derive(Clone)]
 pub struct NoteServiceImpl {
     pub repository: Arc<dyn NoteRepository>,
+    pub cache: Arc<dyn CacheService>,
 }
 
 impl NoteServiceImpl {
-    pub fn new(repository: Arc<dyn NoteRepository>) -> Self {
-        NoteServiceImpl { repository }
+    pub fn new(repository: Arc<dyn NoteRepository>, cache: Arc<dyn CacheService>) -> Self {
+        NoteServiceImpl { repository, cache }
     }
 }
 
 #[async_trait]
 impl NoteService for NoteServiceImpl {
     async fn create(&self, note: CreateNote) -> Result<Note, CommonError> {
-        self.repository
+        let result = self
+            .repository
             .create(&note)
             .await
-            .map_err(|e| -> CommonError { e.into() })
+            .map_err(|e| -> CommonError { e.into() })?;
+        self.cache.evict(&[CacheGroup::Notes]).await;
+        Ok(result)
     }
 
     async fn list(&self, params: NoteQueryParams) -> Result<ResultPaging<Note>, CommonError> {
@@ -56,18 +64,26 @@ impl NoteService for NoteServiceImpl {
         &self,
         note_id: i32,
         update_note: UpdateNote,
-    ) -> Result<Option<Note>, CommonError> {
-        self.repository
+    ) -> Result<VersionedUpdate<Note>, CommonError> {
+        let result = self
+            .repository
             .update(note_id, &update_note)
             .await
-            .map_err(|e| -> CommonError { e.into() })
+            .map_err(|e| -> CommonError { e.into() })?;
+        if let VersionedUpdate::Updated(_) = &result {
+            self.cache.evict(&[CacheGroup::Notes]).await;
+        }
+        Ok(result)
     }
 
     async fn delete(&self, note_id: i32) -> Result<bool, CommonError> {
-        self.repository
+        let result = self
+            .repository
             .delete(note_id)
             .await
-            .map_err(|e| -> CommonError { e.into() })
+            .map_err(|e| -> CommonError { e.into() })?;
+        self.cache.evict(&[CacheGroup::Notes]).await;
+        Ok(result)
     }
 
     async fn exists(&self, note_id: i32) -> Result<bool, CommonError> {
@@ -78,9 +94,12 @@ impl NoteService for NoteServiceImpl {
     }
 
     async fn toggle_published(&self, note_id: i32) -> Result<Option<Note>, CommonError> {
-        self.repository
+        let result = self
+            .repository
             .toggle_published(note_id)
             .await
-            .map_err(|e| -> CommonError { e.into() })
+            .map_err(|e| -> CommonError { e.into() })?;
+        self.cache.evict(&[CacheGroup::Notes]).await;
+        Ok(result)
     }
 }
@@ -3,32 +3,59 @@ use std::sync::Arc;
 
 use crate::domain::{
     error::CommonError,
-    models::snippet::{CreateSnippet, Snippet, UpdateSnippet},
+    models::{
+        cache::CacheGroup,
+        snippet::{CreateSnippet, Snippet, UpdateSnippet},
+        version::VersionedUpdate,
+    },
     repositories::{
         repository::ResultPaging,
         snippet::{SnippetQueryParams, SnippetRepository},
     },
-    services::snippet::SnippetService,
+    services::{cache::CacheService, snippet::SnippetService},
+    slug::create_with_unique_slug,
 };
 
 #[derive(Clone)]
 pub struct SnippetServiceImpl {
     pub repository: Arc<dyn SnippetRepository>,
+    pub cache: Arc<dyn CacheService>,
 }
 
 impl SnippetServiceImpl {
-    pub fn new(repository: Arc<dyn SnippetRepository>) -> Self {
-        SnippetServiceImpl { repository }
+    pub fn new(repository: Arc<dyn SnippetRepository>, cache: Arc<dyn CacheService>) -> Self {
+        SnippetServiceImpl { repository, cache }
     }
 }
 
 #[async_trait]
 impl SnippetService for SnippetServiceImpl {
     async fn create(&self, snippet: CreateSnippet) -> Result<Snippet, CommonError> {
-        self.repository
-            .create(&snippet)
+        // 未提供 slug 时根据标题生成
+        let result = if snippet.slug.trim().is_empty() {
+            let repository = self.repository.clone();
+            create_with_unique_slug(
+                &snippet.title,
+                |candidate| {
+                    let repository = repository.clone();
+                    async move { repository.slug_exists(&candidate).await }
+                },
+                |slug| {
+                    let repository = repository.clone();
+                    let snippet = CreateSnippet {
+                        slug,
+                        ..snippet.clone()
+                    };
+                    async move { repository.create(&snippet).await }
+                },
+            )
             .await
-            .map_err(|e| -> CommonError { e.into() })
+        } else {
+            self.repository.create(&snippet).await
+        }
+        .map_err(|e| -> CommonError { e.into() })?;
+        self.cache.evict(&[CacheGroup::Snippets]).await;
+        Ok(result)
     }
 
     async fn list(&self, params: SnippetQueryParams) -> Result<ResultPaging<Snippet>, CommonError> {
@@ -52,6 +79,13 @@ impl SnippetService for SnippetServiceImpl {
             .map_err(|e| -> CommonError { e.into() })
     }
 
+    async fn find_redirect(&self, slug: &str) -> Result<Option<String>, CommonError> {
+        self.repository
+            .find_redirect(slug)
+            .await
+            .map_err(|e| -> CommonError { e.into() })
+    }
+
     async fn get_published(&self) -> Result<Vec<Snippet>, CommonError> {
         self.repository
             .get_published()
@@ -63,18 +97,26 @@ impl SnippetService for SnippetServiceImpl {
         &self,
         snippet_id: i32,
         update_snippet: UpdateSnippet,
-    ) -> Result<Option<Snippet>, CommonError> {
-        self.repository
+    ) -> Result<VersionedUpdate<Snippet>, CommonError> {
+        let result = self
+            .repository
             .update(snippet_id, &update_snippet)
             .await
-            .map_err(|e| -> CommonError { e.into() })
+            .map_err(|e| -> CommonError { e.into() })?;
+        if let VersionedUpdate::Updated(_) = &result {
+            self.cache.evict(&[CacheGroup::Snippets]).await;
+        }
+        Ok(result)
     }
 
     async fn delete(&self, snippet_id: i32) -> Result<bool, CommonError> {
-        self.repository
+        let result = self
+            .repository
             .delete(snippet_id)
             .await
-            .map_err(|e| -> CommonError { e.into() })
+            .map_err(|e| -> CommonError { e.into() })?;
+        self.cache.evict(&[CacheGroup::Snippets]).await;
+        Ok(result)
     }
 
     async fn exists(&self, snippet_id: i32) -> Result<bool, CommonError> {
@@ -85,9 +127,12 @@ impl SnippetService for SnippetServiceImpl {
     }
 
     async fn toggle_published(&self, snippet_id: i32) -> Result<Option<Snippet>, CommonError> {
-        self.repository
+        let result = self
+            .repository
             .toggle_published(snippet_id)
             .await
-            .map_err(|e| -> CommonError { e.into() })
+            .map_err(|e| -> CommonError { e.into() })?;
+        self.cache.evict(&[CacheGroup::Snippets]).await;
+        Ok(result)
     }
 }
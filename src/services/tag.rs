@@ -3,33 +3,59 @@ use std::sync::Arc;
 
 use crate::domain::{
     error::CommonError,
-    models::tag::{CreateTag, Tag, TagType},
+    models::{
+        cache::CacheGroup,
+        tag::{CreateTag, Tag, TagType},
+        version::VersionedUpdate,
+    },
     repositories::{
         repository::ResultPaging,
         tag::{TagQueryParams, TagRepository},
     },
-    services::tag::TagService,
+    services::{cache::CacheService, tag::TagService},
+    slug::create_with_unique_slug,
 };
 
 #[derive(Clone)]
 pub struct TagServiceImpl {
     pub repository: Arc<dyn TagRepository>,
+    pub cache: Arc<dyn CacheService>,
 }
 
 impl TagServiceImpl {
-    pub fn new(repository: Arc<dyn TagRepository>) -> Self {
-        TagServiceImpl { repository }
+    pub fn new(repository: Arc<dyn TagRepository>, cache: Arc<dyn CacheService>) -> Self {
+        TagServiceImpl { repository, cache }
     }
 }
 
 #[async_trait]
 impl TagService for TagServiceImpl {
-    /// 创建标签
+    /// 创建标签，未提供 slug 时根据名称生成
     async fn create(&self, tag: CreateTag) -> Result<Tag, CommonError> {
-        self.repository
-            .create(&tag)
+        let result = if tag.slug.trim().is_empty() {
+            let repository = self.repository.clone();
+            create_with_unique_slug(
+                &tag.name,
+                |candidate| {
+                    let repository = repository.clone();
+                    async move { repository.slug_exists(&candidate).await }
+                },
+                |slug| {
+                    let repository = repository.clone();
+                    let tag = CreateTag {
+                        slug,
+                        ..tag.clone()
+                    };
+                    async move { repository.create(&tag).await }
+                },
+            )
             .await
-            .map_err(|e| -> CommonError { e.into() })
+        } else {
+            self.repository.create(&tag).await
+        }
+        .map_err(|e| -> CommonError { e.into() })?;
+        self.cache.evict(&[CacheGroup::Tags]).await;
+        Ok(result)
     }
 
     async fn list(&self, params: TagQueryParams) -> Result<ResultPaging<Tag>, CommonError> {
@@ -47,10 +73,13 @@ impl TagService for TagServiceImpl {
     }
 
     async fn delete(&self, tag_id: i32) -> Result<bool, CommonError> {
-        self.repository
+        let result = self
+            .repository
             .delete(tag_id)
             .await
-            .map_err(|e| -> CommonError { e.into() })
+            .map_err(|e| -> CommonError { e.into() })?;
+        self.cache.evict(&[CacheGroup::Tags]).await;
+        Ok(result)
     }
 
     async fn exists(&self, tag_id: i32) -> Result<bool, CommonError> {
@@ -64,11 +93,16 @@ impl TagService for TagServiceImpl {
         &self,
         tag_id: i32,
         update_tag: crate::domain::models::tag::UpdateTag,
-    ) -> Result<Option<Tag>, CommonError> {
-        self.repository
+    ) -> Result<VersionedUpdate<Tag>, CommonError> {
+        let result = self
+            .repository
             .update(tag_id, &update_tag)
             .await
-            .map_err(|e| -> CommonError { e.into() })
+            .map_err(|e| -> CommonError { e.into() })?;
+        if let VersionedUpdate::Updated(_) = &result {
+            self.cache.evict(&[CacheGroup::Tags]).await;
+        }
+        Ok(result)
     }
 
     async fn get_all(&self, tag_type: Option<TagType>) -> Result<Vec<Tag>, CommonError> {
@@ -6,12 +6,14 @@ use solana_sdk::{pubkey::Pubkey, signature::Signature};
 use tokio::fs;
 
 use crate::{
-    constants::nonce::REDIS_NONCE_KEY,
+    constants::{jwt::JWT_KEY_FILE, nonce::REDIS_NONCE_KEY},
     domain::{
-        error::CommonError,
+        error::{CommonError, RepositoryError},
+        models::user::{AdminWallet, AdminWalletSource},
         repositories::{redis::RedisRepository, user::UserRepository},
         services::user::{AuthResponse, UserService},
     },
+    metrics::AUTH_ATTEMPTS_TOTAL,
 };
 
 #[derive(Clone)]
@@ -36,12 +38,10 @@ impl UserServiceImpl {
 
     /// 获取或创建JWT密钥
     async fn get_or_create_jwt_key(&self) -> Result<HS256Key, CommonError> {
-        let key_file_path = "./jwt_key.bin";
-
         // 检查密钥文件是否存在
-        if fs::metadata(key_file_path).await.is_ok() {
+        if fs::metadata(JWT_KEY_FILE).await.is_ok() {
             // 密钥文件存在，读取并加载
-            let key_bytes = fs::read(key_file_path).await.map_err(|e| {
+            let key_bytes = fs::read(JWT_KEY_FILE).await.map_err(|e| {
                 CommonError::from(format!("Failed to read JWT key file: {}", e).as_str())
             })?;
 
@@ -53,48 +53,27 @@ impl UserServiceImpl {
             let key = HS256Key::generate();
             let key_bytes = key.to_bytes();
 
-            fs::write(key_file_path, &key_bytes).await.map_err(|e| {
+            fs::write(JWT_KEY_FILE, &key_bytes).await.map_err(|e| {
                 CommonError::from(format!("Failed to save JWT key file: {}", e).as_str())
             })?;
 
             Ok(key)
         }
     }
-}
-
-#[async_trait]
-impl UserService for UserServiceImpl {
-    async fn get_nonce(&self, address: String) -> Result<String, CommonError> {
-        Pubkey::from_str(&address).map_err(|e| CommonError::from(e.to_string().as_str()))?;
 
-        //这里要检查Redis 如果存在，就返回，不存在就生成并存储
-        let nonce = self
-            .redis_repository
-            .get(&format!("{}{}", REDIS_NONCE_KEY, address))
-            .await
-            .map_err(|e| -> CommonError { e.into() })?;
-        match nonce {
-            Some(existing_nonce) => return Ok(existing_nonce),
-            None => {
-                let nonce = self
-                    .repository
-                    .gen_nonce()
-                    .await
-                    .map_err(|e| -> CommonError { e.into() })?;
-                self.redis_repository
-                    .set_with_ttl(
-                        &format!("{}{}", REDIS_NONCE_KEY, address),
-                        nonce.clone(),
-                        Duration::from_secs(60),
-                    )
-                    .await
-                    .map_err(|e| -> CommonError { e.into() })?;
-                Ok(nonce)
-            }
+    // 配置文件或数据库白名单中的地址可以登录
+    async fn is_admin_wallet(&self, address: &str) -> Result<bool, CommonError> {
+        if self.auth.iter().any(|a| a == address) {
+            return Ok(true);
         }
+        self.repository
+            .is_admin_wallet(address)
+            .await
+            .map_err(|e| -> CommonError { e.into() })
     }
 
-    async fn verify_signature(
+    // 校验签名并签发JWT，结果由 verify_signature 计入认证指标
+    async fn authenticate(
         &self,
         address: String,
         signature: String,
@@ -122,7 +101,7 @@ impl UserService for UserServiceImpl {
         }
 
         //检查地址是否在白名单中
-        if !self.auth.contains(&address) {
+        if !self.is_admin_wallet(&address).await? {
             return Err(CommonError::from("Address not in whitelist"));
         }
 
@@ -147,3 +126,110 @@ impl UserService for UserServiceImpl {
         Ok(AuthResponse { token, address })
     }
 }
+
+#[async_trait]
+impl UserService for UserServiceImpl {
+    async fn get_nonce(&self, address: String) -> Result<String, CommonError> {
+        Pubkey::from_str(&address).map_err(|e| CommonError::from(e.to_string().as_str()))?;
+
+        //这里要检查Redis 如果存在，就返回，不存在就生成并存储
+        let nonce = self
+            .redis_repository
+            .get(&format!("{}{}", REDIS_NONCE_KEY, address))
+            .await
+            .map_err(|e| -> CommonError { e.into() })?;
+        match nonce {
+            Some(existing_nonce) => return Ok(existing_nonce),
+            None => {
+                let nonce = self
+                    .repository
+                    .gen_nonce()
+                    .await
+                    .map_err(|e| -> CommonError { e.into() })?;
+                self.redis_repository
+                    .set_with_ttl(
+                        &format!("{}{}", REDIS_NONCE_KEY, address),
+                        nonce.clone(),
+                        Duration::from_secs(60),
+                    )
+                    .await
+                    .map_err(|e| -> CommonError { e.into() })?;
+                Ok(nonce)
+            }
+        }
+    }
+
+    async fn verify_signature(
+        &self,
+        address: String,
+        signature: String,
+    ) -> Result<AuthResponse, CommonError> {
+        let result = self.authenticate(address, signature).await;
+        let outcome = if result.is_ok() { "success" } else { "failure" };
+        AUTH_ATTEMPTS_TOTAL.with_label_values(&[outcome]).inc();
+        result
+    }
+
+    async fn list_admin_wallets(&self) -> Result<Vec<AdminWallet>, CommonError> {
+        let mut wallets: Vec<AdminWallet> = self
+            .auth
+            .iter()
+            .map(|address| AdminWallet {
+                address: address.clone(),
+                source: AdminWalletSource::Config,
+                created_at: None,
+            })
+            .collect();
+        let stored = self
+            .repository
+            .list_admin_wallets()
+            .await
+            .map_err(|e| -> CommonError { e.into() })?;
+        wallets.extend(
+            stored
+                .into_iter()
+                .filter(|wallet| !self.auth.contains(&wallet.address)),
+        );
+        Ok(wallets)
+    }
+
+    async fn add_admin_wallet(&self, address: String) -> Result<bool, CommonError> {
+        Pubkey::from_str(&address).map_err(|e| -> CommonError {
+            RepositoryError::ValidationError(e.to_string()).into()
+        })?;
+        if self.auth.contains(&address) {
+            return Ok(false);
+        }
+        self.repository
+            .add_admin_wallet(&address)
+            .await
+            .map_err(|e| -> CommonError { e.into() })
+    }
+
+    async fn remove_admin_wallet(&self, address: String) -> Result<bool, CommonError> {
+        if self.auth.contains(&address) {
+            return Err(RepositoryError::ValidationError(format!(
+                "{} 配置在 auth.address 中，需要修改配置文件",
+                address
+            ))
+            .into());
+        }
+        self.repository
+            .remove_admin_wallet(&address)
+            .await
+            .map_err(|e| -> CommonError { e.into() })
+    }
+
+    async fn rotate_jwt_key(&self) -> Result<(), CommonError> {
+        // 先写入临时文件再替换，验证token时不会读到写了一半的密钥
+        let key = HS256Key::generate();
+        let temp_path = format!("{}.tmp", JWT_KEY_FILE);
+        fs::write(&temp_path, key.to_bytes()).await.map_err(|e| {
+            CommonError::from(format!("Failed to save JWT key file: {}", e).as_str())
+        })?;
+        fs::rename(&temp_path, JWT_KEY_FILE).await.map_err(|e| {
+            CommonError::from(format!("Failed to replace JWT key file: {}", e).as_str())
+        })?;
+        Ok(())
+    }
+}
@@ -37,13 +37,17 @@ mod test_todo_controllers {
                 host: "127.0.0.1".to_string(),
                 port: 8080,
                 workers: 1,
+                shutdown_grace_secs: 30,
+                metrics_allow_ips: vec![],
             },
             database: zwj_blog::config::Database {
                 url: connection_string.clone(),
+                run_migrations: false,
             },
             logging: zwj_blog::config::Logging {
                 level: "debug".to_string(),
                 format: "pretty".to_string(),
+                otel: None,
             },
             redis: zwj_blog::config::Redis {
                 username: "".to_string(),
@@ -55,6 +59,10 @@ mod test_todo_controllers {
             auth: zwj_blog::config::Auth {
                 address: vec!["BMVsMz7reBwBLu1xRf48Xdnrrx5LYi68FDV8XEjW99PF".to_string()],
             },
+            storage: Default::default(),
+            images: Default::default(),
+            uploads: Default::default(),
+            cache: Default::default(),
         };
 
         {
@@ -0,0 +1,2 @@
+pub mod test_blog_repository;
+pub mod test_s3_storage;
@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod test_s3_storage {
+    use futures_util::TryStreamExt;
+    use testcontainers::{clients, images::minio, RunnableImage};
+    use zwj_blog::config::S3Storage;
+    use zwj_blog::domain::repositories::storage::StorageBackend;
+    use zwj_blog::infrastructure::storage::s3::S3StorageBackend;
+
+    const BUCKET: &str = "zwj-blog";
+
+    #[actix_web::test]
+    async fn test() {
+        // MinIO 单盘模式下数据目录中的子目录就是存储桶
+        let data = std::env::temp_dir().join(format!("zwj_blog_minio_{}", std::process::id()));
+        std::fs::create_dir_all(data.join(BUCKET)).unwrap();
+
+        let docker = clients::Cli::default();
+        let image = RunnableImage::from(minio::MinIO::default())
+            .with_volume((data.to_string_lossy().to_string(), "/data"));
+        let minio_node = docker.run(image);
+        let endpoint = format!("http://127.0.0.1:{}", minio_node.get_host_port_ipv4(9000));
+
+        let storage = S3StorageBackend::new(&S3Storage {
+            bucket: BUCKET.to_string(),
+            region: "us-east-1".to_string(),
+            endpoint: Some(endpoint),
+            access_key_id: "minioadmin".to_string(),
+            secret_access_key: "minioadmin".to_string(),
+            public_url: "https://cdn.example.com/".to_string(),
+            allow_http: true,
+        })
+        .unwrap();
+
+        // 上传本地文件
+        let source = data.join("source.txt");
+        std::fs::write(&source, b"hello minio").unwrap();
+        storage.put_file("a.txt", &source).await.unwrap();
+        assert_eq!(storage.get("a.txt").await.unwrap().unwrap(), b"hello minio");
+        assert_eq!(storage.size("a.txt").await.unwrap(), Some(11));
+        assert!(storage.exists("a.txt").await.unwrap());
+
+        // 区间读取
+        let chunks: Vec<_> = storage
+            .get_range("a.txt", 6..11)
+            .await
+            .unwrap()
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(chunks.concat(), b"minio");
+
+        // 写入字节
+        storage.put_bytes("b.txt", b"bytes".to_vec()).await.unwrap();
+        assert_eq!(storage.get("b.txt").await.unwrap().unwrap(), b"bytes");
+
+        // 不存在的对象
+        assert_eq!(storage.get("missing.txt").await.unwrap(), None);
+        assert_eq!(storage.size("missing.txt").await.unwrap(), None);
+        assert!(storage
+            .get_range("missing.txt", 0..1)
+            .await
+            .unwrap()
+            .is_none());
+
+        // 删除
+        assert!(storage.delete("a.txt").await.unwrap());
+        assert!(!storage.delete("a.txt").await.unwrap());
+        assert!(!storage.exists("a.txt").await.unwrap());
+
+        assert_eq!(storage.url("b.txt"), "https://cdn.example.com/b.txt");
+
+        let _ = std::fs::remove_dir_all(&data);
+    }
+}